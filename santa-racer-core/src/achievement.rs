@@ -0,0 +1,140 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use serde::Serialize;
+use serde::Deserialize;
+
+use crate::*;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Achievement {
+  FirstDelivery,
+  BonusDelivery,
+  TopFloorDelivery,
+  AllPowerUps,
+  Finished,
+  FlawlessFinish,
+}
+
+pub struct Achievements {
+  unlocked: Vec<Achievement>,
+  newly_unlocked: Vec<Achievement>,
+  collected_power_ups: std::collections::HashSet<npc::BalloonType>,
+  damage_taken: bool,
+
+  number_of_power_up_types: usize,
+}
+
+impl Achievements {
+  pub fn new() -> Achievements {
+    return Achievements{
+      unlocked: Vec::new(),
+      newly_unlocked: Vec::new(),
+      collected_power_ups: std::collections::HashSet::new(),
+      damage_taken: false,
+
+      number_of_power_up_types: 5,
+    };
+  }
+
+  // unlocked achievements are kept, only the per-run progress is reset
+  pub fn start_game(&mut self) {
+    self.newly_unlocked.clear();
+    self.collected_power_ups.clear();
+    self.damage_taken = false;
+  }
+
+  pub fn handle_event(&mut self, event: &event::GameEvent) {
+    match *event {
+      event::GameEvent::GiftDelivered{chimney, bonus, ..} => {
+        self.unlock(Achievement::FirstDelivery);
+        if bonus { self.unlock(Achievement::BonusDelivery); }
+        if chimney.1 >= 3 { self.unlock(Achievement::TopFloorDelivery); }
+      },
+      event::GameEvent::GiftHitGround{..} | event::GameEvent::SleighHitTile{..}
+          | event::GameEvent::SleighHitNpc{..} => {
+        self.damage_taken = true;
+      },
+      event::GameEvent::PowerUpCollected{kind, ..} => {
+        self.collected_power_ups.insert(kind);
+
+        if self.collected_power_ups.len() == self.number_of_power_up_types {
+          self.unlock(Achievement::AllPowerUps);
+        }
+      },
+      event::GameEvent::Finished => {
+        self.unlock(Achievement::Finished);
+        if !self.damage_taken { self.unlock(Achievement::FlawlessFinish); }
+      },
+      _ => {},
+    }
+  }
+
+  fn unlock(&mut self, achievement: Achievement) {
+    if !self.unlocked.contains(&achievement) {
+      self.unlocked.push(achievement);
+      self.newly_unlocked.push(achievement);
+    }
+  }
+
+  // restores the achievements unlocked in previous runs, which are not unlocked again
+  pub fn set_unlocked(&mut self, unlocked: &[Achievement]) {
+    self.unlocked = unlocked.to_vec();
+  }
+
+  pub fn unlocked(&self) -> &Vec<Achievement> {
+    return &self.unlocked;
+  }
+
+  pub fn take_newly_unlocked(&mut self) -> Vec<Achievement> {
+    return std::mem::replace(&mut self.newly_unlocked, Vec::new());
+  }
+}
+
+impl std::fmt::Display for Achievement {
+  fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+    let description = match self {
+      Achievement::FirstDelivery => locale::Message::AchievementFirstDelivery,
+      Achievement::BonusDelivery => locale::Message::AchievementBonusDelivery,
+      Achievement::TopFloorDelivery => locale::Message::AchievementTopFloorDelivery,
+      Achievement::AllPowerUps => locale::Message::AchievementAllPowerUps,
+      Achievement::Finished => locale::Message::AchievementFinished,
+      Achievement::FlawlessFinish => locale::Message::AchievementFlawlessFinish,
+    };
+
+    return write!(formatter, "{}", description.text());
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn create_gift_delivered_event() -> event::GameEvent {
+    return event::GameEvent::GiftDelivered{chimney: (0, 0), points: 10.0, bonus: false,
+        position: asset::Point::new(0.0, 0.0)};
+  }
+
+  #[test]
+  fn unlocked_achievements_survive_json_round_trip() {
+    let mut achievements = Achievements::new();
+    achievements.handle_event(&create_gift_delivered_event());
+    achievements.handle_event(&event::GameEvent::Finished);
+    assert_eq!(achievements.take_newly_unlocked(), vec![Achievement::FirstDelivery,
+        Achievement::Finished, Achievement::FlawlessFinish]);
+
+    let json = serde_json::to_string(achievements.unlocked()).unwrap();
+    let unlocked: Vec<Achievement> = serde_json::from_str(&json).unwrap();
+    let mut restored_achievements = Achievements::new();
+    restored_achievements.set_unlocked(&unlocked);
+    assert_eq!(restored_achievements.unlocked(), achievements.unlocked());
+
+    restored_achievements.start_game();
+    restored_achievements.handle_event(&create_gift_delivered_event());
+    assert!(restored_achievements.take_newly_unlocked().is_empty());
+  }
+}
@@ -0,0 +1,776 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::*;
+
+pub struct AssetLibrary<'a> {
+  assets_dirs: Vec<std::path::PathBuf>,
+  image_library: SingleTypeAssetLibrary<Image>,
+  song_library: SingleTypeAssetLibrary<Box<dyn Song + 'a>>,
+  sound_library: SingleTypeAssetLibrary<Box<dyn Sound + 'a>>,
+  level_library: SingleTypeAssetLibrary<level_file::LevelFile>,
+}
+
+pub trait CloneAsI32Vector {
+  fn clone_as_i32(&self) -> Vec<i32>;
+}
+
+pub trait Song {
+  fn play(&self);
+  fn stop(&self);
+  fn pause(&self);
+  fn resume(&self);
+}
+
+pub trait Sound {
+  fn play_with_volume_and_pan(&self, volume: f64, pan: f64);
+
+  fn play(&self) {
+    self.play_with_volume_and_pan(1.0, 0.5);
+  }
+
+  fn play_with_level_position(&self, canvas_size: Point, level_offset_x: f64, position: Point) {
+    self.play_with_position(canvas_size, Point::new(position.x() - level_offset_x, position.y()));
+  }
+
+  fn play_with_position(&self, canvas_size: Point, position: Point) {
+    self.play_with_pan(position.x() / canvas_size.x());
+  }
+
+  fn play_with_pan(&self, pan: f64) {
+    self.play_with_volume_and_pan(1.0, pan);
+  }
+
+  fn play_with_volume(&self, volume: f64) {
+    self.play_with_volume_and_pan(volume, 0.5);
+  }
+}
+
+struct SingleTypeAssetLibrary<AssetType> {
+  map: std::collections::HashMap<String, AssetType>,
+}
+
+pub struct Image {
+  name: String,
+  file_path: Option<std::path::PathBuf>,
+  full_width: u32,
+  full_height: u32,
+  number_of_frames: (i32, i32),
+  mask: Vec<bool>,
+}
+
+const REQUIRED_IMAGE_NAMES: [&str; 39] = ["angel", "background", "bigStar", "cashBalloon",
+    "cloud", "damageScoreIcon", "drunkStar", "electrocutedReindeer", "electrocutedSleigh",
+    "finish", "font", "gift1", "gift2", "gift3", "giftBalloon", "giftScoreIcon", "goblin",
+    "goblinSnowball", "heartBalloon", "helpSplash1", "helpSplash2", "landscape", "level",
+    "lostDueToDamageSplash", "lostDueToTimeSplash", "points10", "points15", "points20",
+    "reindeer", "shield", "shieldBalloon", "sleigh", "smallDrunkStar", "smallStar", "snowman",
+    "star", "timeScoreIcon", "wineBalloon", "wonSplash"];
+
+const IMAGE_NUMBERS_OF_FRAMES: [(&str, (i32, i32)); 21] = [("angel", (13, 1)),
+    ("bigStar", (10, 1)), ("cashBalloon", (7, 1)), ("drunkStar", (17, 1)), ("font", (57, 1)),
+    ("gift1", (15, 1)), ("gift2", (15, 1)), ("gift3", (15, 1)), ("giftBalloon", (8, 1)),
+    ("goblin", (19, 1)), ("heartBalloon", (8, 1)), ("level", (8, 11)), ("reindeer", (14, 1)),
+    ("shield", (8, 1)), ("shieldBalloon", (8, 1)), ("sleigh", (14, 1)), ("snowman", (8, 1)),
+    ("smallStar", (17, 1)), ("smallDrunkStar", (17, 1)), ("star", (17, 1)),
+    ("wineBalloon", (8, 1))];
+
+// numbers of frames of the font image as extracted by older and current versions of
+// tools/extractAssets.py, the latter appends punctuation glyphs
+const FONT_NUMBERS_OF_FRAMES: [i32; 2] = [45, 57];
+
+const REQUIRED_LEVEL_NAMES: [&str; 1] = [level::DEFAULT_LEVEL_NAME];
+
+const REQUIRED_SONG_NAMES: [&str; 1] = ["music"];
+
+const REQUIRED_SOUND_NAMES: [&str; 16] = ["bell", "cashBalloon", "dog", "giftBalloon",
+    "giftCollidedWithChimney", "giftCollidedWithGround", "goblinThrowSnowball", "lost",
+    "shieldBalloon", "sleighCollidedWithCloud", "sleighCollidedWithLevelTile1",
+    "sleighCollidedWithLevelTile2", "sleighCollidedWithNpc", "snowmanLaunch", "wineBalloon",
+    "won"];
+
+struct SilentSong;
+
+struct SilentSound;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Point {
+  x: f64,
+  y: f64,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Rect {
+  x: i32,
+  y: i32,
+  width: u32,
+  height: u32,
+}
+
+impl<'a> AssetLibrary<'a> {
+  // assets are searched in the given order, i.e., earlier directories take precedence
+  pub fn new(assets_dirs: &[std::path::PathBuf], verbose: bool) -> AssetLibrary<'a> {
+    let mut image_library: SingleTypeAssetLibrary<Image> = SingleTypeAssetLibrary::new();
+    let mut level_library: SingleTypeAssetLibrary<level_file::LevelFile> =
+        SingleTypeAssetLibrary::new();
+
+    image_library.load_assets(&subdirs(assets_dirs, "images"), verbose);
+    level_library.load_assets(&subdirs(assets_dirs, "levels"), verbose);
+
+    return AssetLibrary{
+      assets_dirs: assets_dirs.to_vec(),
+      image_library: image_library,
+      song_library: SingleTypeAssetLibrary::new(),
+      sound_library: SingleTypeAssetLibrary::new(),
+      level_library: level_library,
+    };
+  }
+
+  // library of the given images and levels without any files, e.g., for tests that run without
+  // extracted assets
+  pub fn from_assets(images: Vec<Image>, levels: Vec<level_file::LevelFile>) -> AssetLibrary<'a> {
+    let mut image_library: SingleTypeAssetLibrary<Image> = SingleTypeAssetLibrary::new();
+    let mut level_library: SingleTypeAssetLibrary<level_file::LevelFile> =
+        SingleTypeAssetLibrary::new();
+
+    for image in images { image_library.map.insert(image.name().to_string(), image); }
+    for level in levels { level_library.map.insert(level.name().to_string(), level); }
+
+    return AssetLibrary{
+      assets_dirs: Vec::new(),
+      image_library: image_library,
+      song_library: SingleTypeAssetLibrary::new(),
+      sound_library: SingleTypeAssetLibrary::new(),
+      level_library: level_library,
+    };
+  }
+
+  pub fn load_songs<F>(&mut self, load_fn: F, verbose: bool)
+        where F: Fn(&std::path::Path) -> Box<dyn Song + 'a> {
+    self.song_library.load_assets_from_path(&subdirs(&self.assets_dirs, "songs"), "ogg",
+        load_fn, verbose);
+  }
+
+  pub fn load_sounds<F>(&mut self, load_fn: F, verbose: bool)
+        where F: Fn(&std::path::Path) -> Box<dyn Sound + 'a> {
+    self.sound_library.load_assets_from_path(&subdirs(&self.assets_dirs, "sounds"), "wav",
+        load_fn, verbose);
+  }
+
+  pub fn get_image<S: Into<String> + std::clone::Clone>(&self, name: S) -> &Image {
+    return self.image_library.get_asset(name.clone()).expect(
+        format!("Could not find image asset with name '{}'", name.into()).as_str());
+  }
+
+  // prefers a variant for the current language such as "helpSplash1.en" if it exists
+  pub fn get_localized_image(&self, name: &str) -> &Image {
+    let localized_name = format!("{}.{}", name, locale::language().code());
+    return self.image_library.get_asset(localized_name).unwrap_or_else(|| self.get_image(name));
+  }
+
+  pub fn get_level<S: Into<String> + std::clone::Clone>(&self,
+        name: S) -> &level_file::LevelFile {
+    return self.level_library.get_asset(name.clone()).expect(
+        format!("Could not find level asset with name '{}'", name.into()).as_str());
+  }
+
+  // names of all levels in the order of the campaign, i.e., sorted by name, where numbers at the
+  // end of names are compared by value ("level2" before "level10")
+  pub fn level_names(&self) -> Vec<String> {
+    let mut level_names: Vec<String> = self.level_library.map.keys().cloned().collect();
+    level_names.sort_by_key(|name| {
+      let prefix = name.trim_end_matches(|x: char| x.is_ascii_digit());
+      (prefix.to_string(), name[prefix.len() ..].parse::<u64>().unwrap_or(0), name.to_string())
+    });
+    return level_names;
+  }
+
+  pub fn get_song<S: Into<String>>(&self, name: S) -> &(dyn Song + 'a) {
+    return match self.song_library.get_asset(name) {
+      Some(song) => song.as_ref(),
+      None => &SilentSong,
+    };
+  }
+
+  pub fn get_sound<S: Into<String>>(&self, name: S) -> &(dyn Sound + 'a) {
+    return match self.sound_library.get_asset(name) {
+      Some(sound) => sound.as_ref(),
+      None => &SilentSound,
+    };
+  }
+
+  pub fn images(&self) -> impl Iterator<Item = &Image> {
+    return self.image_library.map.values();
+  }
+
+  // checks all assets without loading them into a library, returns the paths of checked assets
+  pub fn validate(assets_dirs: &[std::path::PathBuf])
+        -> Result<Vec<std::path::PathBuf>, Vec<String>> {
+    let mut errors: Vec<String> = Vec::new();
+    let mut checked_file_paths = Vec::new();
+
+    for (subdir_name, extension, required_names) in [
+          ("images", "png", &REQUIRED_IMAGE_NAMES[..]),
+          ("levels", "json", &REQUIRED_LEVEL_NAMES[..]),
+          ("songs", "ogg", &REQUIRED_SONG_NAMES[..]),
+          ("sounds", "wav", &REQUIRED_SOUND_NAMES[..])].iter() {
+      let dir_paths = subdirs(assets_dirs, subdir_name);
+      let file_paths = match find_files(&dir_paths, extension) {
+        Ok(file_paths) => file_paths,
+        Err(error) => {
+          errors.push(error);
+          continue;
+        },
+      };
+
+      for required_name in required_names.iter() {
+        if !file_paths.iter().any(|file_path| file_path.file_stem()
+              == Some(std::ffi::OsStr::new(required_name))) {
+          errors.push(format!("Missing asset '{}.{}' in {}", required_name, extension,
+              format_dir_paths(&dir_paths)));
+        }
+      }
+
+      for file_path in file_paths {
+        let result = match *subdir_name {
+          "images" => Image::try_from_file(&file_path, (1, 1)).map(|_| ()),
+          "levels" => level_file::LevelFile::load(&file_path).map(|_| ()),
+          _ => std::fs::metadata(&file_path).map(|_| ()).map_err(|error| format!(
+              "Could not read file '{}': {}", file_path.display(), error)),
+        };
+
+        match result {
+          Ok(()) => checked_file_paths.push(file_path),
+          Err(error) => errors.push(error),
+        }
+      }
+    }
+
+    return if errors.is_empty() { Ok(checked_file_paths) } else { Err(errors) };
+  }
+}
+
+fn image_number_of_frames(name: &str) -> (i32, i32) {
+  return match IMAGE_NUMBERS_OF_FRAMES.iter().find(|x| x.0 == name) {
+    Some((_, number_of_frames)) => *number_of_frames,
+    None => (1, 1),
+  };
+}
+
+fn subdirs(assets_dirs: &[std::path::PathBuf], subdir_name: &str) -> Vec<std::path::PathBuf> {
+  return assets_dirs.iter().map(|x| x.join(subdir_name)).collect();
+}
+
+fn format_dir_paths(dir_paths: &[std::path::PathBuf]) -> String {
+  return dir_paths.iter().map(|x| format!("'{}'", x.display())).collect::<Vec<String>>()
+      .join(", ");
+}
+
+// files of earlier directories shadow files with the same name in later directories,
+// missing directories are skipped unless none of them exists
+fn find_files(dir_paths: &[std::path::PathBuf],
+      extension: &str) -> Result<Vec<std::path::PathBuf>, String> {
+  let mut file_paths: Vec<std::path::PathBuf> = Vec::new();
+  let mut dir_found = false;
+
+  for dir_path in dir_paths {
+    if !dir_path.is_dir() { continue; }
+    dir_found = true;
+
+    for file_path in list_files(dir_path, extension)? {
+      if !file_paths.iter().any(|x| x.file_stem() == file_path.file_stem()) {
+        file_paths.push(file_path);
+      }
+    }
+  }
+
+  if !dir_found {
+    return Err(format!("Could not find any of the directories {}",
+        format_dir_paths(dir_paths)));
+  }
+
+  file_paths.sort_by(|x, y| x.file_stem().cmp(&y.file_stem()));
+  return Ok(file_paths);
+}
+
+fn list_files(dir_path: &std::path::Path,
+      extension: &str) -> Result<Vec<std::path::PathBuf>, String> {
+  let mut file_paths: Vec<std::path::PathBuf> = dir_path.read_dir()
+      .map_err(|error| format!("Could not read directory '{}': {}", dir_path.display(), error))?
+      .filter_map(|x| x.ok()).map(|x| x.path())
+      .filter(|x| x.is_file() && (x.extension() == Some(std::ffi::OsStr::new(extension))))
+      .collect();
+  file_paths.sort();
+  return Ok(file_paths);
+}
+
+impl CloneAsI32Vector for Vec<f64> {
+  fn clone_as_i32(&self) -> Vec<i32> {
+    return self.iter().map(|x| *x as i32).collect();
+  }
+}
+
+impl<AssetType> SingleTypeAssetLibrary<AssetType> {
+  pub fn new() -> SingleTypeAssetLibrary<AssetType> {
+    return SingleTypeAssetLibrary{
+      map: std::collections::HashMap::new(),
+    };
+  }
+
+  fn load_assets_from_path<S: Into<String>, F>(&mut self, dir_paths: &[std::path::PathBuf],
+        extension: S, load_fn: F, verbose: bool) where F: Fn(&std::path::Path) -> AssetType {
+    let file_paths = find_files(dir_paths, &extension.into())
+        .unwrap_or_else(|error| panic!("{}", error));
+
+    for file_path in file_paths {
+      let asset_name = file_path.file_stem().expect("Could not get file stem").to_str()
+          .expect("Could not convert file stem to string").to_string();
+      if verbose { println!("Loading asset '{}' from '{}'...", asset_name, file_path.display()); }
+      let asset = load_fn(&file_path);
+      self.map.insert(asset_name, asset);
+    }
+  }
+
+  fn get_asset<S: Into<String>>(&self, name: S) -> Option<&AssetType> {
+    let name = name.into();
+    return self.map.get(&name);
+  }
+}
+
+impl SingleTypeAssetLibrary<Image> {
+  pub fn load_assets(&mut self, dir_paths: &[std::path::PathBuf], verbose: bool) {
+    self.load_assets_from_path(dir_paths, "png",
+        |file_path| {
+          let asset_name = file_path.file_stem().expect("Could not get file stem").to_str()
+              .expect("Could not convert file stem to string");
+          return Image::try_from_file(file_path, image_number_of_frames(asset_name))
+              .unwrap_or_else(|error| panic!("{}", error));
+        }, verbose);
+  }
+}
+
+impl SingleTypeAssetLibrary<level_file::LevelFile> {
+  pub fn load_assets(&mut self, dir_paths: &[std::path::PathBuf], verbose: bool) {
+    self.load_assets_from_path(dir_paths, "json",
+        |file_path| level_file::LevelFile::load(file_path)
+          .unwrap_or_else(|error| panic!("{}", error)), verbose);
+  }
+}
+
+impl Image {
+  pub fn new<S: Into<String>>(name: S, full_width: u32, full_height: u32,
+        number_of_frames: (i32, i32), mask: Vec<bool>) -> Image {
+    return Image{
+      name: name.into(),
+      file_path: None,
+      full_width: full_width,
+      full_height: full_height,
+      number_of_frames: number_of_frames,
+      mask: mask,
+    };
+  }
+
+  // opaque images in place of all required images, with the numbers of frames of the original
+  // images and roughly their sizes
+  pub fn placeholders() -> Vec<Image> {
+    return REQUIRED_IMAGE_NAMES.iter().map(|&name| {
+      let frame_size: (u32, u32) = match name {
+        "background" | "helpSplash1" | "helpSplash2" | "landscape" | "lostDueToDamageSplash"
+          | "lostDueToTimeSplash" | "wonSplash" => (640, 480),
+        "font" => (16, 16),
+        "level" => (128, 96),
+        "sleigh" | "electrocutedSleigh" => (64, 32),
+        _ => (32, 32),
+      };
+      let number_of_frames = image_number_of_frames(name);
+      let full_width = frame_size.0 * (number_of_frames.0 as u32);
+      let full_height = frame_size.1 * (number_of_frames.1 as u32);
+      return Image::new(name, full_width, full_height, number_of_frames,
+          vec![true; (full_width * full_height) as usize]);
+    }).collect();
+  }
+
+  fn try_from_file(file_path: &std::path::Path,
+        mut number_of_frames: (i32, i32)) -> Result<Image, String> {
+    let name = file_path.file_stem().expect("Could not get file stem").to_str()
+        .expect("Could not convert file stem to string");
+    let file = std::fs::File::open(file_path).map_err(|error| format!(
+        "Could not open file '{}': {}", file_path.display(), error))?;
+    let mut decoder = png::Decoder::new(file);
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let (info, mut reader) = decoder.read_info().map_err(|error| format!(
+        "Could not read PNG header of '{}': {}", file_path.display(), error))?;
+    let mut pixels = vec![0; info.buffer_size()];
+    reader.next_frame(&mut pixels).map_err(|error| format!(
+        "Could not decode PNG image '{}': {}", file_path.display(), error))?;
+
+    let (color_type, bit_depth) = reader.output_color_type();
+    let mask = Image::mask_from_pixels(&pixels, info.line_size, info.width, info.height,
+        color_type, bit_depth);
+
+    if name == "font" {
+      number_of_frames = (Image::font_number_of_frames(info.width, info.height, &mask)
+          .map_err(|error| format!("Invalid font image '{}': {}", file_path.display(), error))?, 1);
+    }
+
+    let mut image = Image::new(name, info.width, info.height, number_of_frames, mask);
+    image.file_path = Some(file_path.to_path_buf());
+    return Ok(image);
+  }
+
+  // the font is extracted with or without punctuation glyphs, which all have the same width
+  fn font_number_of_frames(full_width: u32, full_height: u32,
+        mask: &[bool]) -> Result<i32, String> {
+    let candidates: Vec<i32> = FONT_NUMBERS_OF_FRAMES.iter().cloned()
+        .filter(|&x| full_width % (x as u32) == 0).collect();
+
+    // if the width is a multiple of both numbers, glyphs don't touch the frame borders
+    // for the right one
+    return candidates.iter().cloned().min_by_key(|&number_of_frames| {
+      let frame_width = (full_width as usize) / (number_of_frames as usize);
+      return (1 .. number_of_frames as usize).map(|frame| (0 .. full_height as usize)
+          .filter(|&y| mask[y * (full_width as usize) + frame * frame_width]).count())
+          .sum::<usize>();
+    }).ok_or_else(|| format!("Width {} is not a multiple of {:?} frames, \
+        re-run tools/extractAssets.py", full_width, FONT_NUMBERS_OF_FRAMES));
+  }
+
+  fn mask_from_pixels(pixels: &[u8], line_size: usize, width: u32, height: u32,
+        color_type: png::ColorType, bit_depth: png::BitDepth) -> Vec<bool> {
+    let bytes_per_sample = if bit_depth == png::BitDepth::Sixteen { 2 } else { 1 };
+    let samples_per_pixel = color_type.samples();
+    let has_alpha = (color_type == png::ColorType::RGBA)
+        || (color_type == png::ColorType::GrayscaleAlpha);
+    let mut mask: Vec<bool> = Vec::new();
+
+    for y in 0 .. height as usize {
+      for x in 0 .. width as usize {
+        if !has_alpha {
+          mask.push(true);
+          continue;
+        }
+
+        let offset = y * line_size + ((x + 1) * samples_per_pixel - 1) * bytes_per_sample;
+        mask.push(pixels[offset .. offset + bytes_per_sample].iter().any(|x| *x > 0));
+      }
+    }
+
+    return mask;
+  }
+
+  pub fn draw(&self, renderer: &mut dyn render::Renderer, dst_point: Point, frame: f64) {
+    self.draw_blit(renderer, Rect::new(0, 0, self.width() as u32, self.height() as u32),
+        dst_point, frame);
+  }
+
+  pub fn draw_blit(&self, renderer: &mut dyn render::Renderer, src_rect: Rect, dst_point: Point,
+        frame: f64) {
+    let frame = frame as i32;
+    let src_rect = Rect::new(
+        src_rect.x() + (frame % self.number_of_frames.0) * (self.width() as i32),
+        src_rect.y() + ((frame / self.number_of_frames.0) % self.number_of_frames.1)
+          * (self.height() as i32), src_rect.width(), src_rect.height());
+
+    renderer.draw_image(self, src_rect,
+        Point::new(dst_point.x().floor(), dst_point.y().floor()));
+  }
+
+  pub fn collides(&self, point: Point, frame: f64, other: &Image, other_point: Point,
+        other_frame: f64) -> bool {
+    let (point_x, point_y) = (point.x() as i32, point.y() as i32);
+    let (other_point_x, other_point_y) = (other_point.x() as i32, other_point.y() as i32);
+    let frame = frame as i32;
+    let other_frame = other_frame as i32;
+    let (width, height) = (self.width() as i32, self.height() as i32);
+    let (other_width, other_height) = (other.width() as i32, other.height() as i32);
+
+    if ((point_x < other_point_x) && (point_x + width < other_point_x))
+          || ((other_point_x < point_x) && (other_point_x + other_width < point_x))
+          || ((point_y < other_point_y) && (point_y + height < other_point_y))
+          || ((other_point_y < point_y) && (other_point_y + other_height < point_y)) {
+      return false;
+    }
+
+    let clip_rect_width = if point_x < other_point_x {
+          other_width.min(point_x + width - other_point_x)
+        } else {
+          width.min(other_point_x + other_width - point_x)
+        };
+
+    let clip_rect_height = if point_y < other_point_y {
+          other_height.min(point_y + height - other_point_y)
+        } else {
+          height.min(other_point_y + other_height - point_y)
+        };
+
+    if (clip_rect_width <= 0) || (clip_rect_height <= 0) { return false; }
+
+    let clip_rect = Rect::new(point_x.max(other_point_x), point_y.max(other_point_y),
+        clip_rect_width as u32, clip_rect_height as u32);
+
+    let surface_width = self.full_width as i32;
+    let other_surface_width = other.full_width as i32;
+    let number_of_frames = self.number_of_frames;
+    let other_number_of_frames = other.number_of_frames;
+    let mask = self.mask();
+    let other_mask = other.mask();
+
+    for clip_y in clip_rect.top() .. clip_rect.bottom() {
+      for clip_x in clip_rect.left() .. clip_rect.right() {
+        let index = (
+            (clip_x - point_x
+              + (frame % number_of_frames.0) * width)
+            + (clip_y - point_y
+              + ((frame / number_of_frames.0) % number_of_frames.1)
+              * height) * surface_width) as usize;
+
+        let other_index = (
+            (clip_x - other_point_x
+              + (other_frame % other_number_of_frames.0) * other_width)
+            + (clip_y - other_point_y
+              + ((other_frame / other_number_of_frames.0) % other_number_of_frames.1)
+              * other_height) * other_surface_width) as usize;
+
+        if mask[index] && other_mask[other_index] { return true; }
+      }
+    }
+
+    return false;
+  }
+
+  pub fn name(&self) -> &str {
+    return &self.name;
+  }
+
+  pub fn file_path(&self) -> Option<&std::path::Path> {
+    return self.file_path.as_deref();
+  }
+
+  pub fn width(&self) -> f64 {
+    return (self.full_width as f64) / (self.number_of_frames.0 as f64);
+  }
+
+  pub fn height(&self) -> f64 {
+    return (self.full_height as f64) / (self.number_of_frames.1 as f64);
+  }
+
+  pub fn size(&self) -> Point {
+    return Point::new(self.width(), self.height());
+  }
+
+  pub fn total_number_of_frames(&self) -> i32 {
+    return self.number_of_frames.0 * self.number_of_frames.1;
+  }
+
+  pub fn mask(&self) -> &Vec<bool> {
+    return &self.mask;
+  }
+}
+
+impl Song for SilentSong {
+  fn play(&self) {}
+  fn stop(&self) {}
+  fn pause(&self) {}
+  fn resume(&self) {}
+}
+
+impl Sound for SilentSound {
+  fn play_with_volume_and_pan(&self, _volume: f64, _pan: f64) {}
+}
+
+impl Point {
+  pub const fn new(x: f64, y: f64) -> Point {
+    return Point{x: x, y: y};
+  }
+
+  pub fn zero() -> Point {
+    return Point{x: 0.0, y: 0.0};
+  }
+
+  pub fn from_u32_tuple(point: (u32, u32)) -> Point {
+    return Point{x: point.0 as f64, y: point.1 as f64};
+  }
+
+  pub fn min(&self, rhs: Point) -> Point {
+    return Point::new(self.x.min(rhs.x), self.y.min(rhs.y));
+  }
+
+  pub fn max(&self, rhs: Point) -> Point {
+    return Point::new(self.x.max(rhs.x), self.y.max(rhs.y));
+  }
+
+  pub fn sin(&self) -> Point {
+    return Point::new(f64::sin(self.x), f64::sin(self.y));
+  }
+
+  pub fn x(&self) -> f64 {
+    return self.x;
+  }
+
+  pub fn y(&self) -> f64 {
+    return self.y;
+  }
+}
+
+impl Rect {
+  pub const fn new(x: i32, y: i32, width: u32, height: u32) -> Rect {
+    return Rect{x: x, y: y, width: width, height: height};
+  }
+
+  pub fn x(&self) -> i32 {
+    return self.x;
+  }
+
+  pub fn y(&self) -> i32 {
+    return self.y;
+  }
+
+  pub fn width(&self) -> u32 {
+    return self.width;
+  }
+
+  pub fn height(&self) -> u32 {
+    return self.height;
+  }
+
+  pub fn left(&self) -> i32 {
+    return self.x;
+  }
+
+  pub fn right(&self) -> i32 {
+    return self.x + (self.width as i32);
+  }
+
+  pub fn top(&self) -> i32 {
+    return self.y;
+  }
+
+  pub fn bottom(&self) -> i32 {
+    return self.y + (self.height as i32);
+  }
+}
+
+impl std::ops::Add<Point> for Point {
+  type Output = Point;
+
+  fn add(self, rhs: Point) -> Point {
+    return Point::new(self.x + rhs.x, self.y + rhs.y);
+  }
+}
+
+impl std::ops::Add<f64> for Point {
+  type Output = Point;
+
+  fn add(self, rhs: f64) -> Point {
+    return Point::new(self.x + rhs, self.y + rhs);
+  }
+}
+
+impl std::ops::Add<Point> for f64 {
+  type Output = Point;
+
+  fn add(self, rhs: Point) -> Point {
+    return Point::new(self + rhs.x, self + rhs.y);
+  }
+}
+
+impl std::ops::Sub<Point> for Point {
+  type Output = Point;
+
+  fn sub(self, rhs: Point) -> Point {
+    return Point::new(self.x - rhs.x, self.y - rhs.y);
+  }
+}
+
+impl std::ops::Sub<f64> for Point {
+  type Output = Point;
+
+  fn sub(self, rhs: f64) -> Point {
+    return Point::new(self.x - rhs, self.y - rhs);
+  }
+}
+
+impl std::ops::Sub<Point> for f64 {
+  type Output = Point;
+
+  fn sub(self, rhs: Point) -> Point {
+    return Point::new(self - rhs.x, self - rhs.y);
+  }
+}
+
+impl std::ops::Mul<Point> for Point {
+  type Output = Point;
+
+  fn mul(self, rhs: Point) -> Point {
+    return Point::new(self.x * rhs.x, self.y * rhs.y);
+  }
+}
+
+impl std::ops::Mul<f64> for Point {
+  type Output = Point;
+
+  fn mul(self, rhs: f64) -> Point {
+    return Point::new(self.x * rhs, self.y * rhs);
+  }
+}
+
+impl std::ops::Mul<Point> for f64 {
+  type Output = Point;
+
+  fn mul(self, rhs: Point) -> Point {
+    return Point::new(self * rhs.x, self * rhs.y);
+  }
+}
+
+impl std::ops::Div<Point> for Point {
+  type Output = Point;
+
+  fn div(self, rhs: Point) -> Point {
+    return Point::new(self.x / rhs.x, self.y / rhs.y);
+  }
+}
+
+impl std::ops::Div<f64> for Point {
+  type Output = Point;
+
+  fn div(self, rhs: f64) -> Point {
+    return Point::new(self.x / rhs, self.y / rhs);
+  }
+}
+
+impl std::ops::Div<Point> for f64 {
+  type Output = Point;
+
+  fn div(self, rhs: Point) -> Point {
+    return Point::new(self / rhs.x, self / rhs.y);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // mask of a font with glyphs that leave two transparent columns on both sides of each frame
+  fn create_font_mask(number_of_frames: usize, frame_width: usize, height: usize) -> Vec<bool> {
+    return (0 .. height).flat_map(|_| (0 .. number_of_frames * frame_width)
+        .map(|x| (2 .. frame_width - 2).contains(&(x % frame_width)))).collect();
+  }
+
+  #[test]
+  fn font_number_of_frames_is_derived_from_width() {
+    assert_eq!(Image::font_number_of_frames(720, 16, &create_font_mask(45, 16, 16)), Ok(45));
+    assert_eq!(Image::font_number_of_frames(912, 16, &create_font_mask(57, 16, 16)), Ok(57));
+    assert!(Image::font_number_of_frames(700, 16, &vec![true; 700 * 16]).unwrap_err()
+        .contains("re-run tools/extractAssets.py"));
+  }
+
+  #[test]
+  fn font_number_of_frames_is_derived_from_glyphs_if_width_is_ambiguous() {
+    assert_eq!(Image::font_number_of_frames(855, 16, &create_font_mask(45, 19, 16)), Ok(45));
+    assert_eq!(Image::font_number_of_frames(855, 16, &create_font_mask(57, 15, 16)), Ok(57));
+  }
+}
@@ -0,0 +1,114 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use rand::Rng;
+use rand::SeedableRng;
+
+use crate::*;
+use crate::asset::Point;
+
+pub struct GameAudio<'a> {
+  canvas_size: Point,
+  // only picks between sound variants, must not consume the gameplay RNG
+  rng: rand::rngs::StdRng,
+
+  gift_collided_with_chimney_sound: &'a dyn asset::Sound,
+  gift_collided_with_ground_sound: &'a dyn asset::Sound,
+  sleigh_collided_with_tile_sound1: &'a dyn asset::Sound,
+  sleigh_collided_with_tile_sound2: &'a dyn asset::Sound,
+  sleigh_collided_with_npc_sound: &'a dyn asset::Sound,
+  sleigh_collided_with_cloud_sound: &'a dyn asset::Sound,
+  cash_balloon_sound: &'a dyn asset::Sound,
+  gift_balloon_sound: &'a dyn asset::Sound,
+  shield_balloon_sound: &'a dyn asset::Sound,
+  wine_balloon_sound: &'a dyn asset::Sound,
+  goblin_throw_snowball_sound: &'a dyn asset::Sound,
+  snowman_launch_sound: &'a dyn asset::Sound,
+  dog_sound: &'a dyn asset::Sound,
+  bell_sound: &'a dyn asset::Sound,
+
+  dog_sound_volume: f64,
+  bell_sound_volume: f64,
+}
+
+impl<'a> GameAudio<'a> {
+  pub fn new(asset_library: &'a asset::AssetLibrary<'a>, canvas_size: Point) -> GameAudio<'a> {
+    return GameAudio{
+      canvas_size: canvas_size,
+      rng: rand::rngs::StdRng::from_entropy(),
+
+      gift_collided_with_chimney_sound: asset_library.get_sound("giftCollidedWithChimney"),
+      gift_collided_with_ground_sound: asset_library.get_sound("giftCollidedWithGround"),
+      sleigh_collided_with_tile_sound1: asset_library.get_sound("sleighCollidedWithLevelTile1"),
+      sleigh_collided_with_tile_sound2: asset_library.get_sound("sleighCollidedWithLevelTile2"),
+      sleigh_collided_with_npc_sound: asset_library.get_sound("sleighCollidedWithNpc"),
+      sleigh_collided_with_cloud_sound: asset_library.get_sound("sleighCollidedWithCloud"),
+      cash_balloon_sound: asset_library.get_sound("cashBalloon"),
+      gift_balloon_sound: asset_library.get_sound("giftBalloon"),
+      shield_balloon_sound: asset_library.get_sound("shieldBalloon"),
+      wine_balloon_sound: asset_library.get_sound("wineBalloon"),
+      goblin_throw_snowball_sound: asset_library.get_sound("goblinThrowSnowball"),
+      snowman_launch_sound: asset_library.get_sound("snowmanLaunch"),
+      dog_sound: asset_library.get_sound("dog"),
+      bell_sound: asset_library.get_sound("bell"),
+
+      dog_sound_volume: 0.5,
+      bell_sound_volume: 0.5,
+    };
+  }
+
+  pub fn handle_event(&mut self, event: &event::GameEvent) {
+    let (sound, position) = match *event {
+      event::GameEvent::GiftDelivered{position, ..} => {
+        (self.gift_collided_with_chimney_sound, position)
+      },
+      event::GameEvent::GiftHitGround{position, ..} => {
+        (self.gift_collided_with_ground_sound, position)
+      },
+      event::GameEvent::SleighHitTile{position, ..} => {
+        let sound = match self.rng.gen_range(0, 2) {
+          0 => self.sleigh_collided_with_tile_sound1,
+          _ => self.sleigh_collided_with_tile_sound2,
+        };
+        (sound, position)
+      },
+      event::GameEvent::SleighHitNpc{kind: npc::NpcKind::Cloud, position, ..} => {
+        (self.sleigh_collided_with_cloud_sound, position)
+      },
+      event::GameEvent::SleighHitNpc{position, ..} => {
+        (self.sleigh_collided_with_npc_sound, position)
+      },
+      event::GameEvent::PowerUpCollected{kind, position} => {
+        let sound = match kind {
+          npc::BalloonType::Cash => self.cash_balloon_sound,
+          npc::BalloonType::Gift => self.gift_balloon_sound,
+          npc::BalloonType::Heart => self.gift_collided_with_chimney_sound,
+          npc::BalloonType::Shield => self.shield_balloon_sound,
+          npc::BalloonType::Wine => self.wine_balloon_sound,
+        };
+        (sound, position)
+      },
+      event::GameEvent::SnowballThrown{position} => {
+        (self.goblin_throw_snowball_sound, position)
+      },
+      event::GameEvent::SnowmanLaunched{position} => {
+        (self.snowman_launch_sound, position)
+      },
+      event::GameEvent::DogBarked => {
+        self.dog_sound.play_with_volume(self.dog_sound_volume);
+        return;
+      },
+      event::GameEvent::BellRang => {
+        self.bell_sound.play_with_volume(self.bell_sound_volume);
+        return;
+      },
+      event::GameEvent::Finished => { return; },
+    };
+
+    sound.play_with_position(self.canvas_size, position);
+  }
+}
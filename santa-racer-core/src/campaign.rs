@@ -0,0 +1,161 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use serde::Serialize;
+use serde::Deserialize;
+
+use crate::*;
+
+// levels played one after another, totaling the points of won levels
+pub struct Campaign {
+  level_names: Vec<String>,
+  level_index: usize,
+  difficulty: game::GameDifficulty,
+  // entries of the won levels
+  level_highscores: Vec<highscore::Highscore>,
+}
+
+// persisted state of the campaign across runs
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CampaignProgress {
+  // names of won levels, each unlocks the level after it
+  completed_levels: Vec<String>,
+}
+
+impl Campaign {
+  pub fn new(level_names: Vec<String>, level_index: usize,
+        difficulty: game::GameDifficulty) -> Campaign {
+    assert!(level_index < level_names.len(), "Level index of campaign out of range");
+
+    return Campaign{
+      level_names: level_names,
+      level_index: level_index,
+      difficulty: difficulty,
+      level_highscores: Vec::new(),
+    };
+  }
+
+  // adds the entry of the won current level to the total
+  pub fn complete_level(&mut self, highscore: highscore::Highscore) {
+    self.level_highscores.push(highscore);
+  }
+
+  // stays at the last level
+  pub fn advance(&mut self) {
+    if self.has_next_level() { self.level_index += 1; }
+  }
+
+  pub fn has_next_level(&self) -> bool {
+    return self.level_index + 1 < self.level_names.len();
+  }
+
+  pub fn level_name(&self) -> &str {
+    return &self.level_names[self.level_index];
+  }
+
+  // starting at 1
+  pub fn level_number(&self) -> usize {
+    return self.level_index + 1;
+  }
+
+  pub fn number_of_levels(&self) -> usize {
+    return self.level_names.len();
+  }
+
+  pub fn difficulty(&self) -> game::GameDifficulty {
+    return self.difficulty;
+  }
+
+  pub fn total_points(&self) -> i32 {
+    return self.level_highscores.iter().map(|x| x.points()).sum();
+  }
+
+  // entry for the campaign table, only if all levels have been won, starting at the first one
+  pub fn total_highscore(&self) -> Option<highscore::Highscore> {
+    if (self.number_of_levels() < 2) || (self.level_highscores.len() < self.number_of_levels()) {
+      return None;
+    }
+
+    return Some(highscore::Highscore::from_campaign(self.difficulty, &self.level_highscores));
+  }
+}
+
+impl CampaignProgress {
+  pub fn complete_level(&mut self, level_name: &str) {
+    if !self.is_completed(level_name) {
+      self.completed_levels.push(level_name.to_string());
+    }
+  }
+
+  pub fn is_completed(&self, level_name: &str) -> bool {
+    return self.completed_levels.iter().any(|x| x == level_name);
+  }
+
+  // the first level is always unlocked
+  pub fn is_unlocked(&self, level_names: &[String], level_index: usize) -> bool {
+    return (level_index == 0) || self.is_completed(&level_names[level_index - 1]);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn create_level_names() -> Vec<String> {
+    return vec!["level1".to_string(), "level2".to_string()];
+  }
+
+  #[test]
+  fn campaign_advances_through_levels_and_totals_points() {
+    let mut campaign = Campaign::new(create_level_names(), 0, game::GameDifficulty::Hard);
+    assert_eq!((campaign.level_name(), campaign.level_number()), ("level1", 1));
+    assert!(campaign.has_next_level());
+
+    campaign.complete_level(highscore::Highscore::new("", 120));
+    assert!(campaign.total_highscore().is_none());
+    campaign.advance();
+    assert_eq!((campaign.level_name(), campaign.level_number()), ("level2", 2));
+    assert!(!campaign.has_next_level());
+
+    campaign.complete_level(highscore::Highscore::new("", 80));
+    campaign.advance();
+    assert_eq!(campaign.level_name(), "level2");
+    assert_eq!(campaign.total_points(), 200);
+
+    let highscore = campaign.total_highscore().unwrap();
+    assert_eq!(highscore.points(), 200);
+    assert_eq!(highscore.difficulty(), Some(game::GameDifficulty::Hard));
+    assert_eq!(highscore.level(), highscore::CAMPAIGN_TABLE_NAME);
+  }
+
+  #[test]
+  fn campaign_started_at_later_level_has_no_total() {
+    let mut campaign = Campaign::new(create_level_names(), 1, game::GameDifficulty::Easy);
+    campaign.complete_level(highscore::Highscore::new("", 80));
+    assert_eq!(campaign.total_points(), 80);
+    assert!(campaign.total_highscore().is_none());
+  }
+
+  #[test]
+  fn progress_unlocks_level_after_completed_one() {
+    let level_names = create_level_names();
+    let mut progress = CampaignProgress::default();
+    assert!(progress.is_unlocked(&level_names, 0));
+    assert!(!progress.is_unlocked(&level_names, 1));
+
+    progress.complete_level("level1");
+    progress.complete_level("level1");
+    assert!(progress.is_completed("level1"));
+    assert!(progress.is_unlocked(&level_names, 1));
+    assert!(!progress.is_completed("level2"));
+
+    let progress: CampaignProgress = serde_json::from_value(serde_json::to_value(&progress)
+        .unwrap()).unwrap();
+    assert_eq!(progress.completed_levels, vec!["level1"]);
+  }
+}
@@ -0,0 +1,159 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+pub trait GameClock {
+  fn now(&self) -> std::time::Instant;
+  fn tick(&mut self);
+}
+
+pub struct SystemClock {
+  now: std::time::Instant,
+}
+
+pub struct ManualClock {
+  now: std::time::Instant,
+  tick_duration: std::time::Duration,
+}
+
+pub struct ScaledClock {
+  now: std::time::Instant,
+  last_real_instant: std::time::Instant,
+  scale: f64,
+}
+
+impl SystemClock {
+  pub fn new() -> SystemClock {
+    return SystemClock{
+      now: std::time::Instant::now(),
+    };
+  }
+}
+
+impl GameClock for SystemClock {
+  fn now(&self) -> std::time::Instant {
+    return self.now;
+  }
+
+  fn tick(&mut self) {
+    self.now = std::time::Instant::now();
+  }
+}
+
+impl ManualClock {
+  pub fn new(tick_duration: std::time::Duration) -> ManualClock {
+    return ManualClock{
+      now: std::time::Instant::now(),
+      tick_duration: tick_duration,
+    };
+  }
+
+  pub fn advance(&mut self, duration: std::time::Duration) {
+    self.now += duration;
+  }
+
+  pub fn tick_duration(&self) -> std::time::Duration {
+    return self.tick_duration;
+  }
+
+  pub fn set_tick_duration(&mut self, tick_duration: std::time::Duration) {
+    self.tick_duration = tick_duration;
+  }
+}
+
+impl GameClock for ManualClock {
+  fn now(&self) -> std::time::Instant {
+    return self.now;
+  }
+
+  fn tick(&mut self) {
+    self.now += self.tick_duration;
+  }
+}
+
+impl ScaledClock {
+  pub fn new(scale: f64) -> ScaledClock {
+    let now = std::time::Instant::now();
+
+    return ScaledClock{
+      now: now,
+      last_real_instant: now,
+      scale: scale,
+    };
+  }
+
+  pub fn scale(&self) -> f64 {
+    return self.scale;
+  }
+
+  pub fn set_scale(&mut self, scale: f64) {
+    self.scale = scale.max(0.0);
+  }
+}
+
+impl GameClock for ScaledClock {
+  fn now(&self) -> std::time::Instant {
+    return self.now;
+  }
+
+  fn tick(&mut self) {
+    let real_now = std::time::Instant::now();
+    self.now += (real_now - self.last_real_instant).mul_f64(self.scale);
+    self.last_real_instant = real_now;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn manual_clock_advances_only_when_told() {
+    let mut clock = ManualClock::new(std::time::Duration::from_millis(10));
+    let start_instant = clock.now();
+
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    assert_eq!(clock.now(), start_instant);
+
+    clock.tick();
+    clock.tick();
+    assert_eq!(clock.now() - start_instant, std::time::Duration::from_millis(20));
+
+    clock.advance(std::time::Duration::from_secs(3));
+    assert_eq!(clock.now() - start_instant, std::time::Duration::from_millis(3020));
+
+    clock.set_tick_duration(std::time::Duration::from_millis(1));
+    assert_eq!(clock.tick_duration(), std::time::Duration::from_millis(1));
+    clock.tick();
+    assert_eq!(clock.now() - start_instant, std::time::Duration::from_millis(3021));
+  }
+
+  #[test]
+  fn scaled_clock_scales_real_time() {
+    let sleep_duration = std::time::Duration::from_millis(20);
+    let mut clock = ScaledClock::new(2.0);
+    let start_instant = clock.now();
+
+    std::thread::sleep(sleep_duration);
+    assert_eq!(clock.now(), start_instant);
+    clock.tick();
+    assert!(clock.now() - start_instant >= 2 * sleep_duration);
+
+    clock.set_scale(-1.0);
+    assert_eq!(clock.scale(), 0.0);
+    let paused_instant = clock.now();
+    std::thread::sleep(sleep_duration);
+    clock.tick();
+    assert_eq!(clock.now(), paused_instant);
+
+    clock.set_scale(0.5);
+    let real_start_instant = std::time::Instant::now();
+    std::thread::sleep(sleep_duration);
+    clock.tick();
+    assert!(clock.now() - paused_instant >= sleep_duration / 2);
+    assert!(clock.now() - paused_instant <= real_start_instant.elapsed());
+  }
+}
@@ -0,0 +1,53 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::*;
+use crate::asset::Point;
+
+// positions are canvas coordinates (i.e., level offset already subtracted)
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GameEvent {
+  GiftDelivered{chimney: (usize, usize), points: f64, bonus: bool, position: Point},
+  GiftHitGround{damage_points: f64, position: Point},
+  SleighHitTile{damage_points: f64, position: Point},
+  SleighHitNpc{kind: npc::NpcKind, damage_points: f64, position: Point},
+  PowerUpCollected{kind: npc::BalloonType, position: Point},
+  SnowballThrown{position: Point},
+  SnowmanLaunched{position: Point},
+  // ambient sounds of the level, not tied to a position
+  DogBarked,
+  BellRang,
+  Finished,
+}
+
+pub struct EventQueue {
+  events: Vec<GameEvent>,
+}
+
+impl EventQueue {
+  pub fn new() -> EventQueue {
+    return EventQueue{
+      events: Vec::new(),
+    };
+  }
+
+  pub fn push(&mut self, event: GameEvent) {
+    self.events.push(event);
+  }
+
+  pub fn drain(&mut self) -> std::vec::Drain<'_, GameEvent> {
+    return self.events.drain(..);
+  }
+
+  pub fn clear(&mut self) {
+    self.events.clear();
+  }
+
+  pub fn is_empty(&self) -> bool {
+    return self.events.is_empty();
+  }
+}
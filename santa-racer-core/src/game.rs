@@ -0,0 +1,44 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use serde::Serialize;
+use serde::Deserialize;
+
+use crate::*;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GameMode {
+  Menu,
+  HelpSplash1,
+  HelpSplash2,
+  HighscoreTable,
+  Running,
+  Paused,
+  WonSplash,
+  LostDueToDamageSplash,
+  LostDueToTimeSplash,
+  NewHighscore,
+  Options,
+  LevelSelect,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum GameDifficulty {
+  Easy,
+  Hard,
+}
+
+impl GameDifficulty {
+  pub const ALL: [GameDifficulty; 2] = [GameDifficulty::Easy, GameDifficulty::Hard];
+
+  pub fn label(self) -> locale::Message {
+    return match self {
+      GameDifficulty::Easy => locale::Message::Easy,
+      GameDifficulty::Hard => locale::Message::Hard,
+    };
+  }
+}
@@ -0,0 +1,363 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use rand::Rng;
+
+use crate::*;
+use crate::asset::Point;
+
+pub struct Chimney {
+  position: Point,
+  size: Point,
+  frame: f64,
+}
+
+pub struct Gift<'a> {
+  image: &'a asset::Image,
+  star_image: &'a asset::Image,
+  points10_image: &'a asset::Image,
+  points15_image: &'a asset::Image,
+  points20_image: &'a asset::Image,
+  canvas_size: asset::Point,
+
+  mode: GiftMode,
+  bonus: bool,
+
+  size: Point,
+  position: Point,
+  velocity: Point,
+  acceleration: Point,
+  frame: f64,
+  last_update_instant: std::time::Instant,
+
+  star1_offset: Point,
+  star2_offset: Point,
+  star3_offset: Point,
+  points_offset: Point,
+  bonus_offset: Point,
+  star1_frame_offset: f64,
+  star2_frame_offset: f64,
+  star3_frame_offset: f64,
+  frame_speed: f64,
+  showing_points_frame_speed: f64,
+  damage_points: f64,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum GiftMode {
+  Falling,
+  ShowingPoints(f64),
+  CanBeDeleted,
+}
+
+impl Chimney {
+  pub fn new(position: Point, size: Point, frame: f64) -> Chimney {
+    return Chimney{
+      position: position,
+      size: size,
+      frame: frame,
+    };
+  }
+}
+
+impl<'a> Gift<'a> {
+  pub fn new(asset_library: &'a asset::AssetLibrary<'a>, level: &level::Level,
+        sleigh: &sleigh::Sleigh, canvas_size: Point, difficulty: game::GameDifficulty,
+        clock: &dyn clock::GameClock, rng: &mut rand::rngs::StdRng) -> Gift<'a> {
+    let number_of_gift_types = 4;
+    let image = asset_library.get_image(format!("gift{}",
+        rng.gen_range(1, number_of_gift_types)));
+    let velocity_y = 50.0;
+    let velocity = match difficulty {
+      game::GameDifficulty::Easy => Point::new(level.scroll_speed_x(), velocity_y),
+      game::GameDifficulty::Hard => Point::new(sleigh.velocity().x() + level.scroll_speed_x(),
+        velocity_y + sleigh.velocity().y()),
+    };
+
+    return Gift{
+      image: image,
+      star_image: asset_library.get_image("bigStar"),
+      points10_image: asset_library.get_image("points10"),
+      points15_image: asset_library.get_image("points15"),
+      points20_image: asset_library.get_image("points20"),
+      canvas_size: canvas_size,
+
+      mode: GiftMode::Falling,
+      bonus: sleigh.bonus(),
+
+      size: image.size(),
+      position: Point::new(sleigh.position().x() + level.offset_x(),
+        sleigh.position().y() + sleigh.size().y()),
+      velocity: velocity,
+      acceleration: Point::new(0.0, 200.0),
+      frame: rng.gen_range(0, image.total_number_of_frames()) as f64,
+      last_update_instant: clock.now(),
+
+      star1_offset: Point::new(10.0, 10.0),
+      star2_offset: Point::new(25.0, 15.0),
+      star3_offset: Point::new(15.0, 25.0),
+      points_offset: Point::new(10.0, 10.0),
+      bonus_offset: Point::new(10.0, 10.0),
+      star1_frame_offset: 0.0,
+      star2_frame_offset: 2.0,
+      star3_frame_offset: 4.0,
+      frame_speed: 15.0,
+      showing_points_frame_speed: 15.0,
+      damage_points: 15.0,
+    };
+  }
+
+  pub fn do_logic(&mut self, events: &mut event::EventQueue, level: &level::Level,
+        clock: &dyn clock::GameClock) {
+    let now = clock.now();
+    let seconds_since_last_update = (now - self.last_update_instant).as_secs_f64();
+
+    match self.mode {
+      GiftMode::Falling => {
+        self.position = self.position + seconds_since_last_update * self.velocity;
+        self.velocity = self.velocity + seconds_since_last_update * self.acceleration;
+        self.frame += seconds_since_last_update * self.frame_speed;
+
+        let position = Point::new(self.position.x() - level.offset_x(), self.position.y());
+
+        if let Some(chimney_tile) = self.has_collided_with_chimney(level) {
+          let gift_points = if chimney_tile.1 <= 1 { 10.0 }
+              else if chimney_tile.1 == 2 { 15.0 } else { 20.0 };
+          self.mode = GiftMode::ShowingPoints(gift_points);
+          self.frame = 0.0;
+          events.push(event::GameEvent::GiftDelivered{chimney: chimney_tile,
+              points: gift_points, bonus: self.bonus, position: position});
+        } else if self.has_collided_with_ground() {
+          self.mode = GiftMode::CanBeDeleted;
+          events.push(event::GameEvent::GiftHitGround{damage_points: self.damage_points,
+              position: position});
+        }
+      },
+      GiftMode::ShowingPoints(_) => {
+        self.frame += seconds_since_last_update * self.showing_points_frame_speed;
+
+        if self.frame >= self.star3_frame_offset
+              + (self.star_image.total_number_of_frames() as f64) {
+          self.mode = GiftMode::CanBeDeleted;
+        }
+      },
+      _ => {},
+    }
+
+    self.last_update_instant = now;
+  }
+
+  fn has_collided_with_chimney(&self, level: &level::Level) -> Option<(usize, usize)> {
+    let center_position = Point::new(self.position.x() + self.size.x() / 2.0,
+        self.position.y() + self.size.y() / 2.0);
+
+    for (tile_x, tile_y) in level.visible_tiles_iter() {
+      let frame = level.tile(tile_x, tile_y);
+      if frame < 0.0 { continue; }
+      let tile_position = Point::new((tile_x as f64) * level.tile_size().x(),
+          (tile_y as f64) * level.tile_size().y());
+
+      for chimney in level.chimneys().iter() {
+        if (chimney.frame == frame)
+              && (center_position.x() >= tile_position.x() + chimney.position.x())
+              && (center_position.x() <= tile_position.x() + chimney.position.x() + chimney.size.x())
+              && (center_position.y() >= tile_position.y() + chimney.position.y())
+              && (center_position.y() <= tile_position.y() + chimney.position.y() + chimney.size.y()) {
+          return Some((tile_x, tile_y));
+        }
+      }
+    }
+
+    return None;
+  }
+
+  fn has_collided_with_ground(&self) -> bool {
+    return self.position.y() >= self.canvas_size.y();
+  }
+
+  pub fn draw(&self, renderer: &mut dyn render::Renderer, level_offset_x: f64) {
+    let position: Point = Point::new(self.position.x() - level_offset_x, self.position.y());
+
+    match self.mode {
+      GiftMode::Falling => {
+        self.image.draw(renderer, position, self.frame);
+      },
+
+      GiftMode::ShowingPoints(gift_points) => {
+        let position = Point::new(position.x() - self.star_image.width() / 2.0,
+            position.y() - self.star_image.height() / 2.0);
+        let bonus_position = Point::new(position.x() + self.bonus_offset.x(),
+            position.y() + self.bonus_offset.y());
+
+        self.draw_star(position, renderer);
+        if self.bonus { self.draw_star(bonus_position, renderer); }
+
+        self.draw_points(position, gift_points, renderer);
+        if self.bonus { self.draw_points(bonus_position, gift_points, renderer); }
+      },
+
+      _ => {},
+    }
+  }
+
+  pub fn draw_star(&self, position: Point, renderer: &mut dyn render::Renderer) {
+    let number_of_star_frames = self.star_image.total_number_of_frames() as f64;
+
+    if (self.frame >= self.star1_frame_offset)
+          && (self.frame < self.star1_frame_offset + number_of_star_frames) {
+      self.star_image.draw(renderer, Point::new(position.x() + self.star1_offset.x(),
+          position.y() + self.star1_offset.y()), self.frame - self.star1_frame_offset);
+    }
+
+    if (self.frame >= self.star2_frame_offset)
+          && (self.frame < self.star2_frame_offset + number_of_star_frames) {
+      self.star_image.draw(renderer, Point::new(position.x() + self.star2_offset.x(),
+          position.y() + self.star2_offset.y()), self.frame - self.star2_frame_offset);
+    }
+
+    if (self.frame >= self.star3_frame_offset)
+          && (self.frame < self.star3_frame_offset + number_of_star_frames) {
+      self.star_image.draw(renderer, Point::new(position.x() + self.star3_offset.x(),
+          position.y() + self.star3_offset.y()), self.frame - self.star3_frame_offset);
+    }
+  }
+
+  pub fn draw_points(&self, position: Point,
+        gift_points: f64, renderer: &mut dyn render::Renderer) {
+    let points_image = match gift_points as i32 {
+      10 => self.points10_image,
+      15 => self.points15_image,
+      20 => self.points20_image,
+      _ => self.points10_image,
+    };
+
+    points_image.draw(renderer, Point::new(
+        position.x() + self.points_offset.x() + self.star_image.width() / 2.0,
+        position.y() + self.points_offset.y() + self.star_image.height()), 0.0)
+  }
+
+  pub fn mode(&self) -> GiftMode {
+    return self.mode;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::SeedableRng;
+
+  use super::*;
+  use crate::clock::GameClock;
+
+  // the second column has a tile with a chimney in every row, the other columns are empty
+  fn create_asset_library<'a>() -> asset::AssetLibrary<'a> {
+    let level_file: level_file::LevelFile = serde_json::from_str(r#"{
+        "version": 1, "name": "chimneys", "tileset": "level",
+        "number_of_tiles_x": 8, "number_of_tiles_y": 5, "start_offset_x": 0.0,
+        "min_scroll_speed_x": 40.0, "max_scroll_speed_x": 160.0, "menu_scroll_speed_x": 40.0,
+        "chimneys": [{"tile": 3, "x": 40.0, "y": 0.0, "width": 50.0, "height": 20.0}],
+        "tile_layer": [[-1, 3, -1, -1, -1, -1, -1, -1], [-1, 3, -1, -1, -1, -1, -1, -1],
+          [-1, 3, -1, -1, -1, -1, -1, -1], [-1, 3, -1, -1, -1, -1, -1, -1],
+          [-1, 3, -1, -1, -1, -1, -1, -1]],
+        "npc_layer": []}"#).unwrap();
+    return asset::AssetLibrary::from_assets(asset::Image::placeholders(), vec![level_file]);
+  }
+
+  // drops a gift whose center is at the given level position and lets it fall for one tick
+  fn drop_gift(asset_library: &asset::AssetLibrary, center_position: Point,
+        bonus: bool) -> (Vec<event::GameEvent>, GiftMode, render::RecordingRenderer) {
+    let canvas_size = Point::new(640.0, 480.0);
+    let mut clock = clock::ManualClock::new(std::time::Duration::from_secs_f64(1.0 / 120.0));
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    let level = level::Level::new(asset_library, "chimneys", canvas_size, &clock, &mut rng);
+    let sleigh = sleigh::Sleigh::new(asset_library, canvas_size, &clock, &mut rng);
+    let mut gift = Gift::new(asset_library, &level, &sleigh, canvas_size,
+        game::GameDifficulty::Easy, &clock, &mut rng);
+    gift.position = center_position - gift.size / 2.0;
+    gift.bonus = bonus;
+
+    let mut events = event::EventQueue::new();
+    clock.tick();
+    gift.do_logic(&mut events, &level, &clock);
+
+    let mut renderer = render::RecordingRenderer::new();
+    gift.draw(&mut renderer, level.offset_x());
+    return (events.drain().collect(), gift.mode(), renderer);
+  }
+
+  fn drawn_image_names(renderer: &render::RecordingRenderer) -> Vec<String> {
+    return renderer.commands().iter().filter_map(|command| match command {
+      render::DrawCommand::Image{image_name, ..} => Some(image_name.clone()),
+      _ => None,
+    }).collect();
+  }
+
+  #[test]
+  fn gift_points_depend_on_row_of_chimney() {
+    let asset_library = create_asset_library();
+
+    for &(tile_y, expected_points) in [(0, 10.0), (1, 10.0), (2, 15.0), (3, 20.0),
+          (4, 20.0)].iter() {
+      let (events, mode, renderer) = drop_gift(&asset_library,
+          Point::new(193.0, (tile_y as f64) * 96.0 + 5.0), false);
+
+      match events[..] {
+        [event::GameEvent::GiftDelivered{chimney, points, bonus, ..}] => {
+          assert_eq!(chimney, (1, tile_y));
+          assert_eq!(points, expected_points);
+          assert!(!bonus);
+        },
+        _ => panic!("Unexpected events {:?}", events),
+      }
+
+      assert!(mode == GiftMode::ShowingPoints(expected_points));
+      let points_image_name = format!("points{}", expected_points);
+      assert_eq!(drawn_image_names(&renderer).iter().filter(|x| **x == points_image_name)
+          .count(), 1);
+    }
+  }
+
+  #[test]
+  fn bonus_gift_shows_points_twice() {
+    let asset_library = create_asset_library();
+    let (events, _, renderer) = drop_gift(&asset_library, Point::new(193.0, 101.0), true);
+
+    match events[..] {
+      [event::GameEvent::GiftDelivered{points, bonus, ..}] => {
+        assert_eq!(points, 10.0);
+        assert!(bonus);
+      },
+      _ => panic!("Unexpected events {:?}", events),
+    }
+
+    assert_eq!(drawn_image_names(&renderer).iter().filter(|x| *x == "points10").count(), 2);
+  }
+
+  #[test]
+  fn gift_next_to_chimney_is_not_delivered() {
+    let asset_library = create_asset_library();
+    let (events, mode, renderer) = drop_gift(&asset_library, Point::new(148.0, 101.0), false);
+
+    assert!(events.is_empty());
+    assert!(mode == GiftMode::Falling);
+    assert_eq!(drawn_image_names(&renderer).len(), 1);
+    assert!(drawn_image_names(&renderer)[0].starts_with("gift"));
+  }
+
+  #[test]
+  fn gift_hitting_ground_causes_damage() {
+    let asset_library = create_asset_library();
+    let (events, mode, renderer) = drop_gift(&asset_library, Point::new(300.0, 520.0), false);
+
+    match events[..] {
+      [event::GameEvent::GiftHitGround{damage_points, ..}] => assert_eq!(damage_points, 15.0),
+      _ => panic!("Unexpected events {:?}", events),
+    }
+
+    assert!(mode == GiftMode::CanBeDeleted);
+    assert!(renderer.commands().is_empty());
+  }
+}
@@ -0,0 +1,408 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use hmac::Mac;
+use hmac::NewMac;
+use serde::Serialize;
+use serde::Deserialize;
+
+use crate::*;
+
+// run metadata defaults to zero or empty for placeholders and entries of older versions
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Highscore {
+  name: String,
+  points: i32,
+  #[serde(default)]
+  timestamp: u64,
+  #[serde(default)]
+  difficulty: Option<game::GameDifficulty>,
+  #[serde(default)]
+  gifts_delivered: u32,
+  #[serde(default)]
+  gift_points: f64,
+  #[serde(default)]
+  damage_points: f64,
+  #[serde(default)]
+  remaining_seconds: f64,
+  #[serde(default)]
+  game_version: String,
+  // TOML only supports signed 64-bit integers, so the seed is stored as string
+  #[serde(default)]
+  seed: String,
+  // empty for the default level, as in entries of versions before multiple levels
+  #[serde(default, skip_serializing_if = "String::is_empty")]
+  level: String,
+  // file name of the replay of the run, relative to the directory of highscore replays
+  #[serde(default)]
+  replay_file: String,
+  // hex-encoded HMAC-SHA256 of all other fields and the replay
+  #[serde(default)]
+  signature: String,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Verification {
+  Valid,
+  Unsigned,
+  InvalidSignature,
+  MissingReplay(String),
+  Mismatch{recomputed_points: i32},
+}
+
+// levels store their highscores with the name of the level, totals of campaigns with this name
+pub const CAMPAIGN_TABLE_NAME: &str = "campaign";
+
+// each level and difficulty has its own table, stored under this key, e.g., "level1-easy"
+pub fn table_key(level_name: &str, difficulty: game::GameDifficulty) -> String {
+  return format!("{}-{}", level_name, difficulty_key(difficulty));
+}
+
+// inverse of table_key
+pub fn parse_table_key(key: &str) -> Option<(&str, game::GameDifficulty)> {
+  let mut parts = key.rsplitn(2, '-');
+  let difficulty_part = parts.next()?;
+  let level_name = parts.next().filter(|x| !x.is_empty())?;
+  let difficulty = game::GameDifficulty::ALL.iter().find(
+      |&&x| difficulty_key(x) == difficulty_part)?;
+  return Some((level_name, *difficulty));
+}
+
+// tables of all levels followed by the campaign tables (if there are multiple levels)
+pub fn table_keys(level_names: &[String]) -> Vec<String> {
+  let mut table_names: Vec<&str> = level_names.iter().map(|x| x.as_str()).collect();
+  if level_names.len() > 1 { table_names.push(CAMPAIGN_TABLE_NAME); }
+
+  return table_names.iter().flat_map(|&table_name| game::GameDifficulty::ALL.iter()
+      .map(move |&difficulty| table_key(table_name, difficulty))).collect();
+}
+
+// shown in titles, e.g., "level1 - Easy"
+pub fn table_label(key: &str) -> String {
+  return match parse_table_key(key) {
+    Some((level_name, difficulty)) => format!("{} - {}",
+        if level_name == CAMPAIGN_TABLE_NAME { locale::Message::Campaign.text() }
+          else { level_name },
+        difficulty.label().text()),
+    None => key.to_string(),
+  };
+}
+
+// versions before multiple levels only had one table per difficulty, stored under this key
+pub fn difficulty_key(difficulty: game::GameDifficulty) -> &'static str {
+  return match difficulty {
+    game::GameDifficulty::Easy => "easy",
+    game::GameDifficulty::Hard => "hard",
+  };
+}
+
+pub fn insert_highscore(highscores: &mut Vec<Highscore>, highscore: Highscore,
+      number_of_highscores: usize) -> Option<usize> {
+  let new_highscore_index = highscores.iter().position(|x| x.points() <= highscore.points())?;
+  highscores.insert(new_highscore_index, highscore);
+
+  if highscores.len() > number_of_highscores {
+    highscores.resize(number_of_highscores, Highscore::new("", 0));
+  }
+
+  return Some(new_highscore_index);
+}
+
+impl Highscore {
+  pub fn new<S: Into<String>>(name: S, points: i32) -> Highscore {
+    return Highscore{
+      name: name.into(),
+      points: points,
+      timestamp: 0,
+      difficulty: None,
+      gifts_delivered: 0,
+      gift_points: 0.0,
+      damage_points: 0.0,
+      remaining_seconds: 0.0,
+      game_version: String::new(),
+      seed: String::new(),
+      level: String::new(),
+      replay_file: String::new(),
+      signature: String::new(),
+    };
+  }
+
+  // unnamed entry for the current state of a finished run
+  pub fn from_simulation(simulation: &simulation::Simulation) -> Highscore {
+    let score = simulation.score();
+    let timestamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)
+        .map(|x| x.as_secs()).unwrap_or(0);
+
+    return Highscore{
+      name: String::new(),
+      points: score.score_points() as i32,
+      timestamp: timestamp,
+      difficulty: Some(simulation.difficulty()),
+      gifts_delivered: simulation.statistics().gifts_delivered(),
+      gift_points: score.gift_points(),
+      damage_points: score.damage_points(),
+      remaining_seconds: score.remaining_duration().as_secs_f64(),
+      game_version: env!("CARGO_PKG_VERSION").to_string(),
+      seed: simulation.seed().to_string(),
+      level: simulation.level().name().to_string(),
+      replay_file: String::new(),
+      signature: String::new(),
+    };
+  }
+
+  // unnamed entry for the total of all levels of a campaign, stored in the campaign table
+  pub fn from_campaign(difficulty: game::GameDifficulty, level_highscores: &[Highscore])
+        -> Highscore {
+    let timestamp = level_highscores.iter().map(|x| x.timestamp).max().unwrap_or(0);
+
+    return Highscore{
+      name: String::new(),
+      points: level_highscores.iter().map(|x| x.points).sum(),
+      timestamp: timestamp,
+      difficulty: Some(difficulty),
+      gifts_delivered: level_highscores.iter().map(|x| x.gifts_delivered).sum(),
+      gift_points: level_highscores.iter().map(|x| x.gift_points).sum(),
+      damage_points: level_highscores.iter().map(|x| x.damage_points).sum(),
+      remaining_seconds: level_highscores.iter().map(|x| x.remaining_seconds).sum(),
+      game_version: env!("CARGO_PKG_VERSION").to_string(),
+      seed: String::new(),
+      level: CAMPAIGN_TABLE_NAME.to_string(),
+      replay_file: String::new(),
+      signature: String::new(),
+    };
+  }
+
+  pub fn name(&self) -> String {
+    return self.name.to_string();
+  }
+
+  // placeholders have no name, but are shown as empty entries in the current language
+  pub fn display_name(&self) -> String {
+    return if self.is_placeholder() { locale::Message::EmptyHighscoreName.text().to_string() }
+        else { self.name.clone() };
+  }
+
+  pub fn set_name<S: Into<String>>(&mut self, name: S) {
+    self.name = name.into();
+  }
+
+  pub fn points(&self) -> i32 {
+    return self.points;
+  }
+
+  pub fn set_points(&mut self, points: i32) {
+    self.points = points;
+  }
+
+  pub fn timestamp(&self) -> u64 {
+    return self.timestamp;
+  }
+
+  pub fn difficulty(&self) -> Option<game::GameDifficulty> {
+    return self.difficulty;
+  }
+
+  pub fn gifts_delivered(&self) -> u32 {
+    return self.gifts_delivered;
+  }
+
+  pub fn gift_points(&self) -> f64 {
+    return self.gift_points;
+  }
+
+  pub fn damage_points(&self) -> f64 {
+    return self.damage_points;
+  }
+
+  pub fn remaining_seconds(&self) -> f64 {
+    return self.remaining_seconds;
+  }
+
+  pub fn game_version(&self) -> String {
+    return self.game_version.to_string();
+  }
+
+  pub fn seed(&self) -> Option<u64> {
+    return self.seed.parse().ok();
+  }
+
+  pub fn level(&self) -> &str {
+    return if self.level.is_empty() { level::DEFAULT_LEVEL_NAME } else { &self.level };
+  }
+
+  pub fn replay_file(&self) -> String {
+    return self.replay_file.to_string();
+  }
+
+  pub fn set_replay_file<S: Into<String>>(&mut self, replay_file: S) {
+    self.replay_file = replay_file.into();
+  }
+
+  pub fn is_signed(&self) -> bool {
+    return !self.signature.is_empty() && !self.replay_file.is_empty();
+  }
+
+  // signatures can only be verified on the machine that created them
+  pub fn remove_signature(&mut self) {
+    self.replay_file.clear();
+    self.signature.clear();
+  }
+
+  // has to be called again after changing the entry, e.g., when the name has been entered
+  pub fn sign(&mut self, key: &[u8], replay: &replay::Replay) {
+    self.signature = self.compute_signature(key, replay);
+  }
+
+  fn compute_signature(&self, key: &[u8], replay: &replay::Replay) -> String {
+    let mut unsigned_highscore = self.clone();
+    unsigned_highscore.signature.clear();
+
+    let mut mac = hmac::Hmac::<sha2::Sha256>::new_from_slice(key)
+        .expect("HMAC accepts keys of any length");
+    mac.update(&serde_json::to_vec(&unsigned_highscore).expect("Could not serialize highscore"));
+    mac.update(&serde_json::to_vec(replay).expect("Could not serialize replay"));
+
+    return mac.finalize().into_bytes().iter().map(|x| format!("{:02x}", x)).collect();
+  }
+
+  // checks the signature and that simulating the replay results in the same points
+  pub fn verify<'a>(&self, asset_library: &'a asset::AssetLibrary<'a>, key: &[u8],
+        replay: Result<replay::Replay, String>) -> Verification {
+    if !self.is_signed() { return Verification::Unsigned; }
+
+    let replay = match replay {
+      Ok(replay) => replay,
+      Err(error) => return Verification::MissingReplay(error),
+    };
+
+    // comparison of all bytes, as timing does not matter here
+    if self.compute_signature(key, &replay) != self.signature {
+      return Verification::InvalidSignature;
+    }
+
+    let result = simulation::Simulation::run_replay(asset_library, &replay);
+    let recomputed_points = result.score_points() as i32;
+
+    if (result.outcome() != Some(simulation::SimulationOutcome::Won))
+          || (recomputed_points != self.points) || (Some(replay.seed()) != self.seed())
+          || (Some(replay.difficulty()) != self.difficulty)
+          || (replay.level() != self.level()) {
+      return Verification::Mismatch{recomputed_points: recomputed_points};
+    }
+
+    return Verification::Valid;
+  }
+
+  // entries without run metadata are placeholders or stem from older versions
+  pub fn has_details(&self) -> bool {
+    return self.timestamp > 0;
+  }
+
+  // entries filling up tables that have less than the number of highscores
+  pub fn is_placeholder(&self) -> bool {
+    return (self.points == 0) && !self.has_details();
+  }
+
+  // pairs of label and value as shown in the detail view
+  pub fn details(&self) -> Vec<(String, String)> {
+    let unknown = || locale::Message::Unknown.text().to_string();
+    let if_known = |value: String| if self.has_details() { value } else { unknown() };
+    let remaining_seconds = self.remaining_seconds.max(0.0) as u64;
+
+    return vec![
+      (locale::Message::HighscorePoints.text().to_string(), self.points.to_string()),
+      (locale::Message::HighscoreDate.text().to_string(),
+        if_known(format_timestamp(self.timestamp))),
+      (locale::Message::OptionDifficulty.text().to_string(),
+        self.difficulty.map(|x| x.label().text().to_string()).unwrap_or_else(unknown)),
+      (locale::Message::HighscoreGiftsDelivered.text().to_string(),
+        if_known(self.gifts_delivered.to_string())),
+      (locale::Message::HighscoreGiftPoints.text().to_string(),
+        if_known(format!("{:.0}", self.gift_points))),
+      (locale::Message::HighscoreDamagePoints.text().to_string(),
+        if_known(format!("{:.0}", self.damage_points))),
+      (locale::Message::HighscoreRemainingTime.text().to_string(),
+        if_known(format!("{}:{:02}", remaining_seconds / 60, remaining_seconds % 60))),
+      (locale::Message::HighscoreGameVersion.text().to_string(),
+        if self.game_version.is_empty() { unknown() } else { self.game_version() }),
+      (locale::Message::HighscoreSeed.text().to_string(),
+        if self.seed.is_empty() { unknown() } else { self.seed.to_string() }),
+      (locale::Message::HighscoreLevel.text().to_string(),
+        if self.level == CAMPAIGN_TABLE_NAME { locale::Message::Campaign.text().to_string() }
+          else { self.level().to_string() }),
+    ];
+  }
+}
+
+impl std::fmt::Display for Verification {
+  fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+    return match self {
+      Verification::Valid => write!(formatter, "{}", locale::Message::VerificationValid.text()),
+      Verification::Unsigned => write!(formatter, "{}",
+          locale::Message::VerificationUnsigned.text()),
+      Verification::InvalidSignature => write!(formatter, "{}",
+          locale::Message::VerificationInvalidSignature.text()),
+      Verification::MissingReplay(error) => write!(formatter, "{}",
+          locale::Message::VerificationMissingReplay.format(&[error])),
+      Verification::Mismatch{recomputed_points} => write!(formatter, "{}",
+          locale::Message::VerificationMismatch.format(&[recomputed_points])),
+    };
+  }
+}
+
+// formats seconds since the Unix epoch as "YYYY-MM-DD HH:MM" in UTC
+pub fn format_timestamp(timestamp: u64) -> String {
+  let days = (timestamp / 86400) as i64;
+  let seconds_of_day = timestamp % 86400;
+
+  // conversion of days to civil dates after Howard Hinnant's algorithm
+  let days = days + 719468;
+  let era = days.div_euclid(146097);
+  let day_of_era = days.rem_euclid(146097);
+  let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524
+      - day_of_era / 146096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let month_index = (5 * day_of_year + 2) / 153;
+  let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+  let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+  let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+  return format!("{:04}-{:02}-{:02} {:02}:{:02}", year, month, day,
+      seconds_of_day / 3600, (seconds_of_day % 3600) / 60);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn only_placeholders_are_shown_as_empty() {
+    let placeholder = Highscore::new("", 0);
+    assert!(placeholder.is_placeholder());
+    assert_eq!(placeholder.name(), "");
+    assert_eq!(placeholder.display_name(), locale::Message::EmptyHighscoreName.text());
+
+    let highscore = Highscore::new("Rudolph", 120);
+    assert!(!highscore.is_placeholder());
+    assert_eq!(highscore.display_name(), "Rudolph");
+  }
+
+  #[test]
+  fn table_keys_combine_level_and_difficulty() {
+    let key = table_key("level2", game::GameDifficulty::Hard);
+    assert_eq!(key, "level2-hard");
+    assert_eq!(parse_table_key(&key), Some(("level2", game::GameDifficulty::Hard)));
+    assert_eq!(parse_table_key("my-level-easy"), Some(("my-level", game::GameDifficulty::Easy)));
+    assert_eq!(parse_table_key("easy"), None);
+    assert_eq!(parse_table_key("-easy"), None);
+    assert_eq!(parse_table_key("level1-medium"), None);
+
+    assert_eq!(table_keys(&["level1".to_string()]), vec!["level1-easy", "level1-hard"]);
+    assert_eq!(table_keys(&["level1".to_string(), "level2".to_string()]), vec!["level1-easy",
+        "level1-hard", "level2-easy", "level2-hard", "campaign-easy", "campaign-hard"]);
+  }
+}
@@ -0,0 +1,446 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use serde::Serialize;
+use serde::Deserialize;
+
+use crate::*;
+use crate::highscore::Highscore;
+
+const HIGHSCORE_FILE_FORMAT_VERSION: u32 = 1;
+
+// CSV columns besides the leading "table" column, named as the serialized fields
+const CSV_COLUMNS: [&str; 11] = ["name", "points", "timestamp", "difficulty", "gifts_delivered",
+    "gift_points", "damage_points", "remaining_seconds", "game_version", "seed", "level"];
+const CSV_STRING_COLUMNS: [&str; 5] = ["name", "difficulty", "game_version", "seed", "level"];
+
+pub type HighscoreTables = std::collections::BTreeMap<String, Vec<Highscore>>;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum HighscoreFileFormat {
+  Json,
+  Csv,
+}
+
+#[derive(Serialize, Deserialize)]
+struct HighscoreFile {
+  version: u32,
+  game_version: String,
+  tables: HighscoreTables,
+}
+
+impl HighscoreFileFormat {
+  pub fn from_name(name: &str) -> Option<HighscoreFileFormat> {
+    return match name.to_lowercase().as_str() {
+      "json" => Some(HighscoreFileFormat::Json),
+      "csv" => Some(HighscoreFileFormat::Csv),
+      _ => None,
+    };
+  }
+
+  // JSON is used for files without a known extension
+  pub fn from_path(file_path: &std::path::Path) -> HighscoreFileFormat {
+    return file_path.extension().and_then(|x| x.to_str())
+        .and_then(HighscoreFileFormat::from_name).unwrap_or(HighscoreFileFormat::Json);
+  }
+}
+
+pub fn export_highscores(file_path: &std::path::Path, format: HighscoreFileFormat,
+      tables: &HighscoreTables) -> Result<(), String> {
+  let tables: HighscoreTables = tables.iter().map(|(key, highscores)| (key.to_string(),
+      highscores.iter().filter(|x| !x.is_placeholder()).cloned().collect())).collect();
+
+  let contents = match format {
+    HighscoreFileFormat::Json => serde_json::to_string_pretty(&HighscoreFile{
+      version: HIGHSCORE_FILE_FORMAT_VERSION,
+      game_version: env!("CARGO_PKG_VERSION").to_string(),
+      tables: tables,
+    }).map_err(|error| error.to_string()),
+    HighscoreFileFormat::Csv => format_csv(&tables),
+  }.map_err(|error| format!("Could not serialize highscores: {}", error))?;
+
+  std::fs::write(file_path, contents).map_err(|error| format!(
+      "Could not write highscore file '{}': {}", file_path.display(), error))?;
+  return Ok(());
+}
+
+pub fn import_highscores(file_path: &std::path::Path, format: HighscoreFileFormat)
+      -> Result<HighscoreTables, String> {
+  let contents = std::fs::read_to_string(file_path).map_err(|error| format!(
+      "Could not read highscore file '{}': {}", file_path.display(), error))?;
+
+  let mut tables = match format {
+    HighscoreFileFormat::Json => parse_json(&contents),
+    HighscoreFileFormat::Csv => parse_csv(&contents),
+  }.map_err(|error| format!("Invalid highscore file '{}': {}", file_path.display(), error))?;
+  migrate_table_keys(&mut tables);
+
+  for (key, highscores) in &tables {
+    for (i, highscore) in highscores.iter().enumerate() {
+      validate_highscore(key, highscore).map_err(|error| format!(
+          "Invalid highscore file '{}': entry {} of table '{}' {}",
+          file_path.display(), i + 1, key, error))?;
+    }
+  }
+
+  // replays are not part of highscore files
+  for highscore in tables.values_mut().flatten() { highscore.remove_signature(); }
+
+  return Ok(tables);
+}
+
+// moves the tables of versions before multiple levels, e.g., "easy", to the default level
+pub fn migrate_table_keys(tables: &mut HighscoreTables) {
+  for &difficulty in game::GameDifficulty::ALL.iter() {
+    let new_key = highscore::table_key(level::DEFAULT_LEVEL_NAME, difficulty);
+
+    if !tables.contains_key(&new_key) {
+      if let Some(highscores) = tables.remove(highscore::difficulty_key(difficulty)) {
+        tables.insert(new_key, highscores);
+      }
+    }
+  }
+}
+
+// merges the entries into the table, sorted by points, without duplicates and placeholders
+pub fn merge_highscores(highscores: &mut Vec<Highscore>, imported_highscores: &[Highscore],
+      number_of_highscores: usize) {
+  let mut merged_highscores: Vec<Highscore> = Vec::new();
+
+  for highscore in highscores.iter().chain(imported_highscores.iter()) {
+    // existing signed entries are kept instead of imported unsigned copies
+    if !highscore.is_placeholder() && !merged_highscores.iter().any(
+          |x| without_signature(x) == without_signature(highscore)) {
+      merged_highscores.push(highscore.clone());
+    }
+  }
+
+  // stable sort keeps the existing entries before imported ones with the same points
+  merged_highscores.sort_by(|x, y| y.points().cmp(&x.points()));
+  merged_highscores.truncate(number_of_highscores);
+  *highscores = merged_highscores;
+}
+
+fn without_signature(highscore: &Highscore) -> Highscore {
+  let mut highscore = highscore.clone();
+  highscore.remove_signature();
+  return highscore;
+}
+
+fn validate_highscore(key: &str, highscore: &Highscore) -> Result<(), String> {
+  let (level_name, difficulty) = highscore::parse_table_key(key)
+      .ok_or_else(|| "belongs to an unknown table".to_string())?;
+
+  if highscore.name().trim().is_empty() || highscore.name().chars().any(char::is_control) {
+    return Err(format!("has invalid name '{}'", highscore.name()));
+  }
+
+  if highscore.points() < 0 {
+    return Err(format!("has negative points {}", highscore.points()));
+  }
+
+  if highscore.difficulty().map_or(false, |x| x != difficulty) {
+    return Err("has a difficulty different from its table".to_string());
+  }
+
+  if highscore.level() != level_name {
+    return Err(format!("has a level '{}' different from its table", highscore.level()));
+  }
+
+  for &value in [highscore.gift_points(), highscore.damage_points(),
+        highscore.remaining_seconds()].iter() {
+    if !value.is_finite() || (value < 0.0) {
+      return Err(format!("has invalid value {}", value));
+    }
+  }
+
+  return Ok(());
+}
+
+fn parse_json(contents: &str) -> Result<HighscoreTables, String> {
+  let file: HighscoreFile = serde_json::from_str(contents).map_err(|error| error.to_string())?;
+
+  if file.version != HIGHSCORE_FILE_FORMAT_VERSION {
+    return Err(format!("unsupported version {}, expected version {}",
+        file.version, HIGHSCORE_FILE_FORMAT_VERSION));
+  }
+
+  return Ok(file.tables);
+}
+
+fn format_csv(tables: &HighscoreTables) -> Result<String, String> {
+  let mut contents = format!("table,{}\n", CSV_COLUMNS.join(","));
+
+  for (key, highscores) in tables {
+    for highscore in highscores {
+      let value = serde_json::to_value(highscore).map_err(|error| error.to_string())?;
+      let mut fields = vec![format_csv_field(key)];
+
+      for column in CSV_COLUMNS.iter() {
+        fields.push(format_csv_field(&match &value[column] {
+          serde_json::Value::Null => String::new(),
+          serde_json::Value::String(string) => string.to_string(),
+          value => value.to_string(),
+        }));
+      }
+
+      contents += &fields.join(",");
+      contents += "\n";
+    }
+  }
+
+  return Ok(contents);
+}
+
+fn format_csv_field(field: &str) -> String {
+  return if field.contains(|x| (x == ',') || (x == '"') || (x == '\n') || (x == '\r')) {
+    format!("\"{}\"", field.replace('"', "\"\""))
+  } else {
+    field.to_string()
+  };
+}
+
+fn parse_csv(contents: &str) -> Result<HighscoreTables, String> {
+  let mut records = parse_csv_records(contents)?.into_iter();
+  let header = records.next().ok_or_else(|| "missing CSV header".to_string())?;
+
+  for column in header.iter() {
+    if (column != "table") && !CSV_COLUMNS.contains(&column.as_str()) {
+      return Err(format!("unknown CSV column '{}'", column));
+    }
+  }
+
+  for &column in ["table", "name", "points"].iter() {
+    if !header.iter().any(|x| x == column) {
+      return Err(format!("missing CSV column '{}'", column));
+    }
+  }
+
+  let mut tables = HighscoreTables::new();
+
+  for (i, record) in records.enumerate() {
+    let row = i + 2;
+
+    if record.len() != header.len() {
+      return Err(format!("row {} has {} fields, expected {}", row, record.len(),
+          header.len()));
+    }
+
+    let mut key = String::new();
+    let mut value = serde_json::Map::new();
+
+    for (column, field) in header.iter().zip(record.into_iter()) {
+      if column == "table" {
+        key = field;
+      } else if CSV_STRING_COLUMNS.contains(&column.as_str()) {
+        if !field.is_empty() || (column == "name") {
+          value.insert(column.to_string(), serde_json::Value::String(field));
+        }
+      } else if !field.is_empty() {
+        let number: serde_json::Number = serde_json::from_str(&field).map_err(
+            |_| format!("row {} has invalid number '{}' in column '{}'", row, field, column))?;
+        value.insert(column.to_string(), serde_json::Value::Number(number));
+      }
+    }
+
+    let highscore: Highscore = serde_json::from_value(serde_json::Value::Object(value))
+        .map_err(|error| format!("row {} is invalid: {}", row, error))?;
+    tables.entry(key).or_insert_with(Vec::new).push(highscore);
+  }
+
+  return Ok(tables);
+}
+
+// splits RFC 4180 CSV into records of fields, skipping empty lines
+fn parse_csv_records(contents: &str) -> Result<Vec<Vec<String>>, String> {
+  let mut records: Vec<Vec<String>> = Vec::new();
+  let mut record: Vec<String> = Vec::new();
+  let mut field = String::new();
+  let mut quoted = false;
+  let mut characters = contents.chars().peekable();
+
+  while let Some(character) = characters.next() {
+    if quoted {
+      if (character == '"') && (characters.peek() == Some(&'"')) {
+        field.push('"');
+        characters.next();
+      } else if character == '"' {
+        quoted = false;
+      } else {
+        field.push(character);
+      }
+    } else if (character == '"') && field.is_empty() {
+      quoted = true;
+    } else if character == ',' {
+      record.push(std::mem::replace(&mut field, String::new()));
+    } else if (character == '\n') || (character == '\r') {
+      if (character == '\r') && (characters.peek() == Some(&'\n')) { characters.next(); }
+      record.push(std::mem::replace(&mut field, String::new()));
+      if (record.len() > 1) || !record[0].is_empty() { records.push(record); }
+      record = Vec::new();
+    } else {
+      field.push(character);
+    }
+  }
+
+  if quoted { return Err("unterminated quoted CSV field".to_string()); }
+
+  if !field.is_empty() || !record.is_empty() {
+    record.push(field);
+    records.push(record);
+  }
+
+  return Ok(records);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn create_highscore(name: &str, points: i32, difficulty: &str) -> Highscore {
+    return serde_json::from_value(serde_json::json!({
+      "name": name, "points": points, "timestamp": 1608249600, "difficulty": difficulty,
+      "gifts_delivered": 12, "gift_points": 180.0, "damage_points": 65.5,
+      "remaining_seconds": 123.25, "game_version": "1.0.2", "seed": "18446744073709551615",
+      "level": "level2",
+    })).unwrap();
+  }
+
+  fn create_tables() -> HighscoreTables {
+    let mut tables = HighscoreTables::new();
+    tables.insert("level2-easy".to_string(), vec![
+        create_highscore("Rudolph, the \"Red\"", 300, "Easy"),
+        create_highscore("Knecht Ruprecht", 200, "Easy"), Highscore::new("", 0)]);
+    tables.insert("level2-hard".to_string(), vec![create_highscore("\u{00c4}rger im \"Stall\"", 100,
+        "Hard")]);
+    return tables;
+  }
+
+  fn export_and_import(name: &str, format: HighscoreFileFormat, tables: &HighscoreTables)
+        -> Result<HighscoreTables, String> {
+    let file_path = std::env::temp_dir().join(format!("santa-racer-{}-{}-{:?}", name,
+        std::process::id(), format));
+    export_highscores(&file_path, format, tables)?;
+    let result = import_highscores(&file_path, format);
+    std::fs::remove_file(&file_path).unwrap();
+    return result;
+  }
+
+  #[test]
+  fn csv_records_are_split_as_in_rfc_4180() {
+    let records = parse_csv_records(
+        "a,\"b,c\",\"say \"\"hi\"\"\"\r\n\r\n\"multi\nline\",,x\ny").unwrap();
+    assert_eq!(records, vec![vec!["a", "b,c", "say \"hi\""], vec!["multi\nline", "", "x"],
+        vec!["y"]]);
+
+    assert_eq!(parse_csv_records("a,b\r\nc,d\r\n").unwrap(), vec![vec!["a", "b"],
+        vec!["c", "d"]]);
+    assert_eq!(parse_csv_records("a,b\rc,d").unwrap(), vec![vec!["a", "b"], vec!["c", "d"]]);
+    assert!(parse_csv_records("a,\"b\nc,d").is_err());
+  }
+
+  #[test]
+  fn csv_with_wrong_field_counts_is_rejected() {
+    let header = "table,name,points\r\n";
+    assert!(parse_csv(&format!("{}level1-easy,Rudolph,100\r\n", header)).is_ok());
+    assert!(parse_csv(&format!("{}easy,Rudolph\r\n", header)).unwrap_err()
+        .contains("row 2 has 2 fields, expected 3"));
+    assert!(parse_csv(&format!("{}easy,Rudolph,100\r\nhard,\"Comet, Cupid\",5,6\r\n", header))
+        .unwrap_err().contains("row 3 has 4 fields, expected 3"));
+    assert!(parse_csv("table,name\r\neasy,Rudolph\r\n").unwrap_err()
+        .contains("missing CSV column 'points'"));
+    assert!(parse_csv("table,name,points,color\r\n").unwrap_err()
+        .contains("unknown CSV column 'color'"));
+    assert!(parse_csv(&format!("{}easy,Rudolph,many\r\n", header)).unwrap_err()
+        .contains("invalid number 'many'"));
+  }
+
+  #[test]
+  fn invalid_entries_are_rejected() {
+    assert!(validate_highscore("level2-easy", &create_highscore("Rudolph", 100, "Easy")).is_ok());
+    assert!(validate_highscore("level1-hard", &Highscore::new("Rudolph", 100)).is_ok());
+    assert!(validate_highscore("level1-medium", &Highscore::new("Rudolph", 100)).is_err());
+    assert!(validate_highscore("easy", &Highscore::new("Rudolph", 100)).is_err());
+    assert!(validate_highscore("level1-easy", &Highscore::new(" ", 100)).is_err());
+    assert!(validate_highscore("level1-easy", &Highscore::new("Rudolph\n", 100)).is_err());
+    assert!(validate_highscore("level1-easy", &Highscore::new("Rudolph", -1)).is_err());
+    assert!(validate_highscore("level2-hard", &create_highscore("Rudolph", 100, "Easy")).is_err());
+    assert!(validate_highscore("level1-easy", &create_highscore("Rudolph", 100, "Easy"))
+        .unwrap_err().contains("level 'level2'"));
+
+    let mut value = serde_json::to_value(create_highscore("Rudolph", 100, "Easy")).unwrap();
+    value["remaining_seconds"] = serde_json::json!(-1.0);
+    let highscore: Highscore = serde_json::from_value(value).unwrap();
+    assert!(validate_highscore("level2-easy", &highscore).is_err());
+
+    let mut highscore = Highscore::from_campaign(game::GameDifficulty::Hard,
+        &[create_highscore("", 100, "Hard"), create_highscore("", 200, "Hard")]);
+    highscore.set_name("Rudolph");
+    assert!(validate_highscore("campaign-hard", &highscore).is_ok());
+    assert!(validate_highscore("level2-hard", &highscore).is_err());
+  }
+
+  #[test]
+  fn tables_of_older_versions_are_migrated_to_default_level() {
+    let mut tables = HighscoreTables::new();
+    tables.insert("easy".to_string(), vec![Highscore::new("Rudolph", 100)]);
+    tables.insert("hard".to_string(), vec![Highscore::new("Comet", 50)]);
+    tables.insert("level1-hard".to_string(), vec![Highscore::new("Cupid", 80)]);
+    migrate_table_keys(&mut tables);
+
+    assert_eq!(tables.keys().collect::<Vec<_>>(), vec!["hard", "level1-easy", "level1-hard"]);
+    assert_eq!(tables["level1-easy"][0].name(), "Rudolph");
+    assert_eq!(tables["level1-hard"][0].name(), "Cupid");
+  }
+
+  #[test]
+  fn export_and_import_round_trip() {
+    let tables = create_tables();
+
+    for &format in [HighscoreFileFormat::Json, HighscoreFileFormat::Csv].iter() {
+      let imported_tables = export_and_import("round-trip", format, &tables).unwrap();
+      assert_eq!(imported_tables.keys().collect::<Vec<_>>(), vec!["level2-easy", "level2-hard"]);
+      // placeholders are not exported
+      assert!(imported_tables["level2-easy"][..] == tables["level2-easy"][.. 2]);
+      assert!(imported_tables["level2-hard"] == tables["level2-hard"]);
+    }
+  }
+
+  #[test]
+  fn imported_entries_are_unsigned() {
+    let mut tables = create_tables();
+    let replay = replay::Replay::new(1, game::GameDifficulty::Easy, "level2");
+    tables.get_mut("level2-easy").unwrap()[0].set_replay_file("level2-easy-1.json");
+    tables.get_mut("level2-easy").unwrap()[0].sign(b"key", &replay);
+    assert!(tables["level2-easy"][0].is_signed());
+
+    for &format in [HighscoreFileFormat::Json, HighscoreFileFormat::Csv].iter() {
+      let imported_tables = export_and_import("unsigned", format, &tables).unwrap();
+      assert!(!imported_tables["level2-easy"][0].is_signed());
+      assert!(imported_tables["level2-easy"][0] == without_signature(&tables["level2-easy"][0]));
+    }
+  }
+
+  #[test]
+  fn merge_removes_duplicates_and_prefers_signed_entries() {
+    let replay = replay::Replay::new(1, game::GameDifficulty::Easy, "level2");
+    let mut signed_highscore = create_highscore("Rudolph", 300, "Easy");
+    signed_highscore.set_replay_file("level2-easy-1.json");
+    signed_highscore.sign(b"key", &replay);
+    let mut highscores = vec![signed_highscore.clone(), create_highscore("Comet", 100, "Easy"),
+        Highscore::new("", 0), Highscore::new("", 0)];
+
+    let imported_highscores = vec![without_signature(&signed_highscore),
+        create_highscore("Cupid", 200, "Easy"), create_highscore("Comet", 100, "Easy"),
+        create_highscore("Vixen", 100, "Easy"), Highscore::new("", 0)];
+    merge_highscores(&mut highscores, &imported_highscores, 3);
+
+    let names: Vec<String> = highscores.iter().map(|x| x.name()).collect();
+    assert_eq!(names, vec!["Rudolph", "Cupid", "Comet"]);
+    assert!(highscores[0].is_signed());
+  }
+}
@@ -0,0 +1,93 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use serde::Serialize;
+use serde::Deserialize;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Debug)]
+pub struct TickInput {
+  left: bool,
+  right: bool,
+  up: bool,
+  down: bool,
+  // analog steering in [-1, 1], only used if no digital direction is pressed
+  #[serde(default)]
+  steer_x: f64,
+  #[serde(default)]
+  steer_y: f64,
+  drop_gift: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+  SteerLeft,
+  SteerRight,
+  SteerUp,
+  SteerDown,
+  DropGift,
+  Pause,
+  Menu,
+  Confirm,
+  Help1,
+  Help2,
+  Highscores,
+  Options,
+  StartEasy,
+  StartHard,
+  Mute,
+  Levels,
+}
+
+impl Action {
+  pub const ALL: [Action; 16] = [Action::SteerLeft, Action::SteerRight, Action::SteerUp,
+      Action::SteerDown, Action::DropGift, Action::Pause, Action::Menu, Action::Confirm,
+      Action::Help1, Action::Help2, Action::Highscores, Action::Options, Action::StartEasy,
+      Action::StartHard, Action::Mute, Action::Levels];
+}
+
+impl TickInput {
+  pub fn new(left: bool, right: bool, up: bool, down: bool, steer_x: f64, steer_y: f64,
+        drop_gift: bool) -> TickInput {
+    return TickInput{
+      left: left,
+      right: right,
+      up: up,
+      down: down,
+      steer_x: steer_x.max(-1.0).min(1.0),
+      steer_y: steer_y.max(-1.0).min(1.0),
+      drop_gift: drop_gift,
+    };
+  }
+
+  pub fn left(&self) -> bool {
+    return self.left;
+  }
+
+  pub fn right(&self) -> bool {
+    return self.right;
+  }
+
+  pub fn up(&self) -> bool {
+    return self.up;
+  }
+
+  pub fn down(&self) -> bool {
+    return self.down;
+  }
+
+  pub fn steer_x(&self) -> f64 {
+    return self.steer_x;
+  }
+
+  pub fn steer_y(&self) -> f64 {
+    return self.steer_y;
+  }
+
+  pub fn drop_gift(&self) -> bool {
+    return self.drop_gift;
+  }
+}
@@ -0,0 +1,333 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::*;
+use crate::highscore::Highscore;
+
+// storage of highscores, either the local tables or a remote server
+pub trait LeaderboardBackend {
+  // returns the index of the entry if it is listed immediately
+  fn submit(&mut self, table_key: &str, highscore: &Highscore)
+      -> Result<Option<usize>, String>;
+
+  // entries as currently known, remote backends update them in the background
+  fn fetch(&mut self, table_key: &str) -> Result<Vec<Highscore>, String>;
+}
+
+// client of a server with the endpoints "GET <url>/scores/<table>", which returns a JSON array
+// of highscores, and "POST <url>/scores/<table>", which accepts a JSON highscore, where <table>
+// is the table key, e.g., "level1-easy";
+// requests run in a worker thread, so that the game never waits for the network
+pub struct HttpLeaderboard {
+  requests: std::sync::mpsc::Sender<Request>,
+  responses: std::sync::mpsc::Receiver<Response>,
+  verbose: bool,
+
+  tables: std::collections::BTreeMap<String, Vec<Highscore>>,
+  fetch_instants: std::collections::BTreeMap<String, std::time::Instant>,
+  // errors of the last fetches of tables that failed
+  fetch_errors: std::collections::BTreeMap<String, String>,
+  refresh_interval: std::time::Duration,
+}
+
+enum Request {
+  Submit(String, Highscore),
+  Fetch(String),
+}
+
+enum Response {
+  Fetched(String, Result<Vec<Highscore>, String>),
+  // also sent if the queue could not be written
+  SubmitFailed(String),
+}
+
+// submissions that could not be sent yet, persisted to survive restarts
+struct SubmissionQueue {
+  file_path: std::path::PathBuf,
+  entries: Vec<(String, Highscore)>,
+  retry_instant: std::time::Instant,
+  retry_delay: std::time::Duration,
+  min_retry_delay: std::time::Duration,
+  max_retry_delay: std::time::Duration,
+}
+
+impl HttpLeaderboard {
+  pub fn new(url: &str, queue_file_path: std::path::PathBuf, verbose: bool) -> HttpLeaderboard {
+    let (request_sender, request_receiver) = std::sync::mpsc::channel();
+    let (response_sender, response_receiver) = std::sync::mpsc::channel();
+    let url = url.trim_end_matches('/').to_string();
+
+    std::thread::spawn(move || {
+      HttpLeaderboard::run_worker(&url, queue_file_path, request_receiver, response_sender);
+    });
+
+    return HttpLeaderboard{
+      requests: request_sender,
+      responses: response_receiver,
+      verbose: verbose,
+
+      tables: std::collections::BTreeMap::new(),
+      fetch_instants: std::collections::BTreeMap::new(),
+      fetch_errors: std::collections::BTreeMap::new(),
+      refresh_interval: std::time::Duration::from_secs(60),
+    };
+  }
+
+  fn run_worker(url: &str, queue_file_path: std::path::PathBuf,
+        requests: std::sync::mpsc::Receiver<Request>,
+        responses: std::sync::mpsc::Sender<Response>) {
+    let agent = ureq::AgentBuilder::new().timeout(std::time::Duration::from_secs(10)).build();
+    let mut queue = SubmissionQueue::load(queue_file_path);
+
+    loop {
+      let timeout = if queue.entries.is_empty() { std::time::Duration::from_secs(3600) }
+          else { queue.retry_instant.saturating_duration_since(std::time::Instant::now()) };
+
+      match requests.recv_timeout(timeout) {
+        Ok(Request::Submit(key, highscore)) => {
+          if let Err(error) = queue.push(key, highscore) {
+            if responses.send(Response::SubmitFailed(error)).is_err() { return; }
+          }
+        },
+        Ok(Request::Fetch(key)) => {
+          let result = HttpLeaderboard::fetch_now(&agent, url, &key);
+          if responses.send(Response::Fetched(key, result)).is_err() { return; }
+        },
+        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {},
+        Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => return,
+      }
+
+      if let Err(error) = queue.send(|key, highscore| {
+        HttpLeaderboard::submit_now(&agent, url, key, highscore)
+      }) {
+        if responses.send(Response::SubmitFailed(error)).is_err() { return; }
+      }
+    }
+  }
+
+  fn fetch_now(agent: &ureq::Agent, url: &str, key: &str) -> Result<Vec<Highscore>, String> {
+    let response = agent.get(&format!("{}/scores/{}", url, key)).call()
+        .map_err(|error| format!("Could not fetch highscores from '{}': {}", url, error))?;
+    return response.into_json().map_err(
+        |error| format!("Invalid highscores from '{}': {}", url, error));
+  }
+
+  // errors with status 4xx are permanent, all other errors are retried
+  fn submit_now(agent: &ureq::Agent, url: &str, key: &str,
+        highscore: &Highscore) -> Result<(), (String, bool)> {
+    let body = serde_json::to_value(highscore).expect("Could not serialize highscore");
+
+    return match agent.post(&format!("{}/scores/{}", url, key)).send_json(body) {
+      Ok(_) => Ok(()),
+      Err(ureq::Error::Status(status, _)) => Err((format!(
+          "Server '{}' rejected highscore with status {}", url, status),
+          (400 .. 500).contains(&status))),
+      Err(error) => Err((format!("Could not submit highscore to '{}': {}", url, error), false)),
+    };
+  }
+
+  fn process_responses(&mut self) {
+    while let Ok(response) = self.responses.try_recv() {
+      match response {
+        Response::Fetched(key, Ok(highscores)) => {
+          self.fetch_errors.remove(&key);
+          self.tables.insert(key, highscores);
+        },
+        Response::Fetched(key, Err(error)) => {
+          if self.verbose { println!("{}", error); }
+          self.fetch_errors.insert(key, error);
+        },
+        Response::SubmitFailed(error) => if self.verbose { println!("{}", error); },
+      }
+    }
+  }
+}
+
+impl LeaderboardBackend for HttpLeaderboard {
+  fn submit(&mut self, table_key: &str, highscore: &Highscore)
+        -> Result<Option<usize>, String> {
+    // signatures are only meaningful on the local machine
+    let mut highscore = highscore.clone();
+    highscore.remove_signature();
+
+    self.fetch_instants.remove(table_key);
+    self.requests.send(Request::Submit(table_key.to_string(), highscore))
+        .map_err(|_| "Leaderboard worker has stopped".to_string())?;
+    return Ok(None);
+  }
+
+  fn fetch(&mut self, table_key: &str) -> Result<Vec<Highscore>, String> {
+    self.process_responses();
+
+    let now = std::time::Instant::now();
+
+    if self.fetch_instants.get(table_key).map_or(true, |&x| now >= x + self.refresh_interval) {
+      self.fetch_instants.insert(table_key.to_string(), now);
+      let _ = self.requests.send(Request::Fetch(table_key.to_string()));
+    }
+
+    return match (self.tables.get(table_key), self.fetch_errors.get(table_key)) {
+      (Some(highscores), _) => Ok(highscores.clone()),
+      (None, Some(_)) => Err(locale::Message::LeaderboardUnavailable.text().to_string()),
+      (None, None) => Err(locale::Message::LeaderboardLoading.text().to_string()),
+    };
+  }
+}
+
+impl SubmissionQueue {
+  fn load(file_path: std::path::PathBuf) -> SubmissionQueue {
+    let entries = std::fs::read(&file_path).ok()
+        .and_then(|x| serde_json::from_slice(&x).ok()).unwrap_or_default();
+    let min_retry_delay = std::time::Duration::from_secs(5);
+
+    return SubmissionQueue{
+      file_path: file_path,
+      entries: entries,
+      retry_instant: std::time::Instant::now(),
+      retry_delay: min_retry_delay,
+      min_retry_delay: min_retry_delay,
+      max_retry_delay: std::time::Duration::from_secs(300),
+    };
+  }
+
+  fn push(&mut self, key: String, highscore: Highscore) -> Result<(), String> {
+    self.entries.push((key, highscore));
+    return self.save();
+  }
+
+  // sends entries in order until one fails, retrying later with exponential backoff;
+  // an error writing the queue is returned instead of the error of the submission
+  fn send<F>(&mut self, mut submit: F) -> Result<(), String>
+        where F: FnMut(&str, &Highscore) -> Result<(), (String, bool)> {
+    if self.entries.is_empty() || (std::time::Instant::now() < self.retry_instant) {
+      return Ok(());
+    }
+
+    let mut result = Ok(());
+
+    while let Some((key, highscore)) = self.entries.first() {
+      match submit(key, highscore) {
+        Ok(()) => {
+          self.entries.remove(0);
+        },
+        Err((error, true)) => {
+          self.entries.remove(0);
+          result = Err(error);
+        },
+        Err((error, false)) => {
+          self.retry_instant = std::time::Instant::now() + self.retry_delay;
+          self.retry_delay = (self.retry_delay * 2).min(self.max_retry_delay);
+          self.save()?;
+          return Err(error);
+        },
+      }
+    }
+
+    self.retry_delay = self.min_retry_delay;
+    self.save()?;
+    return result;
+  }
+
+  fn save(&self) -> Result<(), String> {
+    if self.entries.is_empty() {
+      let _ = std::fs::remove_file(&self.file_path);
+      return Ok(());
+    }
+
+    let contents = serde_json::to_vec(&self.entries).expect("Could not serialize highscores");
+    let dir_path = self.file_path.parent().unwrap_or_else(|| std::path::Path::new("."));
+
+    return std::fs::create_dir_all(dir_path)
+        .and_then(|_| std::fs::write(&self.file_path, contents)).map_err(|error| format!(
+          "Could not write leaderboard queue '{}': {}", self.file_path.display(), error));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn create_queue(name: &str) -> SubmissionQueue {
+    let file_path = std::env::temp_dir().join(format!("santa-racer-queue-{}-{}.json", name,
+        std::process::id()));
+    let _ = std::fs::remove_file(&file_path);
+    return SubmissionQueue::load(file_path);
+  }
+
+  #[test]
+  fn failed_submissions_are_retried_with_backoff() {
+    let mut queue = create_queue("backoff");
+    queue.push("level1-easy".to_string(), Highscore::new("Rudolph", 100)).unwrap();
+    let mut number_of_submissions = 0;
+
+    for &expected_retry_delay in [10, 20, 40, 80, 160, 300, 300].iter() {
+      queue.retry_instant = std::time::Instant::now();
+      assert!(queue.send(|_, _| {
+        number_of_submissions += 1;
+        return Err(("offline".to_string(), false));
+      }).is_err());
+      assert_eq!(queue.retry_delay, std::time::Duration::from_secs(expected_retry_delay));
+    }
+
+    // nothing is sent before the retry instant
+    assert!(queue.send(|_, _| panic!("Sent before retry instant")).is_ok());
+    assert_eq!(number_of_submissions, 7);
+    assert_eq!(queue.entries.len(), 1);
+
+    queue.retry_instant = std::time::Instant::now();
+    assert!(queue.send(|_, _| Ok(())).is_ok());
+    assert!(queue.entries.is_empty());
+    assert_eq!(queue.retry_delay, queue.min_retry_delay);
+    assert!(!queue.file_path.exists());
+  }
+
+  #[test]
+  fn rejected_submissions_are_dropped() {
+    let mut queue = create_queue("rejected");
+    queue.push("level1-easy".to_string(), Highscore::new("Rudolph", 100)).unwrap();
+    queue.push("level1-hard".to_string(), Highscore::new("Comet", 50)).unwrap();
+    let mut submitted_keys = Vec::new();
+
+    let result = queue.send(|key, _| {
+      submitted_keys.push(key.to_string());
+      return if key == "level1-easy" { Err(("invalid".to_string(), true)) } else { Ok(()) };
+    });
+
+    assert_eq!(result, Err("invalid".to_string()));
+    assert_eq!(submitted_keys, vec!["level1-easy", "level1-hard"]);
+    assert!(queue.entries.is_empty());
+    assert!(!queue.file_path.exists());
+  }
+
+  #[test]
+  fn queue_survives_restart() {
+    let mut queue = create_queue("restart");
+    queue.push("level1-easy".to_string(), Highscore::new("Rudolph", 100)).unwrap();
+    assert!(queue.send(|_, _| Err(("offline".to_string(), false))).is_err());
+    assert!(queue.file_path.exists());
+
+    let mut queue = SubmissionQueue::load(queue.file_path.clone());
+    assert_eq!(queue.entries, vec![("level1-easy".to_string(), Highscore::new("Rudolph", 100))]);
+    assert!(queue.send(|_, _| Ok(())).is_ok());
+    assert!(!queue.file_path.exists());
+  }
+
+  #[test]
+  fn unwritable_queue_is_reported() {
+    // the parent of the queue file is a file, so that the directory cannot be created
+    let mut queue = create_queue("unwritable");
+    std::fs::write(&queue.file_path, "").unwrap();
+    queue.file_path = queue.file_path.join("queue.json");
+
+    let error = queue.push("level1-easy".to_string(), Highscore::new("Rudolph", 100))
+        .unwrap_err();
+    assert!(error.starts_with("Could not write leaderboard queue"), "Unexpected error: {}",
+        error);
+    assert_eq!(queue.send(|_, _| Err(("offline".to_string(), false))), Err(error));
+    std::fs::remove_file(queue.file_path.parent().unwrap()).unwrap();
+  }
+}
@@ -10,10 +10,14 @@ use rand::Rng;
 use crate::*;
 use crate::asset::Point;
 
+// level of versions before level files, also shown in the menu
+pub const DEFAULT_LEVEL_NAME: &str = "level1";
+
 pub struct Landscape<'a> {
-  image: &'a asset::Image<'a>,
+  image: &'a asset::Image,
 
   offset_x: f64,
+  previous_offset_x: f64,
   scrolling_resume_instant: std::time::Instant,
   last_update_instant: std::time::Instant,
 
@@ -22,19 +26,17 @@ pub struct Landscape<'a> {
 }
 
 pub struct Level<'a> {
-  image: &'a asset::Image<'a>,
+  name: String,
+  image: &'a asset::Image,
   tile_map: Vec<Vec<f64>>,
-  npc_map: Vec<Vec<f64>>,
+  npc_map: Vec<Vec<Option<npc::NpcType>>>,
+  chimneys: Vec<gift::Chimney>,
   canvas_size: Point,
 
-  dog_sound: &'a asset::Sound,
-  bell_sound: &'a asset::Sound,
-  sleigh_collided_with_tile_sound1: &'a asset::Sound,
-  sleigh_collided_with_tile_sound2: &'a asset::Sound,
-
   game_mode: game::GameMode,
 
   offset_x: f64,
+  previous_offset_x: f64,
   scroll_speed_x: f64,
   game_start_instant: std::time::Instant,
   scrolling_resume_instant: std::time::Instant,
@@ -51,8 +53,6 @@ pub struct Level<'a> {
   min_scroll_speed_x: f64,
   max_scroll_speed_x: f64,
   menu_scroll_speed_x: f64,
-  dog_sound_volume: f64,
-  bell_sound_volume: f64,
   min_dog_sound_duration: std::time::Duration,
   max_dog_sound_duration: std::time::Duration,
   min_bell_sound_duration: std::time::Duration,
@@ -71,15 +71,17 @@ pub struct TileIterator {
 }
 
 impl<'a> Landscape<'a> {
-  pub fn new(asset_library: &'a asset::AssetLibrary<'a>) -> Landscape {
+  pub fn new(asset_library: &'a asset::AssetLibrary<'a>, clock: &dyn clock::GameClock) ->
+        Landscape<'a> {
     let image = asset_library.get_image("landscape");
 
     return Landscape{
       image: image,
 
       offset_x: 0.0,
-      scrolling_resume_instant: std::time::Instant::now(),
-      last_update_instant: std::time::Instant::now(),
+      previous_offset_x: 0.0,
+      scrolling_resume_instant: clock.now(),
+      last_update_instant: clock.now(),
 
       size: image.size(),
       scroll_speed_factor_x: 0.1,
@@ -88,23 +90,26 @@ impl<'a> Landscape<'a> {
 
   pub fn start_game(&mut self, game_start_instant: std::time::Instant) {
     self.offset_x = 0.0;
+    self.previous_offset_x = 0.0;
     self.scrolling_resume_instant = game_start_instant;
   }
 
-  pub fn start_menu(&mut self) {
+  pub fn start_menu(&mut self, clock: &dyn clock::GameClock) {
     self.offset_x = 0.0;
-    self.scrolling_resume_instant = std::time::Instant::now();
+    self.previous_offset_x = 0.0;
+    self.scrolling_resume_instant = clock.now();
   }
 
   pub fn pause_scrolling(&mut self, scrolling_resume_instant: std::time::Instant) {
     self.scrolling_resume_instant = scrolling_resume_instant;
   }
 
-  pub fn do_logic(&mut self, level: &level::Level) {
-    let now = std::time::Instant::now();
+  pub fn do_logic(&mut self, level: &level::Level, clock: &dyn clock::GameClock) {
+    let now = clock.now();
     let seconds_since_last_update = (now - self.last_update_instant).as_secs_f64();
 
     let scroll_speed_x = self.scroll_speed_factor_x * level.scroll_speed_x;
+    self.previous_offset_x = self.offset_x;
 
     if now > self.scrolling_resume_instant {
       self.offset_x = (self.offset_x + seconds_since_last_update * scroll_speed_x) %
@@ -114,68 +119,61 @@ impl<'a> Landscape<'a> {
     self.last_update_instant = now;
   }
 
-  pub fn draw<RenderTarget: sdl2::render::RenderTarget>(
-        &self, canvas: &mut sdl2::render::Canvas<RenderTarget>) {
-    self.image.draw_blit(canvas, sdl2::rect::Rect::new(self.offset_x as i32, 0,
-        (self.size.x() - self.offset_x) as u32, self.size.y() as u32),
+  pub fn draw(&self, renderer: &mut dyn render::Renderer, interpolation: f64) {
+    let offset_x = self.interpolated_offset_x(interpolation);
+    self.image.draw_blit(renderer, asset::Rect::new(offset_x as i32, 0,
+        (self.size.x() - offset_x) as u32, self.size.y() as u32),
         Point::zero(), 0.0);
-    self.image.draw_blit(canvas, sdl2::rect::Rect::new(0, 0,
-        self.offset_x as u32, self.size.y() as u32),
-        Point::new(self.size.x() - self.offset_x, 0.0), 0.0);
+    self.image.draw_blit(renderer, asset::Rect::new(0, 0,
+        offset_x as u32, self.size.y() as u32),
+        Point::new(self.size.x() - offset_x, 0.0), 0.0);
+  }
+
+  fn interpolated_offset_x(&self, interpolation: f64) -> f64 {
+    let previous_offset_x = if self.previous_offset_x > self.offset_x {
+          self.previous_offset_x - self.size.x()
+        } else {
+          self.previous_offset_x
+        };
+
+    return (previous_offset_x + interpolation * (self.offset_x - previous_offset_x))
+        .rem_euclid(self.size.x());
   }
 }
 
 impl<'a> Level<'a> {
-  pub fn new(asset_library: &'a asset::AssetLibrary<'a>, canvas_size: Point) -> Level {
-    let image = asset_library.get_image("level");
+  pub fn new(asset_library: &'a asset::AssetLibrary<'a>, name: &str, canvas_size: Point,
+        clock: &dyn clock::GameClock, rng: &mut rand::rngs::StdRng) -> Level<'a> {
+    let level_file = asset_library.get_level(name);
+    let image = asset_library.get_image(level_file.tileset());
     let tile_size = image.size();
-    let tile_map = Level::convert_data_to_map(
-      asset_library.get_data("levelTileMap").to_vec());
-    let npc_map = Level::convert_data_to_map(
-      asset_library.get_data("levelNpcMap").to_vec());
-
-    assert!(tile_map.len() == npc_map.len(),
-        "Lengths of background and foreground object maps are not equal");
-    assert!(tile_map.len() > 0, "Background object map is empty");
-
-    let number_of_tiles_x = tile_map[0].len();
-    let number_of_tiles_y = tile_map.len();
-
-    for tile_y in 0 .. number_of_tiles_y {
-      assert!(tile_map[tile_y].len() == number_of_tiles_x,
-          "Rows of background object map do not have equal length");
-      assert!(npc_map[tile_y].len() == number_of_tiles_x,
-          "Rows of foreground object map do not have equal length");
-    }
-
-    let start_offset_x = -200.0;
+    let (number_of_tiles_x, number_of_tiles_y) = level_file.number_of_tiles();
+    let start_offset_x = level_file.start_offset_x();
 
     let min_dog_sound_duration = std::time::Duration::from_secs_f64(10.0);
     let max_dog_sound_duration = std::time::Duration::from_secs_f64(20.0);
     let min_bell_sound_duration = std::time::Duration::from_secs_f64(10.0);
     let max_bell_sound_duration = std::time::Duration::from_secs_f64(20.0);
-    let now = std::time::Instant::now();
+    let now = clock.now();
 
     return Level{
+      name: name.to_string(),
       image: image,
-      tile_map: tile_map,
-      npc_map: npc_map,
+      tile_map: level_file.tile_map(),
+      npc_map: level_file.npc_map(),
+      chimneys: level_file.chimneys(),
       canvas_size: canvas_size,
 
-      dog_sound: asset_library.get_sound("dog"),
-      bell_sound: asset_library.get_sound("bell"),
-      sleigh_collided_with_tile_sound1: asset_library.get_sound("sleighCollidedWithLevelTile1"),
-      sleigh_collided_with_tile_sound2: asset_library.get_sound("sleighCollidedWithLevelTile2"),
-
       game_mode: game::GameMode::Menu,
 
       offset_x: start_offset_x,
+      previous_offset_x: start_offset_x,
       scroll_speed_x: 0.0,
       game_start_instant: now,
       scrolling_resume_instant: now,
-      dog_sound_instant: now + rand::thread_rng().gen_range(
+      dog_sound_instant: now + rng.gen_range(
           min_dog_sound_duration, max_dog_sound_duration),
-      bell_sound_instant: now + rand::thread_rng().gen_range(
+      bell_sound_instant: now + rng.gen_range(
           min_bell_sound_duration, max_bell_sound_duration),
       last_update_instant: now,
 
@@ -185,11 +183,9 @@ impl<'a> Level<'a> {
       number_of_tiles: (number_of_tiles_x, number_of_tiles_y),
       number_of_visible_tiles_x: (canvas_size.x() / tile_size.x() + 1.0) as usize,
       start_offset_x: start_offset_x,
-      min_scroll_speed_x: 40.0,
-      max_scroll_speed_x: 160.0,
-      menu_scroll_speed_x: 40.0,
-      dog_sound_volume: 0.5,
-      bell_sound_volume: 0.5,
+      min_scroll_speed_x: level_file.min_scroll_speed_x(),
+      max_scroll_speed_x: level_file.max_scroll_speed_x(),
+      menu_scroll_speed_x: level_file.menu_scroll_speed_x(),
       min_dog_sound_duration: min_dog_sound_duration,
       max_dog_sound_duration: max_dog_sound_duration,
       min_bell_sound_duration: min_bell_sound_duration,
@@ -198,37 +194,27 @@ impl<'a> Level<'a> {
     };
   }
 
-  fn convert_data_to_map(data: Vec<f64>) -> Vec<Vec<f64>> {
-    let number_of_tiles_y = 5;
-    let number_of_tiles_x = (data.len() as f64 / number_of_tiles_y as f64).ceil() as usize;
-    let mut map: Vec<Vec<f64>> = Vec::new();
-
-    for tile_y in 0 .. number_of_tiles_y {
-      let mut row: Vec<f64> = Vec::new();
-
-      for tile_x in 0 .. number_of_tiles_x {
-        let i = tile_x + tile_y * number_of_tiles_x;
-        row.push(if i < data.len() { data[i] } else { 0.0 });
-      }
-
-      map.push(row);
-    }
-
-    return map;
-  }
-
-  pub fn start_game(&mut self, game_start_instant: std::time::Instant) {
+  pub fn start_game(&mut self, game_start_instant: std::time::Instant,
+        clock: &dyn clock::GameClock, rng: &mut rand::rngs::StdRng) {
+    let now = clock.now();
     self.game_mode = game::GameMode::Running;
     self.offset_x = self.start_offset_x;
+    self.previous_offset_x = self.start_offset_x;
     self.game_start_instant = game_start_instant;
     self.scrolling_resume_instant = game_start_instant;
+    self.dog_sound_instant = now + rng.gen_range(
+        self.min_dog_sound_duration, self.max_dog_sound_duration);
+    self.bell_sound_instant = now + rng.gen_range(
+        self.min_bell_sound_duration, self.max_bell_sound_duration);
+    self.last_update_instant = now;
     self.npcs.clear();
   }
 
-  pub fn start_menu(&mut self) {
+  pub fn start_menu(&mut self, clock: &dyn clock::GameClock) {
     self.game_mode = game::GameMode::Menu;
     self.offset_x = self.start_offset_x;
-    self.scrolling_resume_instant = std::time::Instant::now();
+    self.previous_offset_x = self.start_offset_x;
+    self.scrolling_resume_instant = clock.now();
     self.npcs.clear();
   }
 
@@ -236,10 +222,12 @@ impl<'a> Level<'a> {
     self.scrolling_resume_instant = scrolling_resume_instant;
   }
 
-  pub fn do_logic(&mut self, asset_library: &'a asset::AssetLibrary<'a>, score: &mut ui::Score,
-        landscape: &mut level::Landscape, sleigh: &mut sleigh::Sleigh) {
-    let now = std::time::Instant::now();
+  pub fn do_logic(&mut self, asset_library: &'a asset::AssetLibrary<'a>,
+        events: &mut event::EventQueue, landscape: &mut level::Landscape,
+        sleigh: &mut sleigh::Sleigh, clock: &dyn clock::GameClock, rng: &mut rand::rngs::StdRng) {
+    let now = clock.now();
     let seconds_since_last_update = (now - self.last_update_instant).as_secs_f64();
+    self.previous_offset_x = self.offset_x;
 
     if self.game_mode == game::GameMode::Menu {
       self.scroll_speed_x = self.menu_scroll_speed_x;
@@ -254,39 +242,35 @@ impl<'a> Level<'a> {
     }
 
     if now >= self.dog_sound_instant {
-      self.dog_sound.play_with_volume(self.dog_sound_volume);
-      self.dog_sound_instant = now + rand::thread_rng().gen_range(
+      events.push(event::GameEvent::DogBarked);
+      self.dog_sound_instant = now + rng.gen_range(
           self.min_dog_sound_duration, self.max_dog_sound_duration);
     }
 
     if now >= self.bell_sound_instant {
-      self.bell_sound.play_with_volume(self.bell_sound_volume);
-      self.bell_sound_instant = now + rand::thread_rng().gen_range(
+      events.push(event::GameEvent::BellRang);
+      self.bell_sound_instant = now + rng.gen_range(
           self.min_bell_sound_duration, self.max_bell_sound_duration);
     }
 
     if (self.game_mode == game::GameMode::Running) && !sleigh.counting_down()
           && !sleigh.immobile() {
       if self.sleigh_collides_with_tile(sleigh) {
-        let collided_with_level_sound = match rand::thread_rng().gen_range(0, 2) {
-          0 => self.sleigh_collided_with_tile_sound1,
-          _ => self.sleigh_collided_with_tile_sound2,
-        };
-
-        collided_with_level_sound.play_with_position(self.canvas_size, sleigh.position());
-        score.add_damage_points(self.sleigh_collided_with_tile_damage_points);
+        events.push(event::GameEvent::SleighHitTile{
+            damage_points: self.sleigh_collided_with_tile_damage_points,
+            position: sleigh.position()});
         landscape.pause_scrolling(now + sleigh.immobile_duration());
         self.pause_scrolling(now + sleigh.immobile_duration());
-        sleigh.start_invincible_and_immobile();
+        sleigh.start_invincible_and_immobile(clock);
       }
 
       for npc in &mut self.npcs {
-        npc.check_collision_with_sleigh(score, self.offset_x, sleigh);
+        npc.check_collision_with_sleigh(events, self.offset_x, sleigh, clock);
       }
     } else if self.game_mode == game::GameMode::Menu {
       for npc in &mut self.npcs {
         if npc.check_collision_with_sleigh_in_menu_mode() {
-          npc.check_collision_with_sleigh(score, self.offset_x, sleigh);
+          npc.check_collision_with_sleigh(events, self.offset_x, sleigh, clock);
         }
       }
     }
@@ -294,8 +278,10 @@ impl<'a> Level<'a> {
     let mut delete_npc: Vec<bool> = vec![true; self.npcs.len()];
 
     for (tile_x, tile_y) in self.visible_tiles_iter() {
-      let frame = self.npc_map[tile_y][tile_x];
-      if frame < 0.0 { continue; }
+      let npc_type = match self.npc_map[tile_y][tile_x] {
+        Some(npc_type) => npc_type,
+        None => continue,
+      };
       let tile = (tile_x, tile_y);
       let mut npc_found = false;
 
@@ -308,7 +294,7 @@ impl<'a> Level<'a> {
       }
 
       if npc_found { continue; }
-      self.npcs.push(npc::new_npc(asset_library, self, tile, frame));
+      self.npcs.push(npc::new_npc(asset_library, self, tile, npc_type, clock));
       delete_npc.push(false);
     }
 
@@ -328,7 +314,9 @@ impl<'a> Level<'a> {
     self.npcs.sort_unstable_by(|x, y| x.z_order().partial_cmp(&y.z_order()).expect(
         "Could not compare NPC z-orders"));
 
-    for npc in &mut self.npcs { npc.do_logic(self.offset_x, self.scroll_speed_x, sleigh); }
+    for npc in &mut self.npcs {
+      npc.do_logic(events, self.offset_x, self.scroll_speed_x, sleigh, clock, rng);
+    }
 
     self.last_update_instant = now;
   }
@@ -345,24 +333,30 @@ impl<'a> Level<'a> {
     return false;
   }
 
-  pub fn draw(&self, canvas: &mut sdl2::render::WindowCanvas) {
+  pub fn draw(&self, renderer: &mut dyn render::Renderer, interpolation: f64) {
+    let offset_x = self.interpolated_offset_x(interpolation);
+
     for npc in &self.npcs {
-      if npc.z_order() < 0.0 { npc.draw(canvas, self.offset_x); }
+      if npc.z_order() < 0.0 { npc.draw(renderer, offset_x); }
     }
 
     for (tile_x, tile_y) in self.visible_tiles_iter() {
       let frame = self.tile_map[tile_y][tile_x];
       if frame < 0.0 { continue; }
-      let dst_point = Point::new((tile_x as f64) * self.tile_size.x() - self.offset_x,
+      let dst_point = Point::new((tile_x as f64) * self.tile_size.x() - offset_x,
           (tile_y as f64) * self.tile_size.y());
-      self.image.draw(canvas, dst_point, frame);
+      self.image.draw(renderer, dst_point, frame);
     }
 
     for npc in &self.npcs {
-      if npc.z_order() >= 0.0 { npc.draw(canvas, self.offset_x); }
+      if npc.z_order() >= 0.0 { npc.draw(renderer, offset_x); }
     }
   }
 
+  pub fn interpolated_offset_x(&self, interpolation: f64) -> f64 {
+    return self.previous_offset_x + interpolation * (self.offset_x - self.previous_offset_x);
+  }
+
   pub fn visible_tiles_iter(&self) -> TileIterator {
     let min_tile_x = (self.offset_x / self.tile_size.x() - 1.0).max(0.0) as usize;
     let max_tile_x = (min_tile_x + self.number_of_visible_tiles_x + 2).min(self.number_of_tiles.0);
@@ -384,6 +378,15 @@ impl<'a> Level<'a> {
     return self.tile_map[tile_y][tile_x];
   }
 
+  // name of the level asset
+  pub fn name(&self) -> &str {
+    return &self.name;
+  }
+
+  pub fn chimneys(&self) -> &Vec<gift::Chimney> {
+    return &self.chimneys;
+  }
+
   pub fn canvas_size(&self) -> Point {
     return self.canvas_size;
   }
@@ -0,0 +1,342 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::io::BufRead;
+
+use serde::Serialize;
+use serde::Deserialize;
+
+use crate::*;
+use crate::asset::Point;
+use crate::npc::NpcType;
+
+const LEVEL_FORMAT_VERSION: u32 = 1;
+
+const LEGACY_CHIMNEY_HEIGHT: f64 = 20.0;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LevelFile {
+  version: u32,
+  name: String,
+  // name of the image asset whose frames are the tiles
+  tileset: String,
+  number_of_tiles_x: usize,
+  number_of_tiles_y: usize,
+  // negative offsets start the level left of the first tile
+  start_offset_x: f64,
+  min_scroll_speed_x: f64,
+  max_scroll_speed_x: f64,
+  menu_scroll_speed_x: f64,
+  chimneys: Vec<ChimneyDefinition>,
+  // rows of tileset frames from top to bottom, -1 for no tile
+  tile_layer: Vec<Vec<i32>>,
+  npc_layer: Vec<NpcPlacement>,
+}
+
+// hit box of a chimney relative to the top left corner of each tile with the tileset frame
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ChimneyDefinition {
+  tile: i32,
+  x: f64,
+  y: f64,
+  width: f64,
+  height: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct NpcPlacement {
+  tile_x: usize,
+  tile_y: usize,
+  #[serde(rename = "type")]
+  npc_type: NpcType,
+}
+
+// pretty-prints JSON, but keeps arrays nested in arrays (the rows of layers) on one line
+struct LevelFileFormatter<'a> {
+  pretty_formatter: serde_json::ser::PrettyFormatter<'a>,
+  array_depth: usize,
+}
+
+impl LevelFile {
+  pub fn load(file_path: &std::path::Path) -> Result<LevelFile, String> {
+    let file = std::fs::File::open(file_path).map_err(
+        |error| format!("Could not open level file '{}': {}", file_path.display(), error))?;
+    let level_file: LevelFile = serde_json::from_reader(std::io::BufReader::new(file)).map_err(
+        |error| format!("Could not parse level file '{}': {}", file_path.display(), error))?;
+
+    if level_file.version != LEVEL_FORMAT_VERSION {
+      return Err(format!("Unsupported version {} of level file '{}', expected version {}",
+          level_file.version, file_path.display(), LEVEL_FORMAT_VERSION));
+    }
+
+    level_file.validate().map_err(
+        |error| format!("Invalid level file '{}': {}", file_path.display(), error))?;
+    return Ok(level_file);
+  }
+
+  pub fn save(&self, file_path: &std::path::Path) -> Result<(), String> {
+    let file = std::fs::File::create(file_path).map_err(
+        |error| format!("Could not create level file '{}': {}", file_path.display(), error))?;
+    let mut writer = std::io::BufWriter::new(file);
+    let formatter = LevelFileFormatter{
+      pretty_formatter: serde_json::ser::PrettyFormatter::new(),
+      array_depth: 0,
+    };
+
+    self.serialize(&mut serde_json::Serializer::with_formatter(&mut writer, formatter))
+        .map_err(|error| error.to_string())
+        .and_then(|_| std::io::Write::write_all(&mut writer, b"\n")
+          .map_err(|error| error.to_string()))
+        .map_err(|error| format!("Could not write level file '{}': {}",
+          file_path.display(), error))?;
+    return Ok(());
+  }
+
+  // converts the whitespace-separated number files of the original game, i.e., the tile map
+  // and the NPC map (rows separated by blank lines) and the chimneys (four numbers x, y,
+  // width, tile each)
+  pub fn convert_legacy_files<S: Into<String>>(name: S, tile_map_file_path: &std::path::Path,
+        npc_map_file_path: &std::path::Path,
+        chimneys_file_path: &std::path::Path) -> Result<LevelFile, String> {
+    let tile_rows = load_legacy_data(tile_map_file_path)?;
+    let npc_rows = load_legacy_data(npc_map_file_path)?;
+    let chimney_data = load_legacy_data(chimneys_file_path)?.concat();
+    let number_of_tiles_y = tile_rows.len();
+    let number_of_tiles_x = tile_rows.first().map_or(0, |x| x.len());
+
+    if (number_of_tiles_x == 0) || tile_rows.iter().any(|x| x.len() != number_of_tiles_x) {
+      return Err(format!("Rows of '{}' are empty or differ in length",
+          tile_map_file_path.display()));
+    }
+
+    if (npc_rows.len() != number_of_tiles_y)
+          || npc_rows.iter().any(|x| x.len() != number_of_tiles_x) {
+      return Err(format!("Dimensions of '{}' differ from {}x{} of '{}'",
+          npc_map_file_path.display(), number_of_tiles_x, number_of_tiles_y,
+          tile_map_file_path.display()));
+    }
+
+    if chimney_data.len() % 4 != 0 {
+      return Err(format!("Number of entries {} of '{}' is not divisible by 4",
+          chimney_data.len(), chimneys_file_path.display()));
+    }
+
+    let tile_layer = tile_rows.iter()
+        .map(|row| row.iter().map(|&x| x as i32).collect()).collect();
+    let mut npc_layer = Vec::new();
+
+    for (tile_y, row) in npc_rows.iter().enumerate() {
+      for (tile_x, &frame) in row.iter().enumerate() {
+        if frame < 0.0 { continue; }
+        npc_layer.push(NpcPlacement{
+          tile_x: tile_x,
+          tile_y: tile_y,
+          npc_type: NpcType::from_legacy_frame(frame),
+        });
+      }
+    }
+
+    let chimneys = chimney_data.chunks(4).map(|x| ChimneyDefinition{
+      tile: x[3] as i32,
+      x: x[0],
+      y: x[1],
+      width: x[2],
+      height: LEGACY_CHIMNEY_HEIGHT,
+    }).collect();
+
+    let level_file = LevelFile{
+      version: LEVEL_FORMAT_VERSION,
+      name: name.into(),
+      tileset: "level".to_string(),
+      number_of_tiles_x: number_of_tiles_x,
+      number_of_tiles_y: number_of_tiles_y,
+      start_offset_x: -200.0,
+      min_scroll_speed_x: 40.0,
+      max_scroll_speed_x: 160.0,
+      menu_scroll_speed_x: 40.0,
+      chimneys: chimneys,
+      tile_layer: tile_layer,
+      npc_layer: npc_layer,
+    };
+
+    level_file.validate()?;
+    return Ok(level_file);
+  }
+
+  fn validate(&self) -> Result<(), String> {
+    if (self.number_of_tiles_x == 0) || (self.number_of_tiles_y == 0) {
+      return Err(format!("has invalid dimensions {}x{}",
+          self.number_of_tiles_x, self.number_of_tiles_y));
+    }
+
+    if self.tile_layer.len() != self.number_of_tiles_y {
+      return Err(format!("has {} rows of tiles, expected {}",
+          self.tile_layer.len(), self.number_of_tiles_y));
+    }
+
+    for (tile_y, row) in self.tile_layer.iter().enumerate() {
+      if row.len() != self.number_of_tiles_x {
+        return Err(format!("has {} tiles in row {}, expected {}",
+            row.len(), tile_y + 1, self.number_of_tiles_x));
+      }
+
+      if let Some(frame) = row.iter().find(|&&x| x < -1) {
+        return Err(format!("has invalid tile {} in row {}", frame, tile_y + 1));
+      }
+    }
+
+    let mut occupied_tiles = std::collections::HashSet::new();
+
+    for npc_placement in &self.npc_layer {
+      let tile = (npc_placement.tile_x, npc_placement.tile_y);
+
+      if (tile.0 >= self.number_of_tiles_x) || (tile.1 >= self.number_of_tiles_y) {
+        return Err(format!("has NPC outside of the level at tile ({}, {})", tile.0, tile.1));
+      }
+
+      if !occupied_tiles.insert(tile) {
+        return Err(format!("has more than one NPC at tile ({}, {})", tile.0, tile.1));
+      }
+    }
+
+    for chimney in &self.chimneys {
+      if (chimney.tile < 0) || !(chimney.width > 0.0) || !(chimney.height > 0.0) {
+        return Err(format!("has invalid chimney of tile {}", chimney.tile));
+      }
+    }
+
+    if !(self.min_scroll_speed_x > 0.0) || !(self.max_scroll_speed_x >= self.min_scroll_speed_x)
+          || !(self.menu_scroll_speed_x > 0.0) || !self.start_offset_x.is_finite() {
+      return Err("has invalid start offset or scroll speeds".to_string());
+    }
+
+    return Ok(());
+  }
+
+  pub fn name(&self) -> &str {
+    return &self.name;
+  }
+
+  pub fn tileset(&self) -> &str {
+    return &self.tileset;
+  }
+
+  pub fn number_of_tiles(&self) -> (usize, usize) {
+    return (self.number_of_tiles_x, self.number_of_tiles_y);
+  }
+
+  pub fn start_offset_x(&self) -> f64 {
+    return self.start_offset_x;
+  }
+
+  pub fn min_scroll_speed_x(&self) -> f64 {
+    return self.min_scroll_speed_x;
+  }
+
+  pub fn max_scroll_speed_x(&self) -> f64 {
+    return self.max_scroll_speed_x;
+  }
+
+  pub fn menu_scroll_speed_x(&self) -> f64 {
+    return self.menu_scroll_speed_x;
+  }
+
+  pub fn tile_map(&self) -> Vec<Vec<f64>> {
+    return self.tile_layer.iter().map(|row| row.iter().map(|&x| x as f64).collect()).collect();
+  }
+
+  pub fn npc_map(&self) -> Vec<Vec<Option<NpcType>>> {
+    let mut npc_map = vec![vec![None; self.number_of_tiles_x]; self.number_of_tiles_y];
+
+    for npc_placement in &self.npc_layer {
+      npc_map[npc_placement.tile_y][npc_placement.tile_x] = Some(npc_placement.npc_type);
+    }
+
+    return npc_map;
+  }
+
+  pub fn chimneys(&self) -> Vec<gift::Chimney> {
+    return self.chimneys.iter().map(|x| gift::Chimney::new(Point::new(x.x, x.y),
+        Point::new(x.width, x.height), x.tile as f64)).collect();
+  }
+}
+
+// returns the blocks of numbers separated by blank lines
+fn load_legacy_data(file_path: &std::path::Path) -> Result<Vec<Vec<f64>>, String> {
+  let file = std::fs::File::open(file_path).map_err(|error| format!(
+      "Could not open file '{}': {}", file_path.display(), error))?;
+  let reader = std::io::BufReader::new(file);
+  let mut data = vec![Vec::new()];
+
+  for line in reader.lines() {
+    let line = line.map_err(|error| format!(
+        "Could not read line from '{}': {}", file_path.display(), error))?;
+
+    if line.trim().is_empty() {
+      if !data.last().unwrap().is_empty() { data.push(Vec::new()); }
+      continue;
+    }
+
+    for entry in line.split(char::is_whitespace) {
+      if entry.is_empty() { continue; }
+      data.last_mut().unwrap().push(entry.parse().map_err(|_| format!(
+          "Could not parse '{}' in '{}' as number", entry, file_path.display()))?);
+    }
+  }
+
+  if data.last().unwrap().is_empty() { data.pop(); }
+  return Ok(data);
+}
+
+impl<'a> serde_json::ser::Formatter for LevelFileFormatter<'a> {
+  fn begin_array<W: ?Sized + std::io::Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+    self.array_depth += 1;
+    if self.array_depth > 1 { return writer.write_all(b"["); }
+    return self.pretty_formatter.begin_array(writer);
+  }
+
+  fn end_array<W: ?Sized + std::io::Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+    self.array_depth -= 1;
+    if self.array_depth > 0 { return writer.write_all(b"]"); }
+    return self.pretty_formatter.end_array(writer);
+  }
+
+  fn begin_array_value<W: ?Sized + std::io::Write>(&mut self, writer: &mut W,
+        first: bool) -> std::io::Result<()> {
+    if self.array_depth > 1 { return writer.write_all(if first { b"" } else { b", " }); }
+    return self.pretty_formatter.begin_array_value(writer, first);
+  }
+
+  fn end_array_value<W: ?Sized + std::io::Write>(&mut self,
+        writer: &mut W) -> std::io::Result<()> {
+    if self.array_depth > 1 { return Ok(()); }
+    return self.pretty_formatter.end_array_value(writer);
+  }
+
+  fn begin_object<W: ?Sized + std::io::Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+    return self.pretty_formatter.begin_object(writer);
+  }
+
+  fn end_object<W: ?Sized + std::io::Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+    return self.pretty_formatter.end_object(writer);
+  }
+
+  fn begin_object_key<W: ?Sized + std::io::Write>(&mut self, writer: &mut W,
+        first: bool) -> std::io::Result<()> {
+    return self.pretty_formatter.begin_object_key(writer, first);
+  }
+
+  fn begin_object_value<W: ?Sized + std::io::Write>(&mut self,
+        writer: &mut W) -> std::io::Result<()> {
+    return self.pretty_formatter.begin_object_value(writer);
+  }
+
+  fn end_object_value<W: ?Sized + std::io::Write>(&mut self,
+        writer: &mut W) -> std::io::Result<()> {
+    return self.pretty_formatter.end_object_value(writer);
+  }
+}
@@ -0,0 +1,31 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+#![allow(dead_code)]
+
+pub mod achievement;
+pub mod asset;
+pub mod audio;
+pub mod campaign;
+pub mod clock;
+pub mod event;
+pub mod game;
+pub mod gift;
+pub mod highscore;
+pub mod highscore_file;
+pub mod input;
+pub mod leaderboard;
+pub mod level;
+pub mod level_file;
+pub mod locale;
+pub mod npc;
+pub mod render;
+pub mod replay;
+pub mod simulation;
+pub mod sleigh;
+pub mod statistics;
+pub mod ui;
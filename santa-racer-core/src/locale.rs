@@ -0,0 +1,260 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use serde::Serialize;
+use serde::Deserialize;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Language {
+  English,
+  German,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Message {
+  MenuHelp,
+  MenuHighscores,
+  MenuOptions,
+  MenuPlay,
+  Paused,
+  PausedResume,
+  EmptyHighscoreName,
+  HighscoresTitle,
+  HighscorePoints,
+  HighscoreDate,
+  HighscoreGiftsDelivered,
+  HighscoreGiftPoints,
+  HighscoreDamagePoints,
+  HighscoreRemainingTime,
+  HighscoreGameVersion,
+  HighscoreSeed,
+  HighscoreLevel,
+  Unknown,
+  HighscoresExported,
+  HighscoresImported,
+  VerificationValid,
+  VerificationUnsigned,
+  VerificationInvalidSignature,
+  VerificationMissingReplay,
+  VerificationMismatch,
+  VerificationSummary,
+  GlobalHighscoresTitle,
+  LeaderboardLoading,
+  LeaderboardUnavailable,
+  LevelConverted,
+  LevelLocked,
+  LevelCompleted,
+  CampaignProgress,
+  CampaignCompleted,
+  Campaign,
+  On,
+  Off,
+  Easy,
+  Hard,
+  OptionFullscreen,
+  OptionSound,
+  OptionMasterVolume,
+  OptionMusicVolume,
+  OptionEffectsVolume,
+  OptionDifficulty,
+  OptionLanguage,
+  KeySteerLeft,
+  KeySteerRight,
+  KeySteerUp,
+  KeySteerDown,
+  KeyDropGift,
+  KeyPause,
+  KeyMute,
+  Description,
+  DescriptionSourceCode,
+  DescriptionAssets,
+  AllAssetsValid,
+  ReplayFinished,
+  ReplayFileRequired,
+  ResultWon,
+  ResultLostDueToDamage,
+  ResultLostDueToTime,
+  ResultAborted,
+  ResultSummary,
+  AchievementUnlocked,
+  AchievementFirstDelivery,
+  AchievementBonusDelivery,
+  AchievementTopFloorDelivery,
+  AchievementAllPowerUps,
+  AchievementFinished,
+  AchievementFlawlessFinish,
+}
+
+// index into Language::ALL, German is the language of the original game
+static LANGUAGE: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(1);
+
+pub fn set_language(language: Language) {
+  let index = Language::ALL.iter().position(|&x| x == language).unwrap_or(0);
+  LANGUAGE.store(index, std::sync::atomic::Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+  return Language::ALL[LANGUAGE.load(std::sync::atomic::Ordering::Relaxed)];
+}
+
+impl Language {
+  pub const ALL: [Language; 2] = [Language::English, Language::German];
+
+  pub fn code(self) -> &'static str {
+    return match self {
+      Language::English => "en",
+      Language::German => "de",
+    };
+  }
+
+  pub fn name(self) -> &'static str {
+    return match self {
+      Language::English => "English",
+      Language::German => "Deutsch",
+    };
+  }
+
+  // accepts codes such as "de", "de_DE", or "de_DE.UTF-8"
+  pub fn from_code(code: &str) -> Option<Language> {
+    let code = code.split(|x| (x == '_') || (x == '-') || (x == '.')).next().unwrap_or("");
+    return Language::ALL.iter().find(|x| x.code().eq_ignore_ascii_case(code)).cloned();
+  }
+
+  // first non-empty variable of LC_ALL, LC_MESSAGES, and LANG as in POSIX
+  pub fn from_environment() -> Option<Language> {
+    for name in ["LC_ALL", "LC_MESSAGES", "LANG"].iter() {
+      if let Some(value) = std::env::var(name).ok().filter(|x| !x.is_empty()) {
+        return Language::from_code(&value);
+      }
+    }
+
+    return None;
+  }
+}
+
+impl Message {
+  pub fn text(self) -> &'static str {
+    let (english, german) = self.texts();
+
+    return match language() {
+      Language::English => english,
+      Language::German => german,
+    };
+  }
+
+  // replaces each "{}" in the text with the next argument
+  pub fn format(self, arguments: &[&dyn std::fmt::Display]) -> String {
+    let mut parts = self.text().split("{}");
+    let mut result = parts.next().unwrap_or("").to_string();
+
+    for (i, part) in parts.enumerate() {
+      if let Some(argument) = arguments.get(i) { result += &argument.to_string(); }
+      result += part;
+    }
+
+    return result;
+  }
+
+  fn texts(self) -> (&'static str, &'static str) {
+    return match self {
+      Message::MenuHelp => ("F1/F2 - Help", "F1/F2 - Hilfe"),
+      Message::MenuHighscores => ("F3 - Highscores", "F3 - Highscores"),
+      Message::MenuOptions => ("F4 - Options", "F4 - Optionen"),
+      Message::MenuPlay => ("F5/F6 - Play", "F5/F6 - Spielen"),
+      Message::Paused => ("Paused", "Pause"),
+      Message::PausedResume => ("P - Resume", "P - Weiter"),
+      Message::EmptyHighscoreName => ("Empty", "Leer"),
+      Message::HighscoresTitle => ("Highscores: {}", "Highscores: {}"),
+      Message::HighscorePoints => ("Points", "Punkte"),
+      Message::HighscoreDate => ("Date", "Datum"),
+      Message::HighscoreGiftsDelivered => ("Gifts", "Geschenke"),
+      Message::HighscoreGiftPoints => ("Gift points", "Geschenkpunkte"),
+      Message::HighscoreDamagePoints => ("Damage points", "Schadenspunkte"),
+      Message::HighscoreRemainingTime => ("Remaining time", "Restzeit"),
+      Message::HighscoreGameVersion => ("Version", "Version"),
+      Message::HighscoreSeed => ("Seed", "Seed"),
+      Message::HighscoreLevel => ("Level", "Level"),
+      Message::Unknown => ("Unknown", "Unbekannt"),
+      Message::HighscoresExported => ("Highscores exported to '{}'",
+          "Highscores nach '{}' exportiert"),
+      Message::HighscoresImported => ("Merged {} highscores from '{}'",
+          "{} Highscores aus '{}' zusammengef\u{00fc}hrt"),
+      Message::VerificationValid => ("valid", "g\u{00fc}ltig"),
+      Message::VerificationUnsigned => ("not signed (older version or imported)",
+          "nicht signiert (\u{00e4}ltere Version oder importiert)"),
+      Message::VerificationInvalidSignature => ("INVALID SIGNATURE",
+          "UNG\u{00dc}LTIGE SIGNATUR"),
+      Message::VerificationMissingReplay => ("MISSING REPLAY: {}", "FEHLENDES REPLAY: {}"),
+      Message::VerificationMismatch => ("MISMATCH: replay results in {} points",
+          "ABWEICHUNG: Replay ergibt {} Punkte"),
+      Message::VerificationSummary => ("{} of {} highscores are valid, {} are not signed",
+          "{} von {} Highscores sind g\u{00fc}ltig, {} sind nicht signiert"),
+      Message::GlobalHighscoresTitle => ("Global highscores: {}", "Globale Highscores: {}"),
+      Message::LeaderboardLoading => ("Loading...", "Wird geladen..."),
+      Message::LeaderboardUnavailable => ("Server not reachable", "Server nicht erreichbar"),
+      Message::LevelConverted => ("Level '{}' converted to '{}'",
+          "Level '{}' nach '{}' konvertiert"),
+      Message::LevelLocked => ("Locked", "Gesperrt"),
+      Message::LevelCompleted => ("Completed", "Geschafft"),
+      Message::CampaignProgress => ("Level {} of {} - Total: {}",
+          "Level {} von {} - Gesamt: {}"),
+      Message::CampaignCompleted => ("All {} levels completed - Total: {}",
+          "Alle {} Level geschafft - Gesamt: {}"),
+      Message::Campaign => ("Campaign", "Kampagne"),
+      Message::On => ("On", "An"),
+      Message::Off => ("Off", "Aus"),
+      Message::Easy => ("Easy", "Leicht"),
+      Message::Hard => ("Hard", "Schwer"),
+      Message::OptionFullscreen => ("Fullscreen", "Vollbild"),
+      Message::OptionSound => ("Sound", "Ton"),
+      Message::OptionMasterVolume => ("Volume", "Lautst\u{00e4}rke"),
+      Message::OptionMusicVolume => ("Music", "Musik"),
+      Message::OptionEffectsVolume => ("Effects", "Effekte"),
+      Message::OptionDifficulty => ("Difficulty", "Schwierigkeit"),
+      Message::OptionLanguage => ("Language", "Sprache"),
+      Message::KeySteerLeft => ("Left", "Links"),
+      Message::KeySteerRight => ("Right", "Rechts"),
+      Message::KeySteerUp => ("Up", "Hoch"),
+      Message::KeySteerDown => ("Down", "Runter"),
+      Message::KeyDropGift => ("Gift", "Geschenk"),
+      Message::KeyPause => ("Pause", "Pause"),
+      Message::KeyMute => ("Mute", "Stumm"),
+      Message::Description => ("Santa Racer - an open-source clone of \"Nikolaus Express 2000\".",
+          "Santa Racer - ein Open-Source-Klon von \"Nikolaus Express 2000\"."),
+      Message::DescriptionSourceCode => (
+          "Source code: Copyright (C) 2020 Julian Valentin, licensed under MPL 2.0.",
+          "Quellcode: Copyright (C) 2020 Julian Valentin, lizenziert unter MPL 2.0."),
+      Message::DescriptionAssets => ("Exception: External assets such as music, sounds, and \
+          textures may be subject to be intellectual property of third parties.",
+          "Ausnahme: Externe Assets wie Musik, Kl\u{00e4}nge und Texturen k\u{00f6}nnen \
+          geistiges Eigentum Dritter sein."),
+      Message::AllAssetsValid => ("All {} assets are valid", "Alle {} Assets sind g\u{00fc}ltig"),
+      Message::ReplayFinished => ("Replay finished: {}", "Replay beendet: {}"),
+      Message::ReplayFileRequired => ("Simulating a replay requires a replay file",
+          "Zum Simulieren eines Replays wird eine Replay-Datei ben\u{00f6}tigt"),
+      Message::ResultWon => ("won", "gewonnen"),
+      Message::ResultLostDueToDamage => ("lost due to damage", "wegen Schaden verloren"),
+      Message::ResultLostDueToTime => ("lost due to time", "wegen Zeit verloren"),
+      Message::ResultAborted => ("aborted", "abgebrochen"),
+      Message::ResultSummary => ("{} with {} points after {} ticks",
+          "{} mit {} Punkten nach {} Ticks"),
+      Message::AchievementUnlocked => ("Achievement unlocked: {}",
+          "Erfolg freigeschaltet: {}"),
+      Message::AchievementFirstDelivery => ("first gift delivered",
+          "erstes Geschenk zugestellt"),
+      Message::AchievementBonusDelivery => ("gift delivered with bonus",
+          "Geschenk mit Bonus zugestellt"),
+      Message::AchievementTopFloorDelivery => ("gift delivered into a top floor chimney",
+          "Geschenk in einen Schornstein im obersten Stockwerk zugestellt"),
+      Message::AchievementAllPowerUps => ("all balloon types collected in one run",
+          "alle Ballonarten in einer Runde eingesammelt"),
+      Message::AchievementFinished => ("finish reached", "Ziel erreicht"),
+      Message::AchievementFlawlessFinish => ("finish reached without any damage",
+          "Ziel ohne Schaden erreicht"),
+    };
+  }
+}
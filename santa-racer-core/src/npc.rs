@@ -5,14 +5,19 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+use serde::Serialize;
+use serde::Deserialize;
+
 use crate::*;
 use crate::asset::Point;
 
 pub trait Npc {
-  fn do_logic(&mut self, level_offset_x: f64, level_scroll_speed_x: f64, sleigh: &sleigh::Sleigh);
-  fn check_collision_with_sleigh(&mut self, score: &mut ui::Score,
-      level_offset_x: f64, sleigh: &mut sleigh::Sleigh);
-  fn draw(&self, canvas: &mut sdl2::render::WindowCanvas, level_offset_x: f64);
+  fn do_logic(&mut self, events: &mut event::EventQueue, level_offset_x: f64,
+      level_scroll_speed_x: f64, sleigh: &sleigh::Sleigh, clock: &dyn clock::GameClock,
+      rng: &mut rand::rngs::StdRng);
+  fn check_collision_with_sleigh(&mut self, events: &mut event::EventQueue,
+      level_offset_x: f64, sleigh: &mut sleigh::Sleigh, clock: &dyn clock::GameClock);
+  fn draw(&self, renderer: &mut dyn render::Renderer, level_offset_x: f64);
 
   fn tile(&self) -> (usize, usize);
   fn z_order(&self) -> f64;
@@ -20,7 +25,7 @@ pub trait Npc {
 }
 
 struct NpcBase<'a> {
-  image: &'a asset::Image<'a>,
+  image: &'a asset::Image,
   canvas_size: Point,
   level_tile_size: Point,
 
@@ -37,27 +42,22 @@ struct NpcBase<'a> {
 
 struct Angel<'a> {
   npc_base: NpcBase<'a>,
-  sound: &'a asset::Sound,
 
   damage_points: f64,
 }
 
 struct Balloon<'a> {
   npc_base: NpcBase<'a>,
-  sound: &'a asset::Sound,
 
   balloon_type: BalloonType,
   frame_increasing: bool,
   visible: bool,
 
   launch_velocity: Point,
-  cash_balloon_damage_points: f64,
-  heart_balloon_gift_points: f64,
 }
 
 struct Cloud<'a> {
   npc_base: NpcBase<'a>,
-  sound: &'a asset::Sound,
 
   damage_points: f64,
 }
@@ -68,9 +68,7 @@ struct Finish<'a> {
 
 struct Goblin<'a> {
   npc_base: NpcBase<'a>,
-  snowball_image: &'a asset::Image<'a>,
-  throw_snowball_sound: &'a asset::Sound,
-  collision_sound: &'a asset::Sound,
+  snowball_image: &'a asset::Image,
 
   snowballs: Vec<NpcBase<'a>>,
   next_throw_snowball_instant: std::time::Instant,
@@ -83,8 +81,6 @@ struct Goblin<'a> {
 
 struct Snowman<'a> {
   npc_base: NpcBase<'a>,
-  launch_sound: &'a asset::Sound,
-  collision_sound: &'a asset::Sound,
 
   launched: bool,
   stars: Vec<sleigh::Star<'a>>,
@@ -94,7 +90,32 @@ struct Snowman<'a> {
   damage_points: f64,
 }
 
-enum BalloonType {
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum NpcKind {
+  Angel,
+  Cloud,
+  Goblin,
+  Snowman,
+}
+
+// types of NPCs as named in level files
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum NpcType {
+  Angel,
+  CashBalloon,
+  Cloud,
+  Finish,
+  GiftBalloon,
+  Goblin,
+  HeartBalloon,
+  ShieldBalloon,
+  Snowman,
+  WineBalloon,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum BalloonType {
   Cash,
   Gift,
   Heart,
@@ -103,35 +124,57 @@ enum BalloonType {
 }
 
 pub fn new_npc<'a>(asset_library: &'a asset::AssetLibrary<'a>, level: &level::Level,
-      tile: (usize, usize), frame: f64) -> Box<dyn Npc + 'a> {
-  if frame == 69.0 {
-    return Box::new(Angel::new(asset_library, level, tile));
-  } else if frame == 70.0 {
-    return Box::new(Balloon::new(asset_library, level, tile, BalloonType::Cash));
-  } else if frame == 71.0 {
-    return Box::new(Balloon::new(asset_library, level, tile, BalloonType::Heart));
-  } else if frame == 72.0 {
-    return Box::new(Balloon::new(asset_library, level, tile, BalloonType::Wine));
-  } else if frame == 73.0 {
-    return Box::new(Balloon::new(asset_library, level, tile, BalloonType::Gift));
-  } else if frame == 75.0 {
-    return Box::new(Balloon::new(asset_library, level, tile, BalloonType::Shield));
-  } else if frame == 74.0 {
-    return Box::new(Cloud::new(asset_library, level, tile));
-  } else if frame == 76.0 {
-    return Box::new(Finish::new(asset_library, level, tile));
-  } else if frame == 68.0 {
-    return Box::new(Goblin::new(asset_library, level, tile));
-  } else if frame == 29.0 {
-    return Box::new(Snowman::new(asset_library, level, tile));
-  } else {
-    return Box::new(Angel::new(asset_library, level, tile));
+      tile: (usize, usize), npc_type: NpcType,
+      clock: &dyn clock::GameClock) -> Box<dyn Npc + 'a> {
+  return match npc_type {
+    NpcType::Angel => Box::new(Angel::new(asset_library, level, tile, clock)),
+    NpcType::CashBalloon => Box::new(Balloon::new(asset_library, level, tile,
+        BalloonType::Cash, clock)),
+    NpcType::HeartBalloon => Box::new(Balloon::new(asset_library, level, tile,
+        BalloonType::Heart, clock)),
+    NpcType::WineBalloon => Box::new(Balloon::new(asset_library, level, tile,
+        BalloonType::Wine, clock)),
+    NpcType::GiftBalloon => Box::new(Balloon::new(asset_library, level, tile,
+        BalloonType::Gift, clock)),
+    NpcType::ShieldBalloon => Box::new(Balloon::new(asset_library, level, tile,
+        BalloonType::Shield, clock)),
+    NpcType::Cloud => Box::new(Cloud::new(asset_library, level, tile, clock)),
+    NpcType::Finish => Box::new(Finish::new(asset_library, level, tile, clock)),
+    NpcType::Goblin => Box::new(Goblin::new(asset_library, level, tile, clock)),
+    NpcType::Snowman => Box::new(Snowman::new(asset_library, level, tile, clock)),
+  };
+}
+
+impl NpcType {
+  // frames of the NPC map of the original game, unknown frames are angels
+  pub fn from_legacy_frame(frame: f64) -> NpcType {
+    if frame == 70.0 {
+      return NpcType::CashBalloon;
+    } else if frame == 71.0 {
+      return NpcType::HeartBalloon;
+    } else if frame == 72.0 {
+      return NpcType::WineBalloon;
+    } else if frame == 73.0 {
+      return NpcType::GiftBalloon;
+    } else if frame == 75.0 {
+      return NpcType::ShieldBalloon;
+    } else if frame == 74.0 {
+      return NpcType::Cloud;
+    } else if frame == 76.0 {
+      return NpcType::Finish;
+    } else if frame == 68.0 {
+      return NpcType::Goblin;
+    } else if frame == 29.0 {
+      return NpcType::Snowman;
+    } else {
+      return NpcType::Angel;
+    }
   }
 }
 
 impl<'a> NpcBase<'a> {
-  pub fn new(image: &'a asset::Image<'a>, canvas_size: Point, level_tile_size: Point,
-        tile: (usize, usize), frame_speed: f64) -> NpcBase<'a> {
+  pub fn new(image: &'a asset::Image, canvas_size: Point, level_tile_size: Point,
+        tile: (usize, usize), frame_speed: f64, clock: &dyn clock::GameClock) -> NpcBase<'a> {
     return NpcBase{
       image: image,
       canvas_size: canvas_size,
@@ -144,7 +187,7 @@ impl<'a> NpcBase<'a> {
       velocity: Point::zero(),
       acceleration: Point::zero(),
       frame: 0.0,
-      last_update_instant: std::time::Instant::now(),
+      last_update_instant: clock.now(),
 
       frame_speed: frame_speed,
     };
@@ -152,8 +195,8 @@ impl<'a> NpcBase<'a> {
 }
 
 impl<'a> NpcBase<'a> {
-  fn do_logic(&mut self) {
-    let now = std::time::Instant::now();
+  fn do_logic(&mut self, clock: &dyn clock::GameClock) {
+    let now = clock.now();
     let seconds_since_last_update = (now - self.last_update_instant).as_secs_f64();
 
     self.velocity = self.velocity + seconds_since_last_update * self.acceleration;
@@ -168,19 +211,18 @@ impl<'a> NpcBase<'a> {
         Point::new(self.position.x() - level_offset_x, self.position.y()), self.frame);
   }
 
-  fn draw(&self, canvas: &mut sdl2::render::WindowCanvas, level_offset_x: f64) {
-    self.image.draw(canvas, Point::new(self.position.x() - level_offset_x, self.position.y()),
+  fn draw(&self, renderer: &mut dyn render::Renderer, level_offset_x: f64) {
+    self.image.draw(renderer, Point::new(self.position.x() - level_offset_x, self.position.y()),
         self.frame);
   }
 }
 
 impl<'a> Angel<'a> {
   pub fn new(asset_library: &'a asset::AssetLibrary<'a>, level: &level::Level,
-        tile: (usize, usize)) -> Angel<'a> {
+        tile: (usize, usize), clock: &dyn clock::GameClock) -> Angel<'a> {
     return Angel{
       npc_base: NpcBase::new(asset_library.get_image("angel"),
-          level.canvas_size(), level.tile_size(), tile, 13.0),
-      sound: asset_library.get_sound("sleighCollidedWithNpc"),
+          level.canvas_size(), level.tile_size(), tile, 13.0, clock),
 
       damage_points: 20.0,
     };
@@ -188,23 +230,24 @@ impl<'a> Angel<'a> {
 }
 
 impl<'a> Npc for Angel<'a> {
-  fn do_logic(&mut self, _level_offset_x: f64, _level_scroll_speed_x: f64,
-        _sleigh: &sleigh::Sleigh) {
-    self.npc_base.do_logic();
+  fn do_logic(&mut self, _events: &mut event::EventQueue, _level_offset_x: f64,
+        _level_scroll_speed_x: f64, _sleigh: &sleigh::Sleigh, clock: &dyn clock::GameClock,
+        _rng: &mut rand::rngs::StdRng) {
+    self.npc_base.do_logic(clock);
   }
 
-  fn check_collision_with_sleigh(&mut self, score: &mut ui::Score,
-        level_offset_x: f64, sleigh: &mut sleigh::Sleigh) {
+  fn check_collision_with_sleigh(&mut self, events: &mut event::EventQueue,
+        level_offset_x: f64, sleigh: &mut sleigh::Sleigh, clock: &dyn clock::GameClock) {
     if !sleigh.invincible() && !sleigh.shield()
           && self.npc_base.collides_with_sleigh(level_offset_x, sleigh) {
-      self.sound.play_with_position(self.npc_base.canvas_size, sleigh.position());
-      score.add_damage_points(self.damage_points);
-      sleigh.start_invincible();
+      events.push(event::GameEvent::SleighHitNpc{kind: NpcKind::Angel,
+          damage_points: self.damage_points, position: sleigh.position()});
+      sleigh.start_invincible(clock);
     }
   }
 
-  fn draw(&self, canvas: &mut sdl2::render::WindowCanvas, level_offset_x: f64) {
-    self.npc_base.draw(canvas, level_offset_x);
+  fn draw(&self, renderer: &mut dyn render::Renderer, level_offset_x: f64) {
+    self.npc_base.draw(renderer, level_offset_x);
   }
 
   fn tile(&self) -> (usize, usize) {
@@ -222,7 +265,8 @@ impl<'a> Npc for Angel<'a> {
 
 impl<'a> Balloon<'a> {
   pub fn new(asset_library: &'a asset::AssetLibrary<'a>, level: &level::Level,
-        tile: (usize, usize), balloon_type: BalloonType) -> Balloon<'a> {
+        tile: (usize, usize), balloon_type: BalloonType, clock: &dyn clock::GameClock) ->
+        Balloon<'a> {
     let image_name = match balloon_type {
       BalloonType::Cash => "cashBalloon",
       BalloonType::Gift => "giftBalloon",
@@ -230,29 +274,25 @@ impl<'a> Balloon<'a> {
       BalloonType::Shield => "shieldBalloon",
       BalloonType::Wine => "wineBalloon",
     };
-    let sound_name = if image_name == "heartBalloon" { "giftCollidedWithChimney" }
-        else { image_name };
 
     return Balloon{
       npc_base: NpcBase::new(asset_library.get_image(image_name),
-          level.canvas_size(), level.tile_size(), tile, 10.0),
-      sound: asset_library.get_sound(sound_name),
+          level.canvas_size(), level.tile_size(), tile, 10.0, clock),
 
       balloon_type: balloon_type,
       frame_increasing: true,
       visible: true,
 
       launch_velocity: Point::new(0.0, -50.0),
-      cash_balloon_damage_points: -50.0,
-      heart_balloon_gift_points: 20.0,
     };
   }
 }
 
 impl<'a> Npc for Balloon<'a> {
-  fn do_logic(&mut self, level_offset_x: f64, _level_scroll_speed_x: f64,
-        _sleigh: &sleigh::Sleigh) {
-    let now = std::time::Instant::now();
+  fn do_logic(&mut self, _events: &mut event::EventQueue, level_offset_x: f64,
+        _level_scroll_speed_x: f64, _sleigh: &sleigh::Sleigh, clock: &dyn clock::GameClock,
+        _rng: &mut rand::rngs::StdRng) {
+    let now = clock.now();
     let seconds_since_last_update = (now - self.npc_base.last_update_instant).as_secs_f64();
 
     if (level_offset_x + self.npc_base.canvas_size.x()) / self.npc_base.level_tile_size.x()
@@ -261,7 +301,7 @@ impl<'a> Npc for Balloon<'a> {
     }
 
     let frame = self.npc_base.frame;
-    self.npc_base.do_logic();
+    self.npc_base.do_logic(clock);
 
     let number_of_frames = self.npc_base.image.total_number_of_frames() as f64;
     let sign = if self.frame_increasing { 1.0 } else { -1.0 };
@@ -276,24 +316,24 @@ impl<'a> Npc for Balloon<'a> {
     self.npc_base.last_update_instant = now;
   }
 
-  fn check_collision_with_sleigh(&mut self, score: &mut ui::Score,
-        level_offset_x: f64, sleigh: &mut sleigh::Sleigh) {
+  fn check_collision_with_sleigh(&mut self, events: &mut event::EventQueue,
+        level_offset_x: f64, sleigh: &mut sleigh::Sleigh, clock: &dyn clock::GameClock) {
     if self.visible && self.npc_base.collides_with_sleigh(level_offset_x, sleigh) {
-      self.sound.play_with_position(self.npc_base.canvas_size, sleigh.position());
+      events.push(event::GameEvent::PowerUpCollected{kind: self.balloon_type,
+          position: sleigh.position()});
       self.visible = false;
 
       match self.balloon_type {
-        BalloonType::Cash => { score.add_damage_points(self.cash_balloon_damage_points); },
-        BalloonType::Gift => { sleigh.start_bonus(); },
-        BalloonType::Heart => { score.add_gift_points(self.heart_balloon_gift_points); },
-        BalloonType::Shield => { sleigh.start_shield(); },
-        BalloonType::Wine => { sleigh.start_drunk(); },
+        BalloonType::Gift => { sleigh.start_bonus(clock); },
+        BalloonType::Shield => { sleigh.start_shield(clock); },
+        BalloonType::Wine => { sleigh.start_drunk(clock); },
+        _ => {},
       }
     }
   }
 
-  fn draw(&self, canvas: &mut sdl2::render::WindowCanvas, level_offset_x: f64) {
-    if self.visible { self.npc_base.draw(canvas, level_offset_x); }
+  fn draw(&self, renderer: &mut dyn render::Renderer, level_offset_x: f64) {
+    if self.visible { self.npc_base.draw(renderer, level_offset_x); }
   }
 
   fn tile(&self) -> (usize, usize) {
@@ -311,11 +351,10 @@ impl<'a> Npc for Balloon<'a> {
 
 impl<'a> Cloud<'a> {
   pub fn new(asset_library: &'a asset::AssetLibrary<'a>, level: &level::Level,
-        tile: (usize, usize)) -> Cloud<'a> {
+        tile: (usize, usize), clock: &dyn clock::GameClock) -> Cloud<'a> {
     return Cloud{
       npc_base: NpcBase::new(asset_library.get_image("cloud"),
-          level.canvas_size(), level.tile_size(), tile, 0.0),
-      sound: asset_library.get_sound("sleighCollidedWithCloud"),
+          level.canvas_size(), level.tile_size(), tile, 0.0, clock),
 
       damage_points: 20.0,
     };
@@ -323,24 +362,25 @@ impl<'a> Cloud<'a> {
 }
 
 impl<'a> Npc for Cloud<'a> {
-  fn do_logic(&mut self, _level_offset_x: f64, _level_scroll_speed_x: f64,
-        _sleigh: &sleigh::Sleigh) {
-    self.npc_base.do_logic();
+  fn do_logic(&mut self, _events: &mut event::EventQueue, _level_offset_x: f64,
+        _level_scroll_speed_x: f64, _sleigh: &sleigh::Sleigh, clock: &dyn clock::GameClock,
+        _rng: &mut rand::rngs::StdRng) {
+    self.npc_base.do_logic(clock);
   }
 
-  fn check_collision_with_sleigh(&mut self, score: &mut ui::Score,
-        level_offset_x: f64, sleigh: &mut sleigh::Sleigh) {
+  fn check_collision_with_sleigh(&mut self, events: &mut event::EventQueue,
+        level_offset_x: f64, sleigh: &mut sleigh::Sleigh, clock: &dyn clock::GameClock) {
     if !sleigh.invincible() && !sleigh.shield()
           && self.npc_base.collides_with_sleigh(level_offset_x, sleigh) {
-      self.sound.play_with_position(self.npc_base.canvas_size, sleigh.position());
-      score.add_damage_points(self.damage_points);
-      sleigh.start_invincible();
-      sleigh.start_electrocuted();
+      events.push(event::GameEvent::SleighHitNpc{kind: NpcKind::Cloud,
+          damage_points: self.damage_points, position: sleigh.position()});
+      sleigh.start_invincible(clock);
+      sleigh.start_electrocuted(clock);
     }
   }
 
-  fn draw(&self, canvas: &mut sdl2::render::WindowCanvas, level_offset_x: f64) {
-    self.npc_base.draw(canvas, level_offset_x);
+  fn draw(&self, renderer: &mut dyn render::Renderer, level_offset_x: f64) {
+    self.npc_base.draw(renderer, level_offset_x);
   }
 
   fn tile(&self) -> (usize, usize) {
@@ -358,30 +398,31 @@ impl<'a> Npc for Cloud<'a> {
 
 impl<'a> Finish<'a> {
   pub fn new(asset_library: &'a asset::AssetLibrary<'a>, level: &level::Level,
-        tile: (usize, usize)) -> Finish<'a> {
+        tile: (usize, usize), clock: &dyn clock::GameClock) -> Finish<'a> {
     return Finish{
       npc_base: NpcBase::new(asset_library.get_image("finish"),
-          level.canvas_size(), level.tile_size(), tile, 0.0),
+          level.canvas_size(), level.tile_size(), tile, 0.0, clock),
     };
   }
 }
 
 impl<'a> Npc for Finish<'a> {
-  fn do_logic(&mut self, _level_offset_x: f64, _level_scroll_speed_x: f64,
-        _sleigh: &sleigh::Sleigh) {
-    self.npc_base.do_logic();
+  fn do_logic(&mut self, _events: &mut event::EventQueue, _level_offset_x: f64,
+        _level_scroll_speed_x: f64, _sleigh: &sleigh::Sleigh, clock: &dyn clock::GameClock,
+        _rng: &mut rand::rngs::StdRng) {
+    self.npc_base.do_logic(clock);
   }
 
-  fn check_collision_with_sleigh(&mut self, score: &mut ui::Score,
-        level_offset_x: f64, sleigh: &mut sleigh::Sleigh) {
+  fn check_collision_with_sleigh(&mut self, events: &mut event::EventQueue,
+        level_offset_x: f64, sleigh: &mut sleigh::Sleigh, _clock: &dyn clock::GameClock) {
     if level_offset_x + sleigh.position().x() + sleigh.size().x() / 2.0
           >= self.npc_base.position.x() + self.npc_base.size.x() / 2.0 {
-      score.set_won(true);
+      events.push(event::GameEvent::Finished);
     }
   }
 
-  fn draw(&self, canvas: &mut sdl2::render::WindowCanvas, level_offset_x: f64) {
-    self.npc_base.draw(canvas, level_offset_x);
+  fn draw(&self, renderer: &mut dyn render::Renderer, level_offset_x: f64) {
+    self.npc_base.draw(renderer, level_offset_x);
   }
 
   fn tile(&self) -> (usize, usize) {
@@ -399,19 +440,18 @@ impl<'a> Npc for Finish<'a> {
 
 impl<'a> Goblin<'a> {
   pub fn new(asset_library: &'a asset::AssetLibrary<'a>, level: &level::Level,
-        tile: (usize, usize)) -> Goblin<'a> {
+        tile: (usize, usize), clock: &dyn clock::GameClock) -> Goblin<'a> {
     let image = asset_library.get_image("goblin");
     let frame_speed = 12.0;
     let throw_snowball_frame = 13.0;
 
     return Goblin{
-      npc_base: NpcBase::new(image, level.canvas_size(), level.tile_size(), tile, frame_speed),
+      npc_base: NpcBase::new(image, level.canvas_size(), level.tile_size(), tile, frame_speed,
+          clock),
       snowball_image: asset_library.get_image("goblinSnowball"),
-      throw_snowball_sound: asset_library.get_sound("goblinThrowSnowball"),
-      collision_sound: asset_library.get_sound("sleighCollidedWithNpc"),
 
       snowballs: Vec::new(),
-      next_throw_snowball_instant: std::time::Instant::now() + std::time::Duration::from_secs_f64(
+      next_throw_snowball_instant: clock.now() + std::time::Duration::from_secs_f64(
         throw_snowball_frame / frame_speed),
 
       snowball_velocity: Point::new(-200.0, -250.0),
@@ -424,16 +464,17 @@ impl<'a> Goblin<'a> {
 }
 
 impl<'a> Npc for Goblin<'a> {
-  fn do_logic(&mut self, level_offset_x: f64, _level_scroll_speed_x: f64,
-        _sleigh: &sleigh::Sleigh) {
-    let now = std::time::Instant::now();
+  fn do_logic(&mut self, events: &mut event::EventQueue, level_offset_x: f64,
+        _level_scroll_speed_x: f64, _sleigh: &sleigh::Sleigh, clock: &dyn clock::GameClock,
+        _rng: &mut rand::rngs::StdRng) {
+    let now = clock.now();
 
     if now >= self.next_throw_snowball_instant {
-      self.throw_snowball_sound.play_with_level_position(self.npc_base.canvas_size,
-          level_offset_x, self.npc_base.position);
+      events.push(event::GameEvent::SnowballThrown{position: Point::new(
+          self.npc_base.position.x() - level_offset_x, self.npc_base.position.y())});
 
       let mut snowball = NpcBase::new(self.snowball_image, self.npc_base.canvas_size,
-          self.npc_base.level_tile_size, self.npc_base.tile, 0.0);
+          self.npc_base.level_tile_size, self.npc_base.tile, 0.0, clock);
       snowball.position = self.npc_base.position;
       snowball.velocity = self.snowball_velocity;
       snowball.acceleration = self.snowball_acceleration;
@@ -444,12 +485,12 @@ impl<'a> Npc for Goblin<'a> {
       }
     }
 
-    for snowball in &mut self.snowballs { snowball.do_logic(); }
-    self.npc_base.do_logic();
+    for snowball in &mut self.snowballs { snowball.do_logic(clock); }
+    self.npc_base.do_logic(clock);
   }
 
-  fn check_collision_with_sleigh(&mut self, score: &mut ui::Score,
-        level_offset_x: f64, sleigh: &mut sleigh::Sleigh) {
+  fn check_collision_with_sleigh(&mut self, events: &mut event::EventQueue,
+        level_offset_x: f64, sleigh: &mut sleigh::Sleigh, clock: &dyn clock::GameClock) {
     if sleigh.invincible() || sleigh.shield() { return; }
     let mut collides = self.npc_base.collides_with_sleigh(level_offset_x, sleigh);
 
@@ -463,17 +504,17 @@ impl<'a> Npc for Goblin<'a> {
     }
 
     if collides {
-      self.collision_sound.play_with_position(self.npc_base.canvas_size, sleigh.position());
-      score.add_damage_points(self.damage_points);
-      sleigh.start_invincible();
+      events.push(event::GameEvent::SleighHitNpc{kind: NpcKind::Goblin,
+          damage_points: self.damage_points, position: sleigh.position()});
+      sleigh.start_invincible(clock);
     }
   }
 
-  fn draw(&self, canvas: &mut sdl2::render::WindowCanvas, level_offset_x: f64) {
-    self.npc_base.draw(canvas, level_offset_x);
+  fn draw(&self, renderer: &mut dyn render::Renderer, level_offset_x: f64) {
+    self.npc_base.draw(renderer, level_offset_x);
 
     for snowball in &self.snowballs {
-      snowball.draw(canvas, level_offset_x);
+      snowball.draw(renderer, level_offset_x);
     }
   }
 
@@ -492,11 +533,11 @@ impl<'a> Npc for Goblin<'a> {
 
 impl<'a> Snowman<'a> {
   pub fn new(asset_library: &'a asset::AssetLibrary<'a>, level: &level::Level,
-        tile: (usize, usize)) -> Snowman<'a> {
+        tile: (usize, usize), clock: &dyn clock::GameClock) -> Snowman<'a> {
     let mut stars = Vec::new();
 
     for _ in 0 .. 20 {
-      let mut star = sleigh::Star::new(asset_library);
+      let mut star = sleigh::Star::new(asset_library, clock);
       star.set_small_probability(0.5);
       star.set_min_offset(Point::new(-12.0, -32.0));
       star.set_max_offset(Point::new(-7.0, -27.0));
@@ -505,9 +546,7 @@ impl<'a> Snowman<'a> {
 
     return Snowman{
       npc_base: NpcBase::new(asset_library.get_image("snowman"),
-          level.canvas_size(), level.tile_size(), tile, 0.0),
-      launch_sound: asset_library.get_sound("snowmanLaunch"),
-      collision_sound: asset_library.get_sound("sleighCollidedWithNpc"),
+          level.canvas_size(), level.tile_size(), tile, 0.0, clock),
 
       launched: false,
       stars: stars,
@@ -520,10 +559,12 @@ impl<'a> Snowman<'a> {
 }
 
 impl<'a> Npc for Snowman<'a> {
-  fn do_logic(&mut self, level_offset_x: f64, level_scroll_speed_x: f64, sleigh: &sleigh::Sleigh) {
+  fn do_logic(&mut self, events: &mut event::EventQueue, level_offset_x: f64,
+        level_scroll_speed_x: f64, sleigh: &sleigh::Sleigh, clock: &dyn clock::GameClock,
+        rng: &mut rand::rngs::StdRng) {
     if self.launched {
       for star in &mut self.stars {
-        star.do_logic(self.npc_base.position, self.npc_base.size, false);
+        star.do_logic(self.npc_base.position, self.npc_base.size, false, clock, rng);
       }
     } else {
       let sleigh_same_y_as_sleigh_seconds = (sleigh.position().y() - self.npc_base.position.y())
@@ -534,36 +575,36 @@ impl<'a> Npc for Snowman<'a> {
           + sleigh_same_y_as_sleigh_seconds * self.launch_velocity.x();
 
       if future_sleigh_position_x >= future_snowman_position_x {
-        self.launch_sound.play_with_level_position(self.npc_base.canvas_size, level_offset_x,
-            self.npc_base.position);
+        events.push(event::GameEvent::SnowmanLaunched{position: Point::new(
+            self.npc_base.position.x() - level_offset_x, self.npc_base.position.y())});
         self.launched = true;
         self.npc_base.velocity = self.launch_velocity;
         self.npc_base.frame_speed = self.launch_frame_speed;
       }
     }
 
-    self.npc_base.do_logic();
+    self.npc_base.do_logic(clock);
 
     let last_frame = (self.npc_base.image.total_number_of_frames() as f64) - 1.0;
     if self.npc_base.frame > last_frame { self.npc_base.frame = last_frame; }
   }
 
-  fn check_collision_with_sleigh(&mut self, score: &mut ui::Score,
-        level_offset_x: f64, sleigh: &mut sleigh::Sleigh) {
+  fn check_collision_with_sleigh(&mut self, events: &mut event::EventQueue,
+        level_offset_x: f64, sleigh: &mut sleigh::Sleigh, clock: &dyn clock::GameClock) {
     if !sleigh.invincible() && !sleigh.shield()
           && self.npc_base.collides_with_sleigh(level_offset_x, sleigh) {
-      self.collision_sound.play_with_position(self.npc_base.canvas_size, sleigh.position());
-      score.add_damage_points(self.damage_points);
-      sleigh.start_invincible();
+      events.push(event::GameEvent::SleighHitNpc{kind: NpcKind::Snowman,
+          damage_points: self.damage_points, position: sleigh.position()});
+      sleigh.start_invincible(clock);
     }
   }
 
-  fn draw(&self, canvas: &mut sdl2::render::WindowCanvas, level_offset_x: f64) {
+  fn draw(&self, renderer: &mut dyn render::Renderer, level_offset_x: f64) {
     if self.launched {
-      for star in &self.stars { star.draw(canvas, level_offset_x); }
+      for star in &self.stars { star.draw(renderer, level_offset_x); }
     }
 
-    self.npc_base.draw(canvas, level_offset_x);
+    self.npc_base.draw(renderer, level_offset_x);
   }
 
   fn tile(&self) -> (usize, usize) {
@@ -0,0 +1,95 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::*;
+use crate::asset::Point;
+
+pub trait Renderer {
+  fn draw_image(&mut self, image: &asset::Image, src_rect: asset::Rect, dst_point: Point);
+  fn fill_rect(&mut self, rect: asset::Rect, color: Color);
+  fn set_alpha(&mut self, alpha: f64);
+  fn alpha(&self) -> f64;
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Color {
+  r: u8,
+  g: u8,
+  b: u8,
+  a: u8,
+}
+
+pub struct RecordingRenderer {
+  commands: Vec<DrawCommand>,
+  alpha: f64,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum DrawCommand {
+  Image{image_name: String, src_rect: asset::Rect, dst_point: Point, alpha: f64},
+  FillRect{rect: asset::Rect, color: Color, alpha: f64},
+}
+
+impl Color {
+  pub const BLACK: Color = Color::new(0, 0, 0, 255);
+
+  pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Color {
+    return Color{r: r, g: g, b: b, a: a};
+  }
+
+  pub fn r(&self) -> u8 {
+    return self.r;
+  }
+
+  pub fn g(&self) -> u8 {
+    return self.g;
+  }
+
+  pub fn b(&self) -> u8 {
+    return self.b;
+  }
+
+  pub fn a(&self) -> u8 {
+    return self.a;
+  }
+}
+
+impl RecordingRenderer {
+  pub fn new() -> RecordingRenderer {
+    return RecordingRenderer{
+      commands: Vec::new(),
+      alpha: 1.0,
+    };
+  }
+
+  pub fn commands(&self) -> &Vec<DrawCommand> {
+    return &self.commands;
+  }
+
+  pub fn clear(&mut self) {
+    self.commands.clear();
+  }
+}
+
+impl Renderer for RecordingRenderer {
+  fn draw_image(&mut self, image: &asset::Image, src_rect: asset::Rect, dst_point: Point) {
+    self.commands.push(DrawCommand::Image{image_name: image.name().to_string(),
+        src_rect: src_rect, dst_point: dst_point, alpha: self.alpha});
+  }
+
+  fn fill_rect(&mut self, rect: asset::Rect, color: Color) {
+    self.commands.push(DrawCommand::FillRect{rect: rect, color: color, alpha: self.alpha});
+  }
+
+  fn set_alpha(&mut self, alpha: f64) {
+    self.alpha = alpha.max(0.0).min(1.0);
+  }
+
+  fn alpha(&self) -> f64 {
+    return self.alpha;
+  }
+}
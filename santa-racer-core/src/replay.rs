@@ -0,0 +1,139 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use serde::Serialize;
+use serde::Deserialize;
+
+use crate::*;
+
+const REPLAY_FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Replay {
+  version: u32,
+  game_version: String,
+  seed: u64,
+  difficulty: game::GameDifficulty,
+  // omitted for the default level, as in replays of versions before multiple levels
+  #[serde(default = "default_level", skip_serializing_if = "is_default_level")]
+  level: String,
+  number_of_ticks: u64,
+  events: Vec<ReplayEvent>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct ReplayEvent {
+  tick: u64,
+  input: input::TickInput,
+}
+
+pub struct ReplayPlayer {
+  replay: Replay,
+  tick: u64,
+  next_event_index: usize,
+  input: input::TickInput,
+}
+
+impl Replay {
+  pub fn new<S: Into<String>>(seed: u64, difficulty: game::GameDifficulty, level: S) -> Replay {
+    return Replay{
+      version: REPLAY_FORMAT_VERSION,
+      game_version: env!("CARGO_PKG_VERSION").to_string(),
+      seed: seed,
+      difficulty: difficulty,
+      level: level.into(),
+      number_of_ticks: 0,
+      events: Vec::new(),
+    };
+  }
+
+  pub fn load(file_path: &std::path::Path) -> Result<Replay, String> {
+    let file = std::fs::File::open(file_path).map_err(
+        |error| format!("Could not open replay file '{}': {}", file_path.display(), error))?;
+    let replay: Replay = serde_json::from_reader(std::io::BufReader::new(file)).map_err(
+        |error| format!("Could not parse replay file '{}': {}", file_path.display(), error))?;
+
+    if replay.version != REPLAY_FORMAT_VERSION {
+      return Err(format!("Unsupported version {} of replay file '{}', expected version {}",
+          replay.version, file_path.display(), REPLAY_FORMAT_VERSION));
+    }
+
+    return Ok(replay);
+  }
+
+  pub fn save(&self, file_path: &std::path::Path) -> Result<(), String> {
+    let file = std::fs::File::create(file_path).map_err(
+        |error| format!("Could not create replay file '{}': {}", file_path.display(), error))?;
+    serde_json::to_writer(std::io::BufWriter::new(file), self).map_err(
+        |error| format!("Could not write replay file '{}': {}", file_path.display(), error))?;
+    return Ok(());
+  }
+
+  pub fn record(&mut self, input: input::TickInput) {
+    if self.events.last().map_or(true, |x| x.input != input) {
+      self.events.push(ReplayEvent{tick: self.number_of_ticks, input: input});
+    }
+
+    self.number_of_ticks += 1;
+  }
+
+  pub fn seed(&self) -> u64 {
+    return self.seed;
+  }
+
+  pub fn difficulty(&self) -> game::GameDifficulty {
+    return self.difficulty;
+  }
+
+  pub fn level(&self) -> &str {
+    return &self.level;
+  }
+
+  pub fn number_of_ticks(&self) -> u64 {
+    return self.number_of_ticks;
+  }
+}
+
+fn default_level() -> String {
+  return level::DEFAULT_LEVEL_NAME.to_string();
+}
+
+fn is_default_level(level: &str) -> bool {
+  return level == level::DEFAULT_LEVEL_NAME;
+}
+
+impl ReplayPlayer {
+  pub fn new(replay: Replay) -> ReplayPlayer {
+    return ReplayPlayer{
+      replay: replay,
+      tick: 0,
+      next_event_index: 0,
+      input: input::TickInput::default(),
+    };
+  }
+
+  pub fn next_input(&mut self) -> Option<input::TickInput> {
+    if self.finished() { return None; }
+
+    while (self.next_event_index < self.replay.events.len())
+          && (self.replay.events[self.next_event_index].tick <= self.tick) {
+      self.input = self.replay.events[self.next_event_index].input;
+      self.next_event_index += 1;
+    }
+
+    self.tick += 1;
+    return Some(self.input);
+  }
+
+  pub fn finished(&self) -> bool {
+    return self.tick >= self.replay.number_of_ticks;
+  }
+
+  pub fn replay(&self) -> &Replay {
+    return &self.replay;
+  }
+}
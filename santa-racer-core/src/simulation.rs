@@ -0,0 +1,299 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use rand::SeedableRng;
+
+use crate::*;
+use crate::asset::Point;
+use crate::clock::GameClock;
+
+pub struct Simulation<'a> {
+  asset_library: &'a asset::AssetLibrary<'a>,
+
+  clock: clock::ManualClock,
+  rng: rand::rngs::StdRng,
+  seed: u64,
+  difficulty: game::GameDifficulty,
+  number_of_ticks: u64,
+  running: bool,
+
+  events: event::EventQueue,
+  last_events: Vec<event::GameEvent>,
+  audio: audio::GameAudio<'a>,
+  statistics: statistics::Statistics,
+  achievements: achievement::Achievements,
+
+  score: ui::Score<'a>,
+  landscape: level::Landscape<'a>,
+  level: level::Level<'a>,
+  sleigh: sleigh::Sleigh<'a>,
+
+  canvas_size: Point,
+  tick_duration: std::time::Duration,
+  countdown_duration: std::time::Duration,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SimulationOutcome {
+  Won,
+  LostDueToDamage,
+  LostDueToTime,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SimulationResult {
+  outcome: Option<SimulationOutcome>,
+  score_points: f64,
+  gift_points: f64,
+  damage_points: f64,
+  number_of_ticks: u64,
+}
+
+impl<'a> Simulation<'a> {
+  pub fn new(asset_library: &'a asset::AssetLibrary<'a>, seed: u64) -> Simulation<'a> {
+    let canvas_size = Point::new(640.0, 480.0);
+    let tick_duration = std::time::Duration::from_secs_f64(1.0 / 120.0);
+    let clock = clock::ManualClock::new(tick_duration);
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    let score = ui::Score::new(asset_library, canvas_size, &clock);
+    let landscape = level::Landscape::new(asset_library, &clock);
+    let level = level::Level::new(asset_library, level::DEFAULT_LEVEL_NAME, canvas_size, &clock,
+        &mut rng);
+    let sleigh = sleigh::Sleigh::new(asset_library, canvas_size, &clock, &mut rng);
+
+    return Simulation{
+      asset_library: asset_library,
+
+      clock: clock,
+      rng: rng,
+      seed: seed,
+      difficulty: game::GameDifficulty::Easy,
+      number_of_ticks: 0,
+      running: false,
+
+      events: event::EventQueue::new(),
+      last_events: Vec::new(),
+      audio: audio::GameAudio::new(asset_library, canvas_size),
+      statistics: statistics::Statistics::new(),
+      achievements: achievement::Achievements::new(),
+
+      score: score,
+      landscape: landscape,
+      level: level,
+      sleigh: sleigh,
+
+      canvas_size: canvas_size,
+      tick_duration: tick_duration,
+      countdown_duration: std::time::Duration::from_secs_f64(3.0),
+    };
+  }
+
+  pub fn run_replay(asset_library: &'a asset::AssetLibrary<'a>,
+        replay: &replay::Replay) -> SimulationResult {
+    let mut simulation = Simulation::new(asset_library, replay.seed());
+    let mut replay_player = replay::ReplayPlayer::new(replay.clone());
+    simulation.start_game(replay.level(), replay.difficulty(), replay.seed());
+
+    while let Some(input) = replay_player.next_input() {
+      simulation.tick();
+      simulation.check_input(&input);
+      simulation.do_logic();
+      if simulation.outcome().is_some() { break; }
+    }
+
+    return simulation.result();
+  }
+
+  pub fn start_game(&mut self, level_name: &str, difficulty: game::GameDifficulty, seed: u64) {
+    let game_start_instant = self.clock.now() + self.countdown_duration;
+
+    // loaded before seeding, so that the random numbers of the run do not depend on it
+    if self.level.name() != level_name {
+      self.level = level::Level::new(self.asset_library, level_name, self.canvas_size,
+          &self.clock, &mut self.rng);
+    }
+
+    self.difficulty = difficulty;
+    self.seed = seed;
+    self.rng = rand::rngs::StdRng::seed_from_u64(seed);
+    self.number_of_ticks = 0;
+    self.running = true;
+    self.events.clear();
+    self.last_events.clear();
+    self.statistics.reset();
+    self.achievements.start_game();
+
+    self.score.start_game(game_start_instant, &self.clock);
+    self.landscape.start_game(game_start_instant);
+    self.level.start_game(game_start_instant, &self.clock, &mut self.rng);
+    self.sleigh.start_game(game_start_instant, &self.clock, &mut self.rng);
+  }
+
+  pub fn start_menu(&mut self) {
+    self.running = false;
+    self.events.clear();
+    self.last_events.clear();
+
+    self.score.start_menu(&self.clock);
+    self.landscape.start_menu(&self.clock);
+    self.level.start_menu(&self.clock);
+    self.sleigh.start_menu(&self.clock);
+  }
+
+  pub fn tick(&mut self) {
+    self.clock.tick();
+    self.number_of_ticks += 1;
+  }
+
+  pub fn check_input(&mut self, input: &input::TickInput) {
+    self.sleigh.check_input(input, &self.clock);
+
+    if input.drop_gift() {
+      self.sleigh.drop_gift(self.asset_library, &self.level, self.difficulty, &self.clock,
+          &mut self.rng);
+    }
+  }
+
+  pub fn do_logic(&mut self) {
+    self.score.do_logic(&self.clock);
+    self.landscape.do_logic(&self.level, &self.clock);
+    self.level.do_logic(self.asset_library, &mut self.events, &mut self.landscape,
+        &mut self.sleigh, &self.clock, &mut self.rng);
+    self.sleigh.do_logic(&mut self.events, &mut self.level, &self.clock, &mut self.rng);
+    self.dispatch_events();
+  }
+
+  fn dispatch_events(&mut self) {
+    self.last_events.clear();
+
+    for event in self.events.drain() {
+      self.audio.handle_event(&event);
+      self.score.handle_event(&event);
+
+      if self.running {
+        self.statistics.handle_event(&event);
+        self.achievements.handle_event(&event);
+      }
+
+      self.last_events.push(event);
+    }
+  }
+
+  pub fn outcome(&self) -> Option<SimulationOutcome> {
+    return if self.score.won() {
+      Some(SimulationOutcome::Won)
+    } else if self.score.lost_due_to_damage() {
+      Some(SimulationOutcome::LostDueToDamage)
+    } else if self.score.lost_due_to_time() {
+      Some(SimulationOutcome::LostDueToTime)
+    } else {
+      None
+    };
+  }
+
+  pub fn result(&self) -> SimulationResult {
+    return SimulationResult{
+      outcome: self.outcome(),
+      score_points: self.score.score_points(),
+      gift_points: self.score.gift_points(),
+      damage_points: self.score.damage_points(),
+      number_of_ticks: self.number_of_ticks,
+    };
+  }
+
+  pub fn now(&self) -> std::time::Instant {
+    return self.clock.now();
+  }
+
+  pub fn seed(&self) -> u64 {
+    return self.seed;
+  }
+
+  pub fn difficulty(&self) -> game::GameDifficulty {
+    return self.difficulty;
+  }
+
+  pub fn number_of_ticks(&self) -> u64 {
+    return self.number_of_ticks;
+  }
+
+  pub fn events(&self) -> &Vec<event::GameEvent> {
+    return &self.last_events;
+  }
+
+  pub fn statistics(&self) -> &statistics::Statistics {
+    return &self.statistics;
+  }
+
+  pub fn achievements(&self) -> &achievement::Achievements {
+    return &self.achievements;
+  }
+
+  pub fn achievements_mut(&mut self) -> &mut achievement::Achievements {
+    return &mut self.achievements;
+  }
+
+  pub fn score(&self) -> &ui::Score<'a> {
+    return &self.score;
+  }
+
+  pub fn landscape(&self) -> &level::Landscape<'a> {
+    return &self.landscape;
+  }
+
+  pub fn level(&self) -> &level::Level<'a> {
+    return &self.level;
+  }
+
+  pub fn sleigh(&self) -> &sleigh::Sleigh<'a> {
+    return &self.sleigh;
+  }
+
+  pub fn canvas_size(&self) -> Point {
+    return self.canvas_size;
+  }
+
+  pub fn tick_duration(&self) -> std::time::Duration {
+    return self.tick_duration;
+  }
+}
+
+impl SimulationResult {
+  pub fn outcome(&self) -> Option<SimulationOutcome> {
+    return self.outcome;
+  }
+
+  pub fn score_points(&self) -> f64 {
+    return self.score_points;
+  }
+
+  pub fn gift_points(&self) -> f64 {
+    return self.gift_points;
+  }
+
+  pub fn damage_points(&self) -> f64 {
+    return self.damage_points;
+  }
+
+  pub fn number_of_ticks(&self) -> u64 {
+    return self.number_of_ticks;
+  }
+}
+
+impl std::fmt::Display for SimulationResult {
+  fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+    let outcome = match self.outcome {
+      Some(SimulationOutcome::Won) => locale::Message::ResultWon,
+      Some(SimulationOutcome::LostDueToDamage) => locale::Message::ResultLostDueToDamage,
+      Some(SimulationOutcome::LostDueToTime) => locale::Message::ResultLostDueToTime,
+      None => locale::Message::ResultAborted,
+    };
+
+    return write!(formatter, "{}", locale::Message::ResultSummary.format(&[&outcome.text(),
+        &format!("{:.0}", self.score_points), &self.number_of_ticks]));
+  }
+}
@@ -11,17 +11,18 @@ use crate::*;
 use crate::asset::Point;
 
 pub struct Sleigh<'a> {
-  sleigh_image: &'a asset::Image<'a>,
-  reindeer_image: &'a asset::Image<'a>,
-  electrocuted_sleigh_image: &'a asset::Image<'a>,
-  electrocuted_reindeer_image: &'a asset::Image<'a>,
-  shield_image: &'a asset::Image<'a>,
+  sleigh_image: &'a asset::Image,
+  reindeer_image: &'a asset::Image,
+  electrocuted_sleigh_image: &'a asset::Image,
+  electrocuted_reindeer_image: &'a asset::Image,
+  shield_image: &'a asset::Image,
   canvas_size: asset::Point,
 
   game_mode: game::GameMode,
 
   size: Point,
   position: Point,
+  previous_position: Point,
   velocity: Point,
   velocity_point1: Point,
   velocity_point2: Point,
@@ -52,7 +53,6 @@ pub struct Sleigh<'a> {
   menu_start_instant: std::time::Instant,
   last_update_instant: std::time::Instant,
 
-  chimneys: Vec<gift::Chimney>,
   gifts: Vec<gift::Gift<'a>>,
   stars: Vec<Star<'a>>,
 
@@ -80,10 +80,10 @@ pub struct Sleigh<'a> {
 }
 
 pub struct Star<'a> {
-  image: &'a asset::Image<'a>,
-  small_image: &'a asset::Image<'a>,
-  drunk_image: &'a asset::Image<'a>,
-  small_drunk_image: &'a asset::Image<'a>,
+  image: &'a asset::Image,
+  small_image: &'a asset::Image,
+  drunk_image: &'a asset::Image,
+  small_drunk_image: &'a asset::Image,
 
   position: Point,
   frame: f64,
@@ -100,16 +100,17 @@ pub struct Star<'a> {
 }
 
 impl<'a> Sleigh<'a> {
-  pub fn new(asset_library: &'a asset::AssetLibrary<'a>, canvas_size: Point) -> Sleigh<'a> {
+  pub fn new(asset_library: &'a asset::AssetLibrary<'a>, canvas_size: Point,
+        clock: &dyn clock::GameClock, rng: &mut rand::rngs::StdRng) -> Sleigh<'a> {
     let sleigh_image = asset_library.get_image("sleigh");
     let reindeer_image = asset_library.get_image("reindeer");
     let reindeer_offset = Point::new(10.0, 3.0);
     let size = Point::new(sleigh_image.height() + reindeer_image.height() +
         reindeer_offset.x(), sleigh_image.height());
-    let now = std::time::Instant::now();
+    let now = clock.now();
     let mut stars: Vec<Star<'a>> = Vec::new();
 
-    for _ in 0 .. 67 { stars.push(Star::new(asset_library)); }
+    for _ in 0 .. 67 { stars.push(Star::new(asset_library, clock)); }
 
     return Sleigh{
       sleigh_image: sleigh_image,
@@ -123,13 +124,14 @@ impl<'a> Sleigh<'a> {
 
       size: size,
       position: Point::zero(),
+      previous_position: Point::zero(),
       velocity: Point::zero(),
       velocity_point1: Point::zero(),
       velocity_point2: Point::zero(),
-      velocity_x_instant1: std::time::Instant::now(),
-      velocity_x_instant2: std::time::Instant::now(),
-      velocity_y_instant1: std::time::Instant::now(),
-      velocity_y_instant2: std::time::Instant::now(),
+      velocity_x_instant1: now,
+      velocity_x_instant2: now,
+      velocity_y_instant1: now,
+      velocity_y_instant2: now,
       sleigh_frame: 0.0,
       reindeer_frame: 0.0,
       shield_frame: 0.0,
@@ -153,7 +155,6 @@ impl<'a> Sleigh<'a> {
       menu_start_instant: now,
       last_update_instant: now,
 
-      chimneys: Sleigh::load_chimneys(asset_library),
       gifts: Vec::new(),
       stars: stars,
 
@@ -174,94 +175,98 @@ impl<'a> Sleigh<'a> {
       electrocuted_duration: std::time::Duration::from_secs_f64(1.0),
       invincible_blink_period_duration: std::time::Duration::from_secs_f64(0.5),
       menu_period: Point::new(30.0, 20.0),
-      menu_offset_angle: Point::new(rand::thread_rng().gen_range(0.0, 2.0 * std::f64::consts::PI),
-        rand::thread_rng().gen_range(0.0, 2.0 * std::f64::consts::PI)),
+      menu_offset_angle: Point::new(rng.gen_range(0.0, 2.0 * std::f64::consts::PI),
+        rng.gen_range(0.0, 2.0 * std::f64::consts::PI)),
       menu_min_position: Point::new(50.0, 50.0),
       menu_max_position: Point::new(450.0, 200.0),
       game_start_position: Point::new(50.0, 100.0),
     };
   }
 
-  fn load_chimneys(asset_library: &'a asset::AssetLibrary) ->
-        Vec<gift::Chimney> {
-    let data = asset_library.get_data("chimneys");
-    let mut chimneys = Vec::new();
-    assert!(data.len() % 4 == 0, "Length of chimney hit box data not divisible by 4");
-
-    for i in 0 .. data.len() / 4 {
-      chimneys.push(gift::Chimney::new(Point::new(data[4 * i], data[4 * i + 1]),
-          Point::new(data[4 * i + 2], 20.0), data[4 * i + 3]));
-    }
-
-    return chimneys;
-  }
-
-  pub fn start_game(&mut self, game_start_instant: std::time::Instant) {
+  pub fn start_game(&mut self, game_start_instant: std::time::Instant,
+        clock: &dyn clock::GameClock, rng: &mut rand::rngs::StdRng) {
+    let now = clock.now();
     self.game_mode = game::GameMode::Running;
     self.position = self.game_start_position;
+    self.previous_position = self.position;
     self.velocity = Point::zero();
     self.velocity_point1 = Point::zero();
     self.velocity_point2 = Point::zero();
+    self.velocity_x_instant1 = now;
+    self.velocity_x_instant2 = now;
+    self.velocity_y_instant1 = now;
+    self.velocity_y_instant2 = now;
+    self.sleigh_frame = 0.0;
+    self.reindeer_frame = 0.0;
+    self.shield_frame = 0.0;
     self.counting_down = true;
+    self.bonus = false;
+    self.shield = false;
     self.drunk = false;
     self.invincible = false;
     self.immobile = false;
+    self.electrocuted = false;
     self.game_start_instant = game_start_instant;
-    for star in &mut self.stars { star.reset_in_between(self.position, self.size, self.drunk); }
+    self.last_gift_instant = now.checked_sub(self.new_gift_wait_duration).unwrap_or(now);
+    self.last_update_instant = now;
+    self.gifts.clear();
+    for star in &mut self.stars {
+      star.reset_in_between(self.position, self.size, self.drunk, clock, rng);
+    }
   }
 
-  pub fn start_menu(&mut self) {
+  pub fn start_menu(&mut self, clock: &dyn clock::GameClock) {
     self.game_mode = game::GameMode::Menu;
     self.counting_down = false;
     self.drunk = false;
     self.invincible = false;
     self.immobile = false;
-    self.menu_start_instant = std::time::Instant::now();
+    self.menu_start_instant = clock.now();
     for star in &mut self.stars { star.frame = -1.0; }
   }
 
-  pub fn check_keyboard_state(&mut self, keyboard_state: &sdl2::keyboard::KeyboardState) {
+  pub fn check_input(&mut self, input: &input::TickInput, clock: &dyn clock::GameClock) {
     if (self.game_mode == game::GameMode::Menu) || self.immobile || self.counting_down { return; }
     let drunk_factor = if self.drunk { -1.0 } else { 1.0 };
 
-    if keyboard_state.is_scancode_pressed(sdl2::keyboard::Scancode::Left) {
-      self.update_velocity_x(-drunk_factor);
-    } else if keyboard_state.is_scancode_pressed(sdl2::keyboard::Scancode::Right) {
-      self.update_velocity_x(drunk_factor);
+    if input.left() {
+      self.update_velocity_x(-drunk_factor, clock);
+    } else if input.right() {
+      self.update_velocity_x(drunk_factor, clock);
     } else {
-      self.update_velocity_x(0.0);
+      self.update_velocity_x(drunk_factor * input.steer_x(), clock);
     }
 
-    if keyboard_state.is_scancode_pressed(sdl2::keyboard::Scancode::Up) {
-      self.update_velocity_y(-drunk_factor);
-    } else if keyboard_state.is_scancode_pressed(sdl2::keyboard::Scancode::Down) {
-      self.update_velocity_y(drunk_factor);
+    if input.up() {
+      self.update_velocity_y(-drunk_factor, clock);
+    } else if input.down() {
+      self.update_velocity_y(drunk_factor, clock);
     } else {
-      self.update_velocity_y(0.0);
+      self.update_velocity_y(drunk_factor * input.steer_y(), clock);
     }
   }
 
-  fn update_velocity_x(&mut self, sign: f64) {
-    let now = std::time::Instant::now();
+  fn update_velocity_x(&mut self, direction: f64, clock: &dyn clock::GameClock) {
+    let now = clock.now();
     let velocity_x = self.get_velocity_x(now);
 
     self.velocity_x_instant1 = now;
     self.velocity_point1 = Point::new(velocity_x, self.velocity_point1.y());
 
-    let target_velocity_x = sign * self.max_velocity.x();
+    let target_velocity_x = direction * self.max_velocity.x();
     self.velocity_x_instant2 = now + std::time::Duration::from_secs_f64(
         (target_velocity_x - velocity_x).abs() / self.max_acceleration.x());
     self.velocity_point2 = Point::new(target_velocity_x, self.velocity_point2.y());
   }
 
-  fn update_velocity_y(&mut self, sign: f64) {
-    let now = std::time::Instant::now();
+  fn update_velocity_y(&mut self, direction: f64, clock: &dyn clock::GameClock) {
+    let now = clock.now();
     let velocity_y = self.get_velocity_y(now);
 
     self.velocity_y_instant1 = now;
     self.velocity_point1 = Point::new(self.velocity_point1.x(), velocity_y);
 
-    let target_velocity_y = sign * self.max_velocity.y();
+    let target_velocity_y = direction * self.max_velocity.y();
     self.velocity_y_instant2 = now + std::time::Duration::from_secs_f64(
         (target_velocity_y - velocity_y).abs() / self.max_acceleration.y());
     self.velocity_point2 = Point::new(self.velocity_point2.x(), target_velocity_y);
@@ -292,54 +297,57 @@ impl<'a> Sleigh<'a> {
   }
 
   pub fn drop_gift(&mut self, asset_library: &'a asset::AssetLibrary, level: &level::Level,
-        game_difficulty: game::GameDifficulty) {
-    let now = std::time::Instant::now();
+        game_difficulty: game::GameDifficulty, clock: &dyn clock::GameClock,
+        rng: &mut rand::rngs::StdRng) {
+    let now = clock.now();
     if now - self.last_gift_instant < self.new_gift_wait_duration { return; }
     self.gifts.push(gift::Gift::new(
-        asset_library, level, self, self.canvas_size, game_difficulty));
+        asset_library, level, self, self.canvas_size, game_difficulty, clock, rng));
     self.last_gift_instant = now;
   }
 
-  pub fn start_bonus(&mut self) {
+  pub fn start_bonus(&mut self, clock: &dyn clock::GameClock) {
     self.bonus = true;
-    self.bonus_reset_instant = std::time::Instant::now() + self.bonus_duration;
+    self.bonus_reset_instant = clock.now() + self.bonus_duration;
   }
 
-  pub fn start_shield(&mut self) {
+  pub fn start_shield(&mut self, clock: &dyn clock::GameClock) {
     self.shield = true;
-    self.shield_reset_instant = std::time::Instant::now() + self.shield_duration;
+    self.shield_reset_instant = clock.now() + self.shield_duration;
     self.shield_frame = 0.0;
   }
 
-  pub fn start_drunk(&mut self) {
+  pub fn start_drunk(&mut self, clock: &dyn clock::GameClock) {
     self.drunk = true;
-    self.drunk_reset_instant = std::time::Instant::now() + self.drunk_duration;
+    self.drunk_reset_instant = clock.now() + self.drunk_duration;
   }
 
-  pub fn start_invincible(&mut self) {
+  pub fn start_invincible(&mut self, clock: &dyn clock::GameClock) {
     self.invincible = true;
-    self.invincible_reset_instant = std::time::Instant::now() + self.invincible_duration;
+    self.invincible_reset_instant = clock.now() + self.invincible_duration;
   }
 
-  pub fn start_electrocuted(&mut self) {
+  pub fn start_electrocuted(&mut self, clock: &dyn clock::GameClock) {
     self.electrocuted = true;
-    self.electrocuted_reset_instant = std::time::Instant::now() + self.electrocuted_duration;
+    self.electrocuted_reset_instant = clock.now() + self.electrocuted_duration;
   }
 
-  pub fn start_invincible_and_immobile(&mut self) {
+  pub fn start_invincible_and_immobile(&mut self, clock: &dyn clock::GameClock) {
+    let now = clock.now();
     self.invincible = true;
     self.immobile = true;
-    self.invincible_reset_instant = std::time::Instant::now() + self.immobile_duration
-        + self.invincible_duration;
-    self.immobile_reset_instant = std::time::Instant::now() + self.immobile_duration;
+    self.invincible_reset_instant = now + self.immobile_duration + self.invincible_duration;
+    self.immobile_reset_instant = now + self.immobile_duration;
     self.velocity = Point::new(0.0, -self.max_velocity.y());
     self.velocity_point1 = self.velocity;
     self.velocity_point2 = self.velocity;
   }
 
-  pub fn do_logic(&mut self, score: &mut ui::Score, level: &mut level::Level) {
-    let now = std::time::Instant::now();
+  pub fn do_logic(&mut self, events: &mut event::EventQueue, level: &mut level::Level,
+        clock: &dyn clock::GameClock, rng: &mut rand::rngs::StdRng) {
+    let now = clock.now();
     let seconds_since_last_update = (now - self.last_update_instant).as_secs_f64();
+    self.previous_position = self.position;
 
     if self.counting_down && (now >= self.game_start_instant) { self.counting_down = false; }
     if self.bonus && (now >= self.bonus_reset_instant) { self.bonus = false; }
@@ -398,7 +406,7 @@ impl<'a> Sleigh<'a> {
       let mut i = 0;
 
       while i < self.gifts.len() {
-        self.gifts[i].do_logic(score, level, &self.chimneys);
+        self.gifts[i].do_logic(events, level, clock);
 
         if self.gifts[i].mode() == gift::GiftMode::CanBeDeleted {
           self.gifts.remove(i);
@@ -409,7 +417,7 @@ impl<'a> Sleigh<'a> {
     }
 
     for star in &mut self.stars {
-      star.do_logic(self.position, self.size, self.drunk);
+      star.do_logic(self.position, self.size, self.drunk, clock, rng);
     }
 
     self.last_update_instant = now;
@@ -423,10 +431,11 @@ impl<'a> Sleigh<'a> {
         self.sleigh_frame, image, position, frame);
   }
 
-  pub fn draw<RenderTarget: sdl2::render::RenderTarget>(
-        &self, canvas: &mut sdl2::render::Canvas<RenderTarget>, font: &ui::Font,
-        level: &level::Level) {
+  pub fn draw(&self, renderer: &mut dyn render::Renderer, font: &ui::Font,
+        level: &level::Level, interpolation: f64) {
     if self.invincible_blink { return; }
+    let position = self.previous_position
+        + interpolation * (self.position - self.previous_position);
 
     if self.electrocuted {
       let electrocuted_sleigh_offset = Point::new(
@@ -439,40 +448,41 @@ impl<'a> Sleigh<'a> {
           - (self.electrocuted_reindeer_image.width() - self.reindeer_image.width()) / 2.0,
           self.electrocuted_offset.y()
           - (self.electrocuted_reindeer_image.height() - self.reindeer_image.height()) / 2.0);
-      self.electrocuted_sleigh_image.draw(canvas, Point::new(
-            self.position.x() + electrocuted_sleigh_offset.x(),
-            self.position.y() + electrocuted_sleigh_offset.y()),
+      self.electrocuted_sleigh_image.draw(renderer, Point::new(
+            position.x() + electrocuted_sleigh_offset.x(),
+            position.y() + electrocuted_sleigh_offset.y()),
           self.sleigh_frame);
-      self.electrocuted_reindeer_image.draw(canvas, Point::new(
-            self.position.x() + self.sleigh_image.width() + self.reindeer_offset.x()
+      self.electrocuted_reindeer_image.draw(renderer, Point::new(
+            position.x() + self.sleigh_image.width() + self.reindeer_offset.x()
             + electrocuted_reindeer_offset.x(),
-            self.position.y() + self.reindeer_offset.y() + electrocuted_reindeer_offset.y()),
+            position.y() + self.reindeer_offset.y() + electrocuted_reindeer_offset.y()),
           self.reindeer_frame);
-      self.electrocuted_reindeer_image.draw(canvas, Point::new(
-            self.position.x() + self.sleigh_image.width() + electrocuted_reindeer_offset.x(),
-            self.position.y() + self.reindeer_offset.y() + electrocuted_reindeer_offset.y()),
+      self.electrocuted_reindeer_image.draw(renderer, Point::new(
+            position.x() + self.sleigh_image.width() + electrocuted_reindeer_offset.x(),
+            position.y() + self.reindeer_offset.y() + electrocuted_reindeer_offset.y()),
           self.reindeer_frame);
     }
 
-    self.sleigh_image.draw(canvas, self.position, self.sleigh_frame);
-    self.reindeer_image.draw(canvas,
-        Point::new(self.position.x() + self.sleigh_image.width() + self.reindeer_offset.x(),
-          self.position.y() + self.reindeer_offset.y()), self.reindeer_frame);
-    self.reindeer_image.draw(canvas,
-        Point::new(self.position.x() + self.sleigh_image.width(),
-          self.position.y() + self.reindeer_offset.y()), self.reindeer_frame);
+    self.sleigh_image.draw(renderer, position, self.sleigh_frame);
+    self.reindeer_image.draw(renderer,
+        Point::new(position.x() + self.sleigh_image.width() + self.reindeer_offset.x(),
+          position.y() + self.reindeer_offset.y()), self.reindeer_frame);
+    self.reindeer_image.draw(renderer,
+        Point::new(position.x() + self.sleigh_image.width(),
+          position.y() + self.reindeer_offset.y()), self.reindeer_frame);
 
-    for gift in &self.gifts { gift.draw(canvas, level); }
-    for star in &self.stars { star.draw(canvas, 0.0); }
+    let level_offset_x = level.interpolated_offset_x(interpolation);
+    for gift in &self.gifts { gift.draw(renderer, level_offset_x); }
+    for star in &self.stars { star.draw(renderer, 0.0); }
 
     if self.shield {
-      self.shield_image.draw(canvas, Point::new(self.position.x() + self.shield_offset.x(),
-          self.position.y() + self.shield_offset.y()), self.shield_frame);
+      self.shield_image.draw(renderer, Point::new(position.x() + self.shield_offset.x(),
+          position.y() + self.shield_offset.y()), self.shield_frame);
     }
 
     if self.counting_down {
-      font.draw(canvas, Point::new(self.position.x() + self.countdown_counter_offset_x,
-          self.position.y() + self.size.y() / 2.0), format!("{}", self.countdown_counter),
+      font.draw(renderer, Point::new(position.x() + self.countdown_counter_offset_x,
+          position.y() + self.size.y() / 2.0), format!("{}", self.countdown_counter),
           ui::Alignment::CenterRight);
     }
   }
@@ -515,7 +525,8 @@ impl<'a> Sleigh<'a> {
 }
 
 impl<'a> Star<'a> {
-  pub fn new(asset_library: &'a asset::AssetLibrary<'a>) -> Star<'a> {
+  pub fn new(asset_library: &'a asset::AssetLibrary<'a>, clock: &dyn clock::GameClock) ->
+        Star<'a> {
     return Star{
       image: asset_library.get_image("star"),
       small_image: asset_library.get_image("smallStar"),
@@ -528,7 +539,7 @@ impl<'a> Star<'a> {
       small: false,
       drunk: false,
       small_probability: 0.5,
-      last_update_instant: std::time::Instant::now(),
+      last_update_instant: clock.now(),
 
       min_offset: Point::new(-150.0, -10.0),
       max_offset: Point::new(-10.0, 0.0),
@@ -537,29 +548,33 @@ impl<'a> Star<'a> {
     };
   }
 
-  pub fn do_logic(&mut self, sleigh_position: Point, sleigh_size: Point, drunk: bool) {
-    if self.frame == -1.0 { self.reset_in_between(sleigh_position, sleigh_size, drunk); }
+  pub fn do_logic(&mut self, sleigh_position: Point, sleigh_size: Point, drunk: bool,
+        clock: &dyn clock::GameClock, rng: &mut rand::rngs::StdRng) {
+    if self.frame == -1.0 {
+      self.reset_in_between(sleigh_position, sleigh_size, drunk, clock, rng);
+    }
 
-    let now = std::time::Instant::now();
+    let now = clock.now();
     let seconds_since_last_update = (now - self.last_update_instant).as_secs_f64();
 
     self.frame += seconds_since_last_update * self.frame_speed;
 
     if self.frame >= self.max_frame {
-      self.reset_from_beginning(sleigh_position, sleigh_size, drunk);
+      self.reset_from_beginning(sleigh_position, sleigh_size, drunk, clock, rng);
     }
 
     self.last_update_instant = now;
   }
 
-  fn reset_from_beginning(&mut self, sleigh_position: Point, sleigh_size: Point, drunk: bool) {
+  fn reset_from_beginning(&mut self, sleigh_position: Point, sleigh_size: Point, drunk: bool,
+        clock: &dyn clock::GameClock, rng: &mut rand::rngs::StdRng) {
     let offset_x = if self.min_offset.x() < self.max_offset.x() {
-          rand::thread_rng().gen_range(self.min_offset.x(), self.max_offset.x())
+          rng.gen_range(self.min_offset.x(), self.max_offset.x())
         } else {
           self.min_offset.x()
         };
     let offset_y = if self.min_offset.y() < self.max_offset.y() {
-          rand::thread_rng().gen_range(self.min_offset.y(), self.max_offset.y())
+          rng.gen_range(self.min_offset.y(), self.max_offset.y())
         } else {
           self.min_offset.y()
         };
@@ -567,27 +582,27 @@ impl<'a> Star<'a> {
     self.position = Point::new(sleigh_position.x() + sleigh_size.x() + offset_x,
         sleigh_position.y() + sleigh_size.y() + offset_y);
     self.frame = 0.0;
-    self.max_frame = rand::thread_rng().gen_range(
+    self.max_frame = rng.gen_range(
         self.image.total_number_of_frames() as f64, self.max_max_frame);
-    self.small = rand::thread_rng().gen_range(0.0, 1.0) < self.small_probability;
+    self.small = rng.gen_range(0.0, 1.0) < self.small_probability;
     self.drunk = drunk;
-    self.last_update_instant = std::time::Instant::now();
+    self.last_update_instant = clock.now();
   }
 
-  fn reset_in_between(&mut self, sleigh_position: Point, sleigh_size: Point, drunk: bool) {
-    self.reset_from_beginning(sleigh_position, sleigh_size, drunk);
-    self.frame = rand::thread_rng().gen_range(0.0, self.max_max_frame);
+  fn reset_in_between(&mut self, sleigh_position: Point, sleigh_size: Point, drunk: bool,
+        clock: &dyn clock::GameClock, rng: &mut rand::rngs::StdRng) {
+    self.reset_from_beginning(sleigh_position, sleigh_size, drunk, clock, rng);
+    self.frame = rng.gen_range(0.0, self.max_max_frame);
   }
 
-  pub fn draw<RenderTarget: sdl2::render::RenderTarget>(
-        &self, canvas: &mut sdl2::render::Canvas<RenderTarget>, level_offset_x: f64) {
+  pub fn draw(&self, renderer: &mut dyn render::Renderer, level_offset_x: f64) {
     if self.frame >= self.image.total_number_of_frames() as f64 { return; }
 
     let image = if self.small { if self.drunk { self.small_drunk_image } else { self.small_image } }
         else { if self.drunk { self.drunk_image } else { self.image } };
     let position = Point::new(self.position.x() - level_offset_x, self.position.y());
 
-    image.draw(canvas, position, self.frame);
+    image.draw(renderer, position, self.frame);
   }
 
   pub fn small_probability(&self) -> f64 {
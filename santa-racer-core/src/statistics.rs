@@ -0,0 +1,68 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::*;
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Statistics {
+  gifts_delivered: u32,
+  bonus_gifts_delivered: u32,
+  gifts_missed: u32,
+  tile_collisions: u32,
+  npc_collisions: u32,
+  power_ups_collected: u32,
+}
+
+impl Statistics {
+  pub fn new() -> Statistics {
+    return Statistics::default();
+  }
+
+  pub fn reset(&mut self) {
+    *self = Statistics::new();
+  }
+
+  pub fn handle_event(&mut self, event: &event::GameEvent) {
+    match *event {
+      event::GameEvent::GiftDelivered{bonus, ..} => {
+        self.gifts_delivered += 1;
+        if bonus { self.bonus_gifts_delivered += 1; }
+      },
+      event::GameEvent::GiftHitGround{..} => { self.gifts_missed += 1; },
+      event::GameEvent::SleighHitTile{..} => { self.tile_collisions += 1; },
+      event::GameEvent::SleighHitNpc{..} => { self.npc_collisions += 1; },
+      event::GameEvent::PowerUpCollected{..} => {
+        self.power_ups_collected += 1;
+      },
+      _ => {},
+    }
+  }
+
+  pub fn gifts_delivered(&self) -> u32 {
+    return self.gifts_delivered;
+  }
+
+  pub fn bonus_gifts_delivered(&self) -> u32 {
+    return self.bonus_gifts_delivered;
+  }
+
+  pub fn gifts_missed(&self) -> u32 {
+    return self.gifts_missed;
+  }
+
+  pub fn tile_collisions(&self) -> u32 {
+    return self.tile_collisions;
+  }
+
+  pub fn npc_collisions(&self) -> u32 {
+    return self.npc_collisions;
+  }
+
+  pub fn power_ups_collected(&self) -> u32 {
+    return self.power_ups_collected;
+  }
+}
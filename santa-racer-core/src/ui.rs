@@ -0,0 +1,1084 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::*;
+use crate::asset::Point;
+
+pub struct Score<'a> {
+  gift_image: &'a asset::Image,
+  damage_image: &'a asset::Image,
+  time_image: &'a asset::Image,
+  canvas_size: asset::Point,
+
+  game_mode: game::GameMode,
+
+  gift_points: f64,
+  damage_points: f64,
+  remaining_duration: std::time::Duration,
+  score_points: f64,
+  won: bool,
+  lost_due_to_damage: bool,
+  lost_due_to_time: bool,
+  game_start_instant: std::time::Instant,
+  last_update_instant: std::time::Instant,
+
+  gift_position_x: f64,
+  damage_position_x: f64,
+  time_position_x: f64,
+  margin_x: f64,
+  position_y: f64,
+  max_damage_points: f64,
+  game_duration: std::time::Duration,
+  score_points_per_gift_point: f64,
+  score_points_per_damage_point: f64,
+  score_points_per_remaining_second: f64,
+  cash_balloon_damage_points: f64,
+  heart_balloon_gift_points: f64,
+}
+
+pub struct HighscoreTable {
+  background_color: render::Color,
+  selection_color: render::Color,
+  canvas_size: asset::Point,
+
+  game_mode: game::GameMode,
+  // key of the shown table and keys of all tables in the order of switching between them
+  table_key: String,
+  table_keys: Vec<String>,
+  // whether the global highscores of the leaderboard server are shown instead of the local ones
+  global: bool,
+  new_highscore_index: usize,
+  selected_index: usize,
+  details_shown: bool,
+
+  size: Point,
+  position: Point,
+  inner_margin: Point,
+  number_of_rows: f64,
+  max_name_length: usize,
+  name_field: TextField,
+}
+
+// single-line text input, the cursor and the length limit count characters, not bytes
+pub struct TextField {
+  characters: Vec<char>,
+  cursor: usize,
+  max_length: usize,
+}
+
+// list of labels and values with a selected entry, used for options and the level selection
+pub struct OptionsMenu {
+  background_color: render::Color,
+  selection_color: render::Color,
+
+  game_mode: game::GameMode,
+  // mode in which the menu is shown
+  shown_game_mode: game::GameMode,
+  selected_index: usize,
+  waiting_for_key: bool,
+
+  size: Point,
+  position: Point,
+  inner_margin: Point,
+  number_of_rows: f64,
+}
+
+pub struct Font<'a> {
+  image: &'a asset::Image,
+  characters: String,
+  character_rects: Vec<asset::Rect>,
+  max_character_width: i32,
+  height: f64,
+}
+
+// fallback for characters without glyph, e.g., in names from imported highscores
+const FONT_REPLACEMENTS: [(&str, &str); 21] = [
+    ("\u{00c0}\u{00c1}\u{00c2}\u{00c3}\u{00c5}\u{00e0}\u{00e1}\u{00e2}\u{00e3}\u{00e5}", "A"),
+    ("\u{00c6}\u{00e6}", "AE"),
+    ("\u{00c7}\u{00e7}\u{0106}\u{0107}\u{010c}\u{010d}", "C"),
+    ("\u{00c8}\u{00c9}\u{00ca}\u{00cb}\u{00e8}\u{00e9}\u{00ea}\u{00eb}", "E"),
+    ("\u{00cc}\u{00cd}\u{00ce}\u{00cf}\u{00ec}\u{00ed}\u{00ee}\u{00ef}", "I"),
+    ("\u{00d1}\u{00f1}\u{0143}\u{0144}", "N"),
+    ("\u{00d2}\u{00d3}\u{00d4}\u{00d5}\u{00d8}\u{00f2}\u{00f3}\u{00f4}\u{00f5}\u{00f8}", "O"),
+    ("\u{0152}\u{0153}", "OE"),
+    ("\u{00df}\u{1e9e}", "SS"),
+    ("\u{0160}\u{0161}\u{015a}\u{015b}", "S"),
+    ("\u{00d9}\u{00da}\u{00db}\u{00f9}\u{00fa}\u{00fb}", "U"),
+    ("\u{00dd}\u{00fd}\u{00ff}\u{0178}", "Y"),
+    ("\u{017d}\u{017e}\u{0179}\u{017a}\u{017b}\u{017c}", "Z"),
+    ("\u{2026}", "..."),
+    ("\u{2013}\u{2014}\u{2212}~", "-"),
+    ("`\u{00b4}\u{2018}\u{2019}", "'"),
+    ("\u{00ab}\u{00bb}\u{201c}\u{201d}\u{201e}", "\""),
+    ("[{<", "("),
+    ("]}>", ")"),
+    ("\t\u{00a0}", " "),
+    ("|\\", "/"),
+];
+
+// fallback for the punctuation glyphs missing in fonts extracted by older versions of
+// tools/extractAssets.py
+const PUNCTUATION_REPLACEMENTS: [(&str, &str); 3] = [
+    (",;!?", "."),
+    ("+", "-"),
+    ("'\"()", ""),
+];
+
+#[derive(Clone, Copy)]
+pub enum Alignment {
+  TopLeft = 0,
+  TopCenter = 1,
+  TopRight = 2,
+  CenterLeft = 3,
+  Center = 4,
+  CenterRight = 5,
+  BottomLeft = 6,
+  BottomCenter = 7,
+  BottomRight = 8,
+}
+
+impl<'a> Score<'a> {
+  pub fn new(asset_library: &'a asset::AssetLibrary<'a>, canvas_size: Point,
+        clock: &dyn clock::GameClock) -> Score<'a> {
+    let gift_image = asset_library.get_image("giftScoreIcon");
+    let now = clock.now();
+
+    return Score{
+      gift_image: gift_image,
+      damage_image: asset_library.get_image("damageScoreIcon"),
+      time_image: asset_library.get_image("timeScoreIcon"),
+      canvas_size: canvas_size,
+
+      game_mode: game::GameMode::Menu,
+
+      gift_points: 0.0,
+      damage_points: 0.0,
+      remaining_duration: std::time::Duration::from_secs_f64(0.0),
+      score_points: 0.0,
+      won: false,
+      lost_due_to_time: false,
+      lost_due_to_damage: false,
+      game_start_instant: now,
+      last_update_instant: now,
+
+      gift_position_x: 0.0,
+      damage_position_x: 150.0,
+      time_position_x: 535.0,
+      margin_x: 35.0,
+      position_y: gift_image.height() / 2.0,
+      max_damage_points: 500.0,
+      game_duration: std::time::Duration::from_secs_f64(450.0),
+      score_points_per_gift_point: 1.0,
+      score_points_per_damage_point: -2.0,
+      score_points_per_remaining_second: 10.0,
+      cash_balloon_damage_points: -50.0,
+      heart_balloon_gift_points: 20.0,
+    };
+  }
+
+  pub fn start_game(&mut self, game_start_instant: std::time::Instant,
+        clock: &dyn clock::GameClock) {
+    self.game_mode = game::GameMode::Running;
+    self.gift_points = 0.0;
+    self.damage_points = 0.0;
+    self.remaining_duration = self.game_duration;
+    self.score_points = 0.0;
+    self.won = false;
+    self.lost_due_to_time = false;
+    self.lost_due_to_damage = false;
+    self.game_start_instant = game_start_instant;
+    self.last_update_instant = clock.now();
+  }
+
+  pub fn start_menu(&mut self, clock: &dyn clock::GameClock) {
+    self.start_game(clock.now(), clock);
+    self.game_mode = game::GameMode::Menu;
+  }
+
+  pub fn add_gift_points(&mut self, gift_points: f64) {
+    self.gift_points = (self.gift_points + gift_points).max(0.0);
+  }
+
+  pub fn add_damage_points(&mut self, damage_points: f64) {
+    self.damage_points = (self.damage_points + damage_points).max(0.0);
+  }
+
+  pub fn handle_event(&mut self, event: &event::GameEvent) {
+    match *event {
+      event::GameEvent::GiftDelivered{points, bonus, ..} => {
+        self.add_gift_points(if bonus { 2.0 * points } else { points });
+      },
+      event::GameEvent::GiftHitGround{damage_points, ..}
+          | event::GameEvent::SleighHitTile{damage_points, ..}
+          | event::GameEvent::SleighHitNpc{damage_points, ..} => {
+        self.add_damage_points(damage_points);
+      },
+      event::GameEvent::PowerUpCollected{kind: npc::BalloonType::Cash, ..} => {
+        self.add_damage_points(self.cash_balloon_damage_points);
+      },
+      event::GameEvent::PowerUpCollected{kind: npc::BalloonType::Heart, ..} => {
+        self.add_gift_points(self.heart_balloon_gift_points);
+      },
+      event::GameEvent::Finished => { self.set_won(true); },
+      _ => {},
+    }
+  }
+
+  pub fn do_logic(&mut self, clock: &dyn clock::GameClock) {
+    let now = clock.now();
+
+    if (self.game_mode == game::GameMode::Running) && (now >= self.game_start_instant)
+          && !self.won {
+      let duration_since_last_update = now - self.last_update_instant;
+
+      if self.remaining_duration >= duration_since_last_update {
+        self.remaining_duration -= duration_since_last_update;
+      } else {
+        self.remaining_duration = std::time::Duration::from_secs_f64(0.0);
+        self.lost_due_to_time = true;
+      }
+
+      self.lost_due_to_damage = self.damage_points > self.max_damage_points;
+    }
+
+    self.last_update_instant = now;
+  }
+
+  pub fn draw(&self, renderer: &mut dyn render::Renderer, font: &'a Font<'a>) {
+    if self.game_mode == game::GameMode::Menu {
+      font.draw(renderer, Point::zero(), locale::Message::MenuHelp.text(), Alignment::TopLeft);
+      font.draw(renderer, Point::new(self.canvas_size.x() / 2.0, 0.0),
+          locale::Message::MenuHighscores.text(), Alignment::TopCenter);
+      font.draw(renderer, Point::new(self.canvas_size.x(), 0.0),
+          locale::Message::MenuPlay.text(), Alignment::TopRight);
+      font.draw(renderer, Point::new(self.canvas_size.x() / 2.0, self.canvas_size.y()),
+          locale::Message::MenuOptions.text(), Alignment::BottomCenter);
+    } else {
+      self.gift_image.draw(renderer, Point::new(self.gift_position_x, 0.0), 0.0);
+      font.draw_monospace(renderer, Point::new(self.gift_position_x + self.margin_x,
+          self.position_y), format!("{}", self.gift_points as i32), Alignment::CenterLeft);
+
+      self.damage_image.draw(renderer, Point::new(self.damage_position_x, 0.0), 0.0);
+      font.draw_monospace(renderer, Point::new(self.damage_position_x + self.margin_x,
+          self.position_y), format!("{}", -self.damage_points as i32), Alignment::CenterLeft);
+
+      let seconds = self.remaining_duration.as_secs_f64();
+      let minutes = (seconds / 60.0).floor() as i32;
+      let seconds = (seconds % 60.0) as i32;
+      self.time_image.draw(renderer, Point::new(self.time_position_x, 0.0), 0.0);
+      font.draw_monospace(renderer, Point::new(self.time_position_x + self.margin_x,
+          self.position_y), format!("{}:{:02}", minutes, seconds), Alignment::CenterLeft);
+    }
+  }
+
+  pub fn won(&self) -> bool {
+    return self.won;
+  }
+
+  pub fn set_won(&mut self, won: bool) {
+    self.won = won;
+
+    if won {
+      self.score_points = self.score_points_per_gift_point * self.gift_points
+          + self.score_points_per_damage_point * self.damage_points
+          + self.score_points_per_remaining_second * self.remaining_duration.as_secs_f64();
+    }
+  }
+
+  pub fn lost_due_to_damage(&self) -> bool {
+    return self.lost_due_to_damage;
+  }
+
+  pub fn lost_due_to_time(&self) -> bool {
+    return self.lost_due_to_time;
+  }
+
+  pub fn score_points(&self) -> f64 {
+    return self.score_points;
+  }
+
+  pub fn gift_points(&self) -> f64 {
+    return self.gift_points;
+  }
+
+  pub fn damage_points(&self) -> f64 {
+    return self.damage_points;
+  }
+
+  pub fn remaining_duration(&self) -> std::time::Duration {
+    return self.remaining_duration;
+  }
+}
+
+impl HighscoreTable {
+  pub fn new(canvas_size: Point, table_keys: Vec<String>) -> HighscoreTable {
+    let position = Point::new(50.0, 50.0);
+    let size = Point::new(canvas_size.x() - 2.0 * position.x(), canvas_size.y() - 2.0 * position.y());
+    let max_name_length = 16;
+
+    return HighscoreTable{
+      background_color: render::Color::new(0, 0, 0, 127),
+      selection_color: render::Color::new(255, 255, 255, 63),
+      canvas_size: canvas_size,
+
+      game_mode: game::GameMode::Menu,
+      table_key: table_keys.first().cloned().unwrap_or_default(),
+      table_keys: table_keys,
+      global: false,
+      new_highscore_index: 0,
+      selected_index: 0,
+      details_shown: false,
+
+      size: size,
+      position: position,
+      inner_margin: Point::new(20.0, 20.0),
+      number_of_rows: 11.0,
+      max_name_length: max_name_length,
+      name_field: TextField::new(max_name_length),
+    };
+  }
+
+  pub fn show(&mut self, table_key: &str) {
+    self.game_mode = game::GameMode::HighscoreTable;
+    self.table_key = table_key.to_string();
+    self.global = false;
+    self.selected_index = 0;
+    self.details_shown = false;
+  }
+
+  pub fn show_global(&mut self) {
+    self.global = true;
+    self.selected_index = 0;
+    self.details_shown = false;
+  }
+
+  pub fn show_next_table(&mut self, direction: isize) {
+    if self.table_keys.is_empty() { return; }
+    let index = self.table_keys.iter().position(|x| *x == self.table_key).unwrap_or(0) as isize;
+    let number_of_tables = self.table_keys.len() as isize;
+    self.table_key = self.table_keys[
+        ((index + direction + number_of_tables) % number_of_tables) as usize].to_string();
+  }
+
+  // moving past the first or last entry continues in the adjacent table
+  pub fn select_previous(&mut self, number_of_entries: usize) {
+    if self.selected_index == 0 {
+      self.show_next_table(-1);
+      self.selected_index = number_of_entries.saturating_sub(1);
+    } else {
+      self.selected_index -= 1;
+    }
+  }
+
+  pub fn select_next(&mut self, number_of_entries: usize) {
+    if self.selected_index + 1 >= number_of_entries {
+      self.show_next_table(1);
+      self.selected_index = 0;
+    } else {
+      self.selected_index += 1;
+    }
+  }
+
+  pub fn toggle_details(&mut self) {
+    self.details_shown = !self.details_shown;
+  }
+
+  pub fn hide(&mut self) {
+    self.game_mode = game::GameMode::Menu;
+    self.details_shown = false;
+  }
+
+  // the name is prefilled with the given name, e.g., the last entered one
+  pub fn new_highscore(&mut self, table_key: &str, new_highscore_index: usize, name: &str) {
+    self.name_field.set_text(name);
+    self.game_mode = game::GameMode::NewHighscore;
+    self.table_key = table_key.to_string();
+    self.global = false;
+    self.new_highscore_index = new_highscore_index;
+    self.selected_index = new_highscore_index;
+    self.details_shown = false;
+  }
+
+  // the error is shown instead of the entries, e.g., if the leaderboard server is not reachable
+  pub fn draw(&self, renderer: &mut dyn render::Renderer, font: &Font,
+        highscores: &Result<Vec<highscore::Highscore>, String>) {
+    if (self.game_mode != game::GameMode::HighscoreTable)
+        && (self.game_mode != game::GameMode::NewHighscore) {
+      return;
+    }
+
+    renderer.fill_rect(asset::Rect::new(self.position.x() as i32, self.position.y() as i32,
+        self.size.x() as u32, self.size.y() as u32), self.background_color);
+
+    let inner_height = self.size.y() - 2.0 * self.inner_margin.y();
+    let offset_y = (inner_height - font.height()) / (self.number_of_rows - 1.0);
+
+    let title = if self.global { locale::Message::GlobalHighscoresTitle }
+        else { locale::Message::HighscoresTitle };
+    let highscores = match highscores {
+      Ok(highscores) => highscores.as_slice(),
+      Err(error) => {
+        font.draw(renderer, Point::new(self.position.x() + self.size.x() / 2.0,
+            self.position.y() + self.inner_margin.y()),
+            title.format(&[&highscore::table_label(&self.table_key)]), Alignment::TopCenter);
+        font.draw(renderer, Point::new(self.position.x() + self.size.x() / 2.0,
+            self.position.y() + self.size.y() / 2.0), error, Alignment::Center);
+        return;
+      },
+    };
+
+    if self.details_shown {
+      if let Some(highscore) = highscores.get(self.selected_index) {
+        self.draw_details(renderer, font, highscore, offset_y);
+        return;
+      }
+    }
+
+    font.draw(renderer, Point::new(self.position.x() + self.size.x() / 2.0,
+        self.position.y() + self.inner_margin.y()),
+        title.format(&[&highscore::table_label(&self.table_key)]), Alignment::TopCenter);
+
+    // the first row is the title
+    for (i, highscore) in highscores.iter().enumerate() {
+      let dst_point = Point::new(self.position.x() + self.inner_margin.x(),
+          self.position.y() + self.inner_margin.y() + offset_y * ((i + 1) as f64));
+      if (self.game_mode == game::GameMode::HighscoreTable) && (i == self.selected_index) {
+        renderer.fill_rect(asset::Rect::new(self.position.x() as i32, dst_point.y() as i32,
+            self.size.x() as u32, font.height() as u32), self.selection_color);
+      }
+
+      if (self.game_mode == game::GameMode::NewHighscore) && (i == self.new_highscore_index) {
+        self.name_field.draw(renderer, font, dst_point);
+      } else {
+        font.draw_monospace(renderer, dst_point, highscore.display_name(), Alignment::TopLeft);
+      }
+
+      let dst_point = Point::new(self.position.x() + self.size.x() - self.inner_margin.y(),
+          dst_point.y());
+      font.draw_monospace(renderer, dst_point, highscore.points().to_string(),
+          Alignment::TopRight);
+    }
+  }
+
+  fn draw_details(&self, renderer: &mut dyn render::Renderer, font: &Font,
+        highscore: &highscore::Highscore, offset_y: f64) {
+    font.draw(renderer, Point::new(self.position.x() + self.size.x() / 2.0,
+        self.position.y() + self.inner_margin.y()),
+        format!("{}. {}", self.selected_index + 1, highscore.display_name()),
+        Alignment::TopCenter);
+
+    for (i, (label, value)) in highscore.details().iter().enumerate() {
+      let dst_point = Point::new(self.position.x() + self.inner_margin.x(),
+          self.position.y() + self.inner_margin.y() + offset_y * ((i + 1) as f64));
+      font.draw(renderer, dst_point, label, Alignment::TopLeft);
+
+      let dst_point = Point::new(self.position.x() + self.size.x() - self.inner_margin.x(),
+          dst_point.y());
+      font.draw(renderer, dst_point, value, Alignment::TopRight);
+    }
+  }
+
+  pub fn table_key(&self) -> &str {
+    return &self.table_key;
+  }
+
+  pub fn new_highscore_index(&self) -> usize {
+    return self.new_highscore_index;
+  }
+
+  pub fn global(&self) -> bool {
+    return self.global;
+  }
+
+  pub fn details_shown(&self) -> bool {
+    return self.details_shown;
+  }
+
+  pub fn max_name_length(&self) -> usize {
+    return self.max_name_length;
+  }
+
+  pub fn name_field(&self) -> &TextField {
+    return &self.name_field;
+  }
+
+  pub fn name_field_mut(&mut self) -> &mut TextField {
+    return &mut self.name_field;
+  }
+}
+
+impl TextField {
+  pub fn new(max_length: usize) -> TextField {
+    return TextField{
+      characters: Vec::new(),
+      cursor: 0,
+      max_length: max_length,
+    };
+  }
+
+  // places the cursor at the end
+  pub fn set_text(&mut self, text: &str) {
+    self.characters = text.chars().take(self.max_length).collect();
+    self.cursor = self.characters.len();
+  }
+
+  // inserts the accepted characters at the cursor as long as the length limit allows
+  pub fn insert<F: Fn(char) -> bool>(&mut self, text: &str, accept: F) {
+    for character in text.chars().filter(|&x| !x.is_control() && accept(x)) {
+      if self.characters.len() >= self.max_length { break; }
+      self.characters.insert(self.cursor, character);
+      self.cursor += 1;
+    }
+  }
+
+  pub fn delete_backward(&mut self) {
+    if self.cursor > 0 {
+      self.cursor -= 1;
+      self.characters.remove(self.cursor);
+    }
+  }
+
+  pub fn delete_forward(&mut self) {
+    if self.cursor < self.characters.len() { self.characters.remove(self.cursor); }
+  }
+
+  pub fn move_left(&mut self) {
+    self.cursor = self.cursor.saturating_sub(1);
+  }
+
+  pub fn move_right(&mut self) {
+    self.cursor = (self.cursor + 1).min(self.characters.len());
+  }
+
+  pub fn move_home(&mut self) {
+    self.cursor = 0;
+  }
+
+  pub fn move_end(&mut self) {
+    self.cursor = self.characters.len();
+  }
+
+  // the cursor is drawn as underscore below the character after it
+  pub fn draw(&self, renderer: &mut dyn render::Renderer, font: &Font, dst_point: Point) {
+    font.draw_monospace(renderer, dst_point, self.text(), Alignment::TopLeft);
+
+    let text_before_cursor: String = self.characters[.. self.cursor].iter().collect();
+    let cursor_point = Point::new(dst_point.x() + font.width(&text_before_cursor, true),
+        dst_point.y());
+    font.draw_monospace(renderer, cursor_point, "_", Alignment::TopLeft);
+  }
+
+  pub fn text(&self) -> String {
+    return self.characters.iter().collect();
+  }
+
+  pub fn cursor(&self) -> usize {
+    return self.cursor;
+  }
+}
+
+impl OptionsMenu {
+  pub fn new(canvas_size: Point, shown_game_mode: game::GameMode) -> OptionsMenu {
+    let position = Point::new(50.0, 50.0);
+    let size = Point::new(canvas_size.x() - 2.0 * position.x(), canvas_size.y() - 2.0 * position.y());
+
+    return OptionsMenu{
+      background_color: render::Color::new(0, 0, 0, 127),
+      selection_color: render::Color::new(255, 255, 255, 63),
+
+      game_mode: game::GameMode::Menu,
+      shown_game_mode: shown_game_mode,
+      selected_index: 0,
+      waiting_for_key: false,
+
+      size: size,
+      position: position,
+      inner_margin: Point::new(20.0, 20.0),
+      number_of_rows: 10.0,
+    };
+  }
+
+  pub fn show(&mut self, selected_index: usize) {
+    self.game_mode = self.shown_game_mode;
+    self.selected_index = selected_index;
+    self.waiting_for_key = false;
+  }
+
+  pub fn hide(&mut self) {
+    self.game_mode = game::GameMode::Menu;
+    self.waiting_for_key = false;
+  }
+
+  pub fn select_previous(&mut self, number_of_entries: usize) {
+    self.selected_index = (self.selected_index + number_of_entries - 1) % number_of_entries;
+  }
+
+  pub fn select_next(&mut self, number_of_entries: usize) {
+    self.selected_index = (self.selected_index + 1) % number_of_entries;
+  }
+
+  pub fn start_waiting_for_key(&mut self) {
+    self.waiting_for_key = true;
+  }
+
+  pub fn stop_waiting_for_key(&mut self) {
+    self.waiting_for_key = false;
+  }
+
+  // entries are pairs of label and current value
+  pub fn draw(&self, renderer: &mut dyn render::Renderer, font: &Font,
+        entries: &Vec<(String, String)>) {
+    if self.game_mode != self.shown_game_mode { return; }
+
+    renderer.fill_rect(asset::Rect::new(self.position.x() as i32, self.position.y() as i32,
+        self.size.x() as u32, self.size.y() as u32), self.background_color);
+
+    let inner_height = self.size.y() - 2.0 * self.inner_margin.y();
+    let offset_y = (inner_height - font.height()) / (self.number_of_rows - 1.0);
+
+    // scroll such that the selected entry is always visible
+    let number_of_rows = self.number_of_rows as usize;
+    let first_index = (self.selected_index + 1).saturating_sub(number_of_rows);
+
+    for (i, (label, value)) in entries.iter().enumerate().skip(first_index).take(number_of_rows) {
+      let dst_point = Point::new(self.position.x() + self.inner_margin.x(),
+          self.position.y() + self.inner_margin.y() + offset_y * ((i - first_index) as f64));
+
+      if i == self.selected_index {
+        renderer.fill_rect(asset::Rect::new(self.position.x() as i32, dst_point.y() as i32,
+            self.size.x() as u32, font.height() as u32), self.selection_color);
+      }
+
+      font.draw(renderer, dst_point, label, Alignment::TopLeft);
+
+      let value = if (i == self.selected_index) && self.waiting_for_key { "..." }
+          else { value.as_str() };
+      let dst_point = Point::new(self.position.x() + self.size.x() - self.inner_margin.x(),
+          dst_point.y());
+      font.draw(renderer, dst_point, value, Alignment::TopRight);
+    }
+  }
+
+  pub fn selected_index(&self) -> usize {
+    return self.selected_index;
+  }
+
+  pub fn waiting_for_key(&self) -> bool {
+    return self.waiting_for_key;
+  }
+}
+
+impl<'a> Font<'a> {
+  pub fn new(asset_library: &'a asset::AssetLibrary<'a>) -> Font<'a> {
+    let image = asset_library.get_image("font");
+    let image_width = image.width() as usize;
+    let image_height = image.height() as usize;
+    let image_surface_width = image_width * (image.total_number_of_frames() as usize);
+    let mask = image.mask();
+    // the punctuation glyphs are missing in the original font and appended by
+    // tools/extractAssets.py, the space has no glyph
+    let mut characters =
+        "-./0123456789:@ABCDEFGHIJKLMNOPQRSTUVWXYZ_\u{00c4}\u{00d6}\u{00dc}".to_string();
+    if image.total_number_of_frames() > characters.chars().count() as i32 {
+      characters += "!\"%&'()+,;=?";
+    }
+    characters.push(' ');
+    let mut character_rects: Vec<asset::Rect> = Vec::new();
+
+    for (frame, character) in characters.chars().enumerate() {
+      if character == ' ' {
+        character_rects.push(asset::Rect::new(
+            0, 0, (image_width / 2) as u32, image_height as u32));
+        continue;
+      }
+
+      let mut min_point: Point = Point::new(image_width as f64, image_height as f64);
+      let mut max_point: Point = Point::new(-1.0, -1.0);
+
+      for y in 0 .. image_height {
+        for x in 0 .. image_width {
+          let i = (frame as usize) * image_width + x + y * image_surface_width;
+
+          if mask[i] {
+            min_point = min_point.min(Point::new(x as f64, y as f64));
+            max_point = max_point.max(Point::new(x as f64, y as f64));
+          }
+        }
+      }
+
+      character_rects.push(asset::Rect::new(min_point.x() as i32, 0,
+          (max_point.x() - min_point.x() + 1.0) as u32, image_height as u32));
+    }
+
+    let max_character_width: i32 = character_rects.iter().max_by(|&x, &y| x.width().cmp(&y.width()))
+        .expect("No elements in character_widths").width() as i32;
+
+    return Font{
+      image: image,
+      characters: characters,
+      character_rects: character_rects,
+      max_character_width: max_character_width,
+      height: image.height(),
+    };
+  }
+
+  pub fn height(&self) -> f64 {
+    return self.height;
+  }
+
+  pub fn draw<S: Into<String>>(
+        &self, renderer: &mut dyn render::Renderer,
+        dst_point: Point, text: S, alignment: Alignment) {
+    self.draw_internal(renderer, dst_point, text, alignment, false);
+  }
+
+  pub fn draw_monospace<S: Into<String>>(
+        &self, renderer: &mut dyn render::Renderer,
+        dst_point: Point, text: S, alignment: Alignment) {
+    self.draw_internal(renderer, dst_point, text, alignment, true);
+  }
+
+  // width of the drawn text in pixels
+  pub fn width(&self, text: &str, monospace: bool) -> f64 {
+    let text = self.transliterate(text);
+    return self.text_width(&self.frames(&text), monospace) as f64;
+  }
+
+  fn frames(&self, text: &str) -> Vec<i32> {
+    return text.chars().map(
+        |x| self.characters.chars().position(|y| y == x).unwrap_or(0) as i32).collect();
+  }
+
+  fn text_width(&self, frames: &[i32], monospace: bool) -> i32 {
+    return if monospace { (frames.len() as i32) * self.max_character_width }
+        else { frames.iter().map(|x| self.character_rects[*x as usize].width() as i32).sum() };
+  }
+
+  fn draw_internal<S: Into<String>>(
+        &self, renderer: &mut dyn render::Renderer,
+        dst_point: Point, text: S, alignment: Alignment, monospace: bool) {
+    let text = self.transliterate(&text.into());
+    let frames = self.frames(&text);
+
+    let text_character_rects: Vec<asset::Rect> =
+        frames.iter().map(|x| self.character_rects[*x as usize]).collect();
+    let text_width = self.text_width(&frames, monospace);
+
+    let mut dst_point = Point::new(
+      dst_point.x() - (((alignment as i32) % 3) * (text_width / 2)) as f64,
+      dst_point.y() - (((alignment as i32) / 3) * ((self.height as i32) / 2)) as f64,
+    );
+
+    for ((character, character_rect), frame) in
+          text.chars().zip(text_character_rects.iter()).zip(frames.iter()) {
+      let monospace_offset_x = ((self.max_character_width as f64)
+          - (character_rect.width() as f64)) / 2.0;
+      if monospace { dst_point = Point::new(dst_point.x() + monospace_offset_x, dst_point.y()); }
+
+      if character != ' ' {
+        self.image.draw_blit(renderer, *character_rect, dst_point, *frame as f64);
+      }
+
+      dst_point = Point::new(dst_point.x() + (character_rect.width() as f64), dst_point.y());
+      if monospace { dst_point = Point::new(dst_point.x() + monospace_offset_x, dst_point.y()); }
+    }
+  }
+
+  pub fn has_glyph(&self, character: char) -> bool {
+    return self.characters.contains(character)
+        || character.to_uppercase().all(|x| self.characters.contains(x));
+  }
+
+  // maps characters without glyph to similar characters with glyphs, e.g., "ß" to "SS"
+  fn transliterate(&self, text: &str) -> String {
+    let mut result = String::new();
+
+    for character in text.chars() {
+      if self.characters.contains(character) {
+        result.push(character);
+        continue;
+      }
+
+      let uppercase: String = character.to_uppercase().collect();
+
+      if uppercase.chars().all(|x| self.characters.contains(x)) {
+        result += &uppercase;
+      } else if let Some(&(_, replacement)) = FONT_REPLACEMENTS.iter().find(
+            |x| x.0.contains(character)) {
+        // replacements may be punctuation, which older fonts lack as well
+        result += &self.transliterate(replacement);
+      } else if let Some(&(_, replacement)) = PUNCTUATION_REPLACEMENTS.iter().find(
+            |x| x.0.contains(character)) {
+        result += replacement;
+      } else {
+        result.push('-');
+      }
+    }
+
+    return result;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::clock::GameClock;
+
+  fn create_asset_library<'a>() -> asset::AssetLibrary<'a> {
+    return asset::AssetLibrary::from_assets(asset::Image::placeholders(), Vec::new());
+  }
+
+  fn start_score<'a>(asset_library: &'a asset::AssetLibrary<'a>,
+        clock: &clock::ManualClock) -> Score<'a> {
+    let mut score = Score::new(asset_library, Point::new(640.0, 480.0), clock);
+    score.start_game(clock.now(), clock);
+    return score;
+  }
+
+  #[test]
+  fn score_accumulates_gift_and_damage_points() {
+    let asset_library = create_asset_library();
+    let clock = clock::ManualClock::new(std::time::Duration::from_secs_f64(1.0 / 120.0));
+    let mut score = start_score(&asset_library, &clock);
+    let position = Point::zero();
+
+    score.handle_event(&event::GameEvent::GiftDelivered{chimney: (0, 2), points: 15.0,
+        bonus: false, position: position});
+    score.handle_event(&event::GameEvent::GiftDelivered{chimney: (0, 3), points: 20.0,
+        bonus: true, position: position});
+    score.handle_event(&event::GameEvent::PowerUpCollected{kind: npc::BalloonType::Heart,
+        position: position});
+    assert_eq!(score.gift_points(), 75.0);
+
+    score.handle_event(&event::GameEvent::GiftHitGround{damage_points: 15.0,
+        position: position});
+    score.handle_event(&event::GameEvent::SleighHitTile{damage_points: 50.0,
+        position: position});
+    score.handle_event(&event::GameEvent::SleighHitNpc{kind: npc::NpcKind::Goblin,
+        damage_points: 20.0, position: position});
+    assert_eq!(score.damage_points(), 85.0);
+
+    score.handle_event(&event::GameEvent::PowerUpCollected{kind: npc::BalloonType::Cash,
+        position: position});
+    assert_eq!(score.damage_points(), 35.0);
+    score.handle_event(&event::GameEvent::PowerUpCollected{kind: npc::BalloonType::Cash,
+        position: position});
+    assert_eq!(score.damage_points(), 0.0);
+    assert_eq!(score.gift_points(), 75.0);
+  }
+
+  #[test]
+  fn too_much_damage_loses_game() {
+    let asset_library = create_asset_library();
+    let mut clock = clock::ManualClock::new(std::time::Duration::from_secs_f64(1.0 / 120.0));
+    let mut score = start_score(&asset_library, &clock);
+
+    for _ in 0 .. 10 {
+      score.handle_event(&event::GameEvent::SleighHitTile{damage_points: 50.0,
+          position: Point::zero()});
+    }
+
+    clock.tick();
+    score.do_logic(&clock);
+    assert!(!score.lost_due_to_damage());
+
+    score.handle_event(&event::GameEvent::GiftHitGround{damage_points: 15.0,
+        position: Point::zero()});
+    assert!(!score.lost_due_to_damage());
+    clock.tick();
+    score.do_logic(&clock);
+    assert!(score.lost_due_to_damage());
+    assert!(!score.won());
+    assert_eq!(score.score_points(), 0.0);
+  }
+
+  #[test]
+  fn won_game_scores_points_and_remaining_time() {
+    let asset_library = create_asset_library();
+    let mut clock = clock::ManualClock::new(std::time::Duration::from_secs_f64(1.0 / 120.0));
+    let mut score = start_score(&asset_library, &clock);
+
+    score.add_gift_points(100.0);
+    score.add_damage_points(20.0);
+    clock.advance(std::time::Duration::from_secs(50));
+    score.do_logic(&clock);
+    assert_eq!(score.remaining_duration(), std::time::Duration::from_secs(400));
+
+    score.handle_event(&event::GameEvent::Finished);
+    assert!(score.won());
+    assert_eq!(score.score_points(), 100.0 - 2.0 * 20.0 + 10.0 * 400.0);
+
+    // the time stops after winning
+    clock.advance(std::time::Duration::from_secs(10));
+    score.do_logic(&clock);
+    assert_eq!(score.remaining_duration(), std::time::Duration::from_secs(400));
+  }
+
+  #[test]
+  fn countdown_precedes_game_time() {
+    let asset_library = create_asset_library();
+    let mut clock = clock::ManualClock::new(std::time::Duration::from_millis(500));
+    let mut score = Score::new(&asset_library, Point::new(640.0, 480.0), &clock);
+    score.start_game(clock.now() + std::time::Duration::from_secs(3), &clock);
+
+    for _ in 0 .. 5 {
+      clock.tick();
+      score.do_logic(&clock);
+    }
+
+    assert_eq!(score.remaining_duration(), std::time::Duration::from_secs(450));
+
+    for _ in 0 .. 2 {
+      clock.tick();
+      score.do_logic(&clock);
+    }
+
+    let remaining_duration = score.remaining_duration();
+    assert!(remaining_duration < std::time::Duration::from_secs(450));
+
+    for _ in 0 .. 20 {
+      clock.tick();
+      score.do_logic(&clock);
+    }
+
+    assert_eq!(score.remaining_duration(),
+        remaining_duration - std::time::Duration::from_secs(10));
+    clock.advance(score.remaining_duration());
+    score.do_logic(&clock);
+    assert!(!score.lost_due_to_time());
+
+    clock.tick();
+    score.do_logic(&clock);
+    assert!(score.lost_due_to_time());
+    assert_eq!(score.remaining_duration(), std::time::Duration::from_secs(0));
+  }
+
+  #[test]
+  fn font_transliterates_only_characters_without_glyph() {
+    let asset_library = create_asset_library();
+    let font = Font::new(&asset_library);
+
+    assert_eq!(font.transliterate("Hallo, Welt! (100%)?"), "HALLO, WELT! (100%)?");
+    assert_eq!(font.transliterate("\u{201e}Stra\u{00df}e\u{201c} [1]"), "\"STRASSE\" (1)");
+    assert_eq!(font.transliterate("Ren\u{00e9}e\u{2026}"), "RENEE...");
+    assert!(font.has_glyph(';') && font.has_glyph('\'') && font.has_glyph('\u{00e4}'));
+    assert!(!font.has_glyph('\u{00e9}') && !font.has_glyph('['));
+  }
+
+  #[test]
+  fn text_field_counts_characters_not_bytes() {
+    let mut text_field = TextField::new(4);
+
+    text_field.insert("\u{00e4}B\u{00dc}", |_| true);
+    assert_eq!((text_field.text().as_str(), text_field.cursor()), ("\u{00e4}B\u{00dc}", 3));
+
+    // "Ü" takes two bytes, but only one of the four characters
+    text_field.insert("\u{00dc}\u{00dc}", |_| true);
+    assert_eq!(text_field.text(), "\u{00e4}B\u{00dc}\u{00dc}");
+    assert_eq!(text_field.cursor(), 4);
+  }
+
+  #[test]
+  fn text_field_moves_cursor_around_multi_byte_characters() {
+    let mut text_field = TextField::new(16);
+    text_field.set_text("\u{00c4}\u{00dc}");
+
+    text_field.move_left();
+    text_field.insert("X", |_| true);
+    assert_eq!((text_field.text().as_str(), text_field.cursor()), ("\u{00c4}X\u{00dc}", 2));
+
+    text_field.move_home();
+    text_field.move_left();
+    assert_eq!(text_field.cursor(), 0);
+    text_field.move_right();
+    text_field.delete_forward();
+    assert_eq!((text_field.text().as_str(), text_field.cursor()), ("\u{00c4}\u{00dc}", 1));
+
+    text_field.move_end();
+    text_field.move_right();
+    assert_eq!(text_field.cursor(), 2);
+    text_field.delete_backward();
+    assert_eq!((text_field.text().as_str(), text_field.cursor()), ("\u{00c4}", 1));
+  }
+
+  #[test]
+  fn text_field_deletes_nothing_at_boundaries() {
+    let mut text_field = TextField::new(16);
+    text_field.delete_backward();
+    text_field.delete_forward();
+    assert_eq!((text_field.text().as_str(), text_field.cursor()), ("", 0));
+
+    text_field.set_text("\u{00d6}");
+    text_field.delete_forward();
+    text_field.move_home();
+    text_field.delete_backward();
+    assert_eq!((text_field.text().as_str(), text_field.cursor()), ("\u{00d6}", 0));
+  }
+
+  #[test]
+  fn text_field_rejects_characters_without_glyph() {
+    let asset_library = create_asset_library();
+    let font = Font::new(&asset_library);
+    let mut text_field = TextField::new(16);
+
+    text_field.insert("Ren\u{00e9}e [\u{00e4}]\n", |x| font.has_glyph(x));
+    assert_eq!(text_field.text(), "Rene \u{00e4}");
+  }
+
+  #[test]
+  fn new_highscore_prefills_last_name() {
+    let mut highscore_table = HighscoreTable::new(Point::new(640.0, 480.0),
+        vec!["level1-easy".to_string()]);
+
+    highscore_table.new_highscore("level1-easy", 2, "Rudolph der Sch\u{00f6}ne");
+    assert_eq!(highscore_table.name_field().text(), "Rudolph der Sch\u{00f6}");
+    assert_eq!(highscore_table.name_field().cursor(), 16);
+    assert_eq!(highscore_table.new_highscore_index(), 2);
+
+    highscore_table.name_field_mut().delete_backward();
+    highscore_table.new_highscore("level1-easy", 0, "Comet");
+    assert_eq!(highscore_table.name_field().text(), "Comet");
+  }
+
+  #[test]
+  fn font_without_punctuation_glyphs_falls_back_to_replacements() {
+    let mut images = asset::Image::placeholders();
+    images.retain(|x| x.name() != "font");
+    images.push(asset::Image::new("font", 16 * 45, 16, (45, 1), vec![true; 16 * 45 * 16]));
+    let asset_library = asset::AssetLibrary::from_assets(images, Vec::new());
+    let font = Font::new(&asset_library);
+
+    assert!(!font.has_glyph('!') && !font.has_glyph('\'') && font.has_glyph('.'));
+    assert_eq!(font.transliterate("Hallo, Welt! (100%)?"), "HALLO. WELT. 100-.");
+    assert_eq!(font.transliterate("\u{201e}Stra\u{00df}e\u{201c} [1]"), "STRASSE 1");
+  }
+
+  #[test]
+  fn score_draws_icons_and_values() {
+    let asset_library = create_asset_library();
+    let font = Font::new(&asset_library);
+    let clock = clock::ManualClock::new(std::time::Duration::from_secs_f64(1.0 / 120.0));
+    let mut score = start_score(&asset_library, &clock);
+    let mut renderer = render::RecordingRenderer::new();
+
+    score.add_gift_points(55.0);
+    score.add_damage_points(65.0);
+    score.draw(&mut renderer, &font);
+
+    let image_names: Vec<&str> = renderer.commands().iter().filter_map(|command| match command {
+      render::DrawCommand::Image{image_name, ..} => Some(image_name.as_str()),
+      _ => None,
+    }).collect();
+
+    for icon_name in ["giftScoreIcon", "damageScoreIcon", "timeScoreIcon"].iter() {
+      assert_eq!(image_names.iter().filter(|x| *x == icon_name).count(), 1);
+    }
+
+    // "55", "-65" and "7:30"
+    assert_eq!(image_names.iter().filter(|x| **x == "font").count(), 9);
+  }
+}
@@ -0,0 +1,196 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::io::BufRead;
+use std::io::Read;
+use std::io::Write;
+
+use santa_racer_core::*;
+use santa_racer_core::leaderboard::LeaderboardBackend;
+
+const TABLE_KEY: &str = "level1-easy";
+const TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+struct MockServer {
+  url: String,
+  requests: std::sync::mpsc::Receiver<MockRequest>,
+}
+
+#[derive(Debug)]
+struct MockRequest {
+  method: String,
+  path: String,
+  body: String,
+}
+
+impl MockServer {
+  // answers the n-th request with the n-th status of statuses (or with the last one),
+  // GET requests with the given body
+  fn start(statuses: Vec<u16>, get_body: &str) -> MockServer {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("Could not bind listener");
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (request_sender, request_receiver) = std::sync::mpsc::channel();
+    let get_body = get_body.to_string();
+
+    std::thread::spawn(move || {
+      for (i, stream) in listener.incoming().enumerate() {
+        let status = *statuses.get(i).or(statuses.last()).unwrap();
+        let request = match stream {
+          Ok(stream) => MockServer::respond(stream, status, &get_body),
+          Err(_) => return,
+        };
+        if request_sender.send(request).is_err() { return; }
+      }
+    });
+
+    return MockServer{url: url, requests: request_receiver};
+  }
+
+  fn respond(stream: std::net::TcpStream, status: u16, get_body: &str) -> MockRequest {
+    let mut reader = std::io::BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+    let mut content_length = 0;
+
+    loop {
+      line.clear();
+      reader.read_line(&mut line).unwrap();
+      if line.trim().is_empty() { break; }
+      let mut parts = line.splitn(2, ':');
+      if parts.next().unwrap().eq_ignore_ascii_case("content-length") {
+        content_length = parts.next().unwrap().trim().parse().unwrap();
+      }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).unwrap();
+    let response_body = if method == "GET" { get_body } else { "" };
+    let _ = write!(reader.get_mut(), "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\n\
+        Content-Length: {}\r\nConnection: close\r\n\r\n{}", status, response_body.len(),
+        response_body);
+
+    return MockRequest{method: method, path: path, body: String::from_utf8(body).unwrap()};
+  }
+
+  fn next_request(&self) -> MockRequest {
+    return self.requests.recv_timeout(TIMEOUT).expect("Server did not receive request");
+  }
+}
+
+fn create_queue_file_path(name: &str) -> std::path::PathBuf {
+  let file_path = std::env::temp_dir().join(format!("santa-racer-leaderboard-{}-{}.json", name,
+      std::process::id()));
+  let _ = std::fs::remove_file(&file_path);
+  return file_path;
+}
+
+fn wait_until<F: FnMut() -> bool>(mut condition: F) {
+  let start_instant = std::time::Instant::now();
+
+  while !condition() {
+    assert!(start_instant.elapsed() < TIMEOUT, "Condition not met in time");
+    std::thread::sleep(std::time::Duration::from_millis(10));
+  }
+}
+
+fn assert_submitted(request: &MockRequest, name: &str, points: i32) {
+  assert_eq!((request.method.as_str(), request.path.as_str()), ("POST", "/scores/level1-easy"));
+  let highscore: highscore::Highscore = serde_json::from_str(&request.body).unwrap();
+  assert_eq!((highscore.name().as_str(), highscore.points()), (name, points));
+  assert!(!highscore.is_signed());
+}
+
+#[test]
+fn highscores_are_submitted_and_fetched() {
+  let highscores = vec![highscore::Highscore::new("Rudolph", 300),
+      highscore::Highscore::new("Comet", 200)];
+  let server = MockServer::start(vec![200], &serde_json::to_string(&highscores).unwrap());
+  let queue_file_path = create_queue_file_path("submit");
+  let mut leaderboard = leaderboard::HttpLeaderboard::new(&server.url, queue_file_path.clone(),
+      false);
+
+  assert_eq!(leaderboard.submit(TABLE_KEY,
+      &highscore::Highscore::new("Dasher", 100)), Ok(None));
+  assert_submitted(&server.next_request(), "Dasher", 100);
+
+  assert_eq!(leaderboard.fetch(TABLE_KEY),
+      Err(locale::Message::LeaderboardLoading.text().to_string()));
+  let request = server.next_request();
+  assert_eq!((request.method.as_str(), request.path.as_str()), ("GET", "/scores/level1-easy"));
+
+  wait_until(|| leaderboard.fetch(TABLE_KEY).is_ok());
+  assert_eq!(leaderboard.fetch(TABLE_KEY), Ok(highscores));
+  assert!(!queue_file_path.exists());
+}
+
+#[test]
+fn failed_submission_is_queued_and_resent_after_restart() {
+  let server = MockServer::start(vec![503], "");
+  let queue_file_path = create_queue_file_path("retry");
+  let mut leaderboard = leaderboard::HttpLeaderboard::new(&server.url, queue_file_path.clone(),
+      false);
+
+  leaderboard.submit(TABLE_KEY, &highscore::Highscore::new("Dasher", 100))
+      .unwrap();
+  assert_submitted(&server.next_request(), "Dasher", 100);
+
+  // later submissions wait behind the failed one until the retry delay has passed
+  leaderboard.submit(TABLE_KEY, &highscore::Highscore::new("Comet", 50))
+      .unwrap();
+  assert!(server.requests.recv_timeout(std::time::Duration::from_millis(500)).is_err());
+  assert!(queue_file_path.exists());
+  drop(leaderboard);
+
+  let server = MockServer::start(vec![200], "");
+  let _leaderboard = leaderboard::HttpLeaderboard::new(&server.url, queue_file_path.clone(),
+      false);
+  assert_submitted(&server.next_request(), "Dasher", 100);
+  assert_submitted(&server.next_request(), "Comet", 50);
+  wait_until(|| !queue_file_path.exists());
+}
+
+#[test]
+fn rejected_submission_is_dropped() {
+  let server = MockServer::start(vec![400, 200], "");
+  let queue_file_path = create_queue_file_path("rejected");
+  let mut leaderboard = leaderboard::HttpLeaderboard::new(&server.url, queue_file_path.clone(),
+      false);
+
+  leaderboard.submit(TABLE_KEY, &highscore::Highscore::new("Dasher", 100))
+      .unwrap();
+  assert_submitted(&server.next_request(), "Dasher", 100);
+
+  // unlike after a server error, the next submission is sent without delay
+  leaderboard.submit(TABLE_KEY, &highscore::Highscore::new("Comet", 50))
+      .unwrap();
+  assert_submitted(&server.next_request(), "Comet", 50);
+  wait_until(|| !queue_file_path.exists());
+  assert!(server.requests.recv_timeout(std::time::Duration::from_millis(100)).is_err());
+}
+
+#[test]
+fn fetch_error_affects_only_its_table() {
+  let highscores = vec![highscore::Highscore::new("Rudolph", 300)];
+  let server = MockServer::start(vec![503, 200], &serde_json::to_string(&highscores).unwrap());
+  let mut leaderboard = leaderboard::HttpLeaderboard::new(&server.url,
+      create_queue_file_path("fetch"), false);
+  let unavailable = Err(locale::Message::LeaderboardUnavailable.text().to_string());
+
+  leaderboard.fetch("level1-hard").unwrap_err();
+  server.next_request();
+  wait_until(|| leaderboard.fetch("level1-hard") == unavailable);
+
+  assert_eq!(leaderboard.fetch(TABLE_KEY),
+      Err(locale::Message::LeaderboardLoading.text().to_string()));
+  server.next_request();
+  wait_until(|| leaderboard.fetch(TABLE_KEY).is_ok());
+  assert_eq!(leaderboard.fetch(TABLE_KEY), Ok(highscores));
+  assert_eq!(leaderboard.fetch("level1-hard"), unavailable);
+}
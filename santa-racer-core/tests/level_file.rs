@@ -0,0 +1,51 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use santa_racer_core::*;
+
+// the data files of the original game, from which assets/levels/level1.json was converted
+fn legacy_file_path(name: &str) -> std::path::PathBuf {
+  return std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/legacy")
+      .join(name);
+}
+
+fn convert_legacy_files(npc_map_file_path: &std::path::Path)
+      -> Result<level_file::LevelFile, String> {
+  return level_file::LevelFile::convert_legacy_files(level::DEFAULT_LEVEL_NAME,
+      &legacy_file_path("levelTileMap.txt"), npc_map_file_path,
+      &legacy_file_path("chimneys.txt"));
+}
+
+#[test]
+fn legacy_files_convert_to_first_level() {
+  let level_file = convert_legacy_files(&legacy_file_path("levelNpcMap.txt"))
+      .unwrap_or_else(|error| panic!("{}", error));
+  let expected_level_file = level_file::LevelFile::load(
+      &std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../assets/levels")
+      .join(format!("{}.json", level::DEFAULT_LEVEL_NAME)))
+      .unwrap_or_else(|error| panic!("{}", error));
+
+  assert_eq!(level_file.number_of_tiles(), (270, 5));
+  assert_eq!(serde_json::to_value(&level_file).unwrap(),
+      serde_json::to_value(&expected_level_file).unwrap());
+}
+
+#[test]
+fn legacy_npc_map_with_other_number_of_rows_is_rejected() {
+  let contents = std::fs::read_to_string(legacy_file_path("levelNpcMap.txt")).unwrap();
+  let npc_map_file_path = std::env::temp_dir().join(format!("santa-racer-npc-map-{}.txt",
+      std::process::id()));
+
+  // drop the last row
+  let rows: Vec<&str> = contents.trim_end().rsplitn(2, "\n\n").collect();
+  std::fs::write(&npc_map_file_path, rows[1]).unwrap();
+  let result = convert_legacy_files(&npc_map_file_path);
+  std::fs::remove_file(&npc_map_file_path).unwrap();
+
+  let error = result.err().expect("Conversion did not fail");
+  assert!(error.contains("differ from 270x5"), "Unexpected error: {}", error);
+}
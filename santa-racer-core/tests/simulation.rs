@@ -0,0 +1,126 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use santa_racer_core::*;
+
+const SEED: u64 = 2020;
+const NUMBER_OF_TICKS: u64 = 120 * 30;
+
+// the real level with placeholder images, as the extracted images are not part of the repository
+fn create_asset_library<'a>() -> asset::AssetLibrary<'a> {
+  let level_file_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+      .join("../assets/levels").join(format!("{}.json", level::DEFAULT_LEVEL_NAME));
+  let level_file = level_file::LevelFile::load(&level_file_path)
+      .unwrap_or_else(|error| panic!("{}", error));
+  return asset::AssetLibrary::from_assets(asset::Image::placeholders(), vec![level_file]);
+}
+
+// zigzags through the level and drops a gift every second
+fn create_input(tick: u64) -> input::TickInput {
+  let phase = (tick / 240) % 4;
+  return input::TickInput::new(phase == 3, phase == 1, phase == 0, phase == 2, 0.0, 0.0,
+      tick % 120 == 0);
+}
+
+fn record_replay(asset_library: &asset::AssetLibrary) -> (replay::Replay,
+      simulation::SimulationResult) {
+  let mut replay = replay::Replay::new(SEED, game::GameDifficulty::Hard,
+      level::DEFAULT_LEVEL_NAME);
+  let mut simulation = simulation::Simulation::new(asset_library, SEED);
+  simulation.start_game(level::DEFAULT_LEVEL_NAME, game::GameDifficulty::Hard, SEED);
+
+  for tick in 0 .. NUMBER_OF_TICKS {
+    let input = create_input(tick);
+    replay.record(input);
+    simulation.tick();
+    simulation.check_input(&input);
+    simulation.do_logic();
+    if simulation.outcome().is_some() { break; }
+  }
+
+  return (replay, simulation.result());
+}
+
+fn assert_same_result(result: &simulation::SimulationResult,
+      expected_result: &simulation::SimulationResult) {
+  assert_eq!(result.outcome(), expected_result.outcome());
+  assert_eq!(result.score_points(), expected_result.score_points());
+  assert_eq!(result.gift_points(), expected_result.gift_points());
+  assert_eq!(result.damage_points(), expected_result.damage_points());
+  assert_eq!(result.number_of_ticks(), expected_result.number_of_ticks());
+}
+
+#[test]
+fn replay_reproduces_recorded_run() {
+  let asset_library = create_asset_library();
+  let (replay, recorded_result) = record_replay(&asset_library);
+
+  assert_eq!(replay.number_of_ticks(), recorded_result.number_of_ticks());
+  assert_eq!(recorded_result.outcome(), Some(simulation::SimulationOutcome::LostDueToDamage));
+  assert!(recorded_result.gift_points() > 0.0);
+
+  let result = simulation::Simulation::run_replay(&asset_library, &replay);
+  assert_same_result(&result, &recorded_result);
+  let result = simulation::Simulation::run_replay(&asset_library, &replay);
+  assert_same_result(&result, &recorded_result);
+}
+
+#[test]
+fn replay_survives_json_round_trip() {
+  let asset_library = create_asset_library();
+  let (replay, recorded_result) = record_replay(&asset_library);
+
+  let file_path = std::env::temp_dir().join(format!("santa-racer-replay-{}.json",
+      std::process::id()));
+  replay.save(&file_path).unwrap();
+  let loaded_replay = replay::Replay::load(&file_path);
+  std::fs::remove_file(&file_path).unwrap();
+  let loaded_replay = loaded_replay.unwrap();
+
+  assert_eq!(loaded_replay.seed(), replay.seed());
+  assert_eq!(loaded_replay.difficulty(), replay.difficulty());
+  assert_eq!(loaded_replay.level(), replay.level());
+  assert_eq!(loaded_replay.number_of_ticks(), replay.number_of_ticks());
+
+  let result = simulation::Simulation::run_replay(&asset_library, &loaded_replay);
+  assert_same_result(&result, &recorded_result);
+}
+
+#[test]
+fn shipped_levels_form_campaign() {
+  let levels_dir_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+      .join("../assets/levels");
+  let level_files = ["level2", "level1"].iter()
+      .map(|name| level_file::LevelFile::load(&levels_dir_path.join(format!("{}.json", name)))
+        .unwrap_or_else(|error| panic!("{}", error))).collect();
+  let asset_library = asset::AssetLibrary::from_assets(asset::Image::placeholders(), level_files);
+  let level_names = asset_library.level_names();
+  assert_eq!(level_names, vec!["level1", "level2"]);
+  // the second level is a placeholder with the layout of the first one and higher speeds,
+  // so the campaign is run through the same layout twice
+  assert!(asset_library.get_level("level2").min_scroll_speed_x()
+      > asset_library.get_level("level1").min_scroll_speed_x());
+
+  let mut campaign = campaign::Campaign::new(level_names, 0, game::GameDifficulty::Easy);
+
+  loop {
+    let mut simulation = simulation::Simulation::new(&asset_library, SEED);
+    simulation.start_game(campaign.level_name(), campaign.difficulty(), SEED);
+
+    for tick in 0 .. 120 {
+      simulation.tick();
+      simulation.check_input(&create_input(tick));
+      simulation.do_logic();
+    }
+
+    assert_eq!(simulation.level().name(), campaign.level_name());
+    if !campaign.has_next_level() { break; }
+    campaign.advance();
+  }
+
+  assert_eq!(campaign.level_number(), 2);
+}
@@ -0,0 +1,131 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use santa_racer_core::input::Action;
+
+pub struct Controllers {
+  subsystem: Option<sdl2::GameControllerSubsystem>,
+  controllers: Vec<sdl2::controller::GameController>,
+  verbose: bool,
+
+  dead_zone: f64,
+  steering_resolution: f64,
+}
+
+impl Controllers {
+  pub fn new(sdl: &sdl2::Sdl, verbose: bool) -> Controllers {
+    let subsystem = match sdl.game_controller() {
+      Ok(subsystem) => Some(subsystem),
+      Err(error) => {
+        println!("Could not initialize game controller subsystem: {}", error);
+        None
+      },
+    };
+
+    // already connected controllers are reported with ControllerDeviceAdded events
+    return Controllers{
+      subsystem: subsystem,
+      controllers: Vec::new(),
+      verbose: verbose,
+
+      dead_zone: 0.2,
+      steering_resolution: 32.0,
+    };
+  }
+
+  pub fn handle_event(&mut self, event: &sdl2::event::Event) -> Vec<Action> {
+    match *event {
+      sdl2::event::Event::ControllerDeviceAdded{which, ..} => self.open(which),
+      sdl2::event::Event::ControllerDeviceRemoved{which, ..} => {
+        if self.verbose { println!("Game controller {} disconnected", which); }
+        self.controllers.retain(|controller| controller.instance_id() != which);
+      },
+      sdl2::event::Event::ControllerButtonDown{button, ..} => {
+        return Controllers::actions(button);
+      },
+      _ => {},
+    }
+
+    return Vec::new();
+  }
+
+  fn open(&mut self, joystick_index: u32) {
+    let subsystem = match &self.subsystem {
+      Some(subsystem) => subsystem,
+      None => return,
+    };
+
+    match subsystem.open(joystick_index) {
+      Ok(controller) => {
+        if self.controllers.iter().any(|x| x.instance_id() == controller.instance_id()) {
+          return;
+        }
+
+        if self.verbose {
+          println!("Game controller {} connected: {}", controller.instance_id(),
+              controller.name());
+        }
+
+        self.controllers.push(controller);
+      },
+      Err(error) => println!("Could not open game controller {}: {}", joystick_index, error),
+    }
+  }
+
+  fn actions(button: sdl2::controller::Button) -> Vec<Action> {
+    return match button {
+      sdl2::controller::Button::A => vec![Action::Confirm, Action::DropGift],
+      sdl2::controller::Button::B | sdl2::controller::Button::Back => vec![Action::Menu],
+      sdl2::controller::Button::X => vec![Action::Options],
+      sdl2::controller::Button::Y => vec![Action::Highscores],
+      sdl2::controller::Button::Start => vec![Action::Pause, Action::Confirm],
+      sdl2::controller::Button::LeftShoulder => vec![Action::Levels],
+      sdl2::controller::Button::DPadLeft => vec![Action::SteerLeft],
+      sdl2::controller::Button::DPadRight => vec![Action::SteerRight],
+      sdl2::controller::Button::DPadUp => vec![Action::SteerUp],
+      sdl2::controller::Button::DPadDown => vec![Action::SteerDown],
+      _ => Vec::new(),
+    };
+  }
+
+  pub fn steering(&self) -> (f64, f64) {
+    let mut steering: (f64, f64) = (0.0, 0.0);
+
+    for controller in &self.controllers {
+      let x = self.axis_value(controller, sdl2::controller::Axis::LeftX)
+          - (controller.button(sdl2::controller::Button::DPadLeft) as i32 as f64)
+          + (controller.button(sdl2::controller::Button::DPadRight) as i32 as f64);
+      let y = self.axis_value(controller, sdl2::controller::Axis::LeftY)
+          - (controller.button(sdl2::controller::Button::DPadUp) as i32 as f64)
+          + (controller.button(sdl2::controller::Button::DPadDown) as i32 as f64);
+
+      if x.abs() > steering.0.abs() { steering.0 = x.max(-1.0).min(1.0); }
+      if y.abs() > steering.1.abs() { steering.1 = y.max(-1.0).min(1.0); }
+    }
+
+    return steering;
+  }
+
+  // values are quantized, as every change of the input is stored in replays
+  fn axis_value(&self, controller: &sdl2::controller::GameController,
+        axis: sdl2::controller::Axis) -> f64 {
+    let value = (controller.axis(axis) as f64 / 32767.0).max(-1.0).min(1.0);
+    if value.abs() < self.dead_zone { return 0.0; }
+
+    let value = value.signum() * (value.abs() - self.dead_zone) / (1.0 - self.dead_zone);
+    return (value * self.steering_resolution).round() / self.steering_resolution;
+  }
+
+  pub fn rumble(&mut self, strength: f64, duration: std::time::Duration) {
+    let strength = (strength.max(0.0).min(1.0) * 65535.0) as u16;
+
+    for controller in &mut self.controllers {
+      // not all controllers support rumble
+      let _ = controller.set_rumble(strength, strength, duration.as_millis() as u32);
+    }
+  }
+}
@@ -5,8 +5,43 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+use rand::Rng;
+
 use crate::*;
-use crate::asset::Point;
+use santa_racer_core::asset::Point;
+use santa_racer_core::clock::GameClock;
+use santa_racer_core::render::Renderer;
+use santa_racer_core::game::GameDifficulty;
+use santa_racer_core::game::GameMode;
+use santa_racer_core::input::Action;
+use santa_racer_core::leaderboard;
+use santa_racer_core::leaderboard::LeaderboardBackend;
+use santa_racer_core::locale::Language;
+use santa_racer_core::locale::Message;
+
+#[derive(Clone, Copy, PartialEq)]
+enum OptionsEntry {
+  Fullscreen,
+  Muted,
+  MasterVolume,
+  MusicVolume,
+  EffectsVolume,
+  Difficulty,
+  Language,
+  // action and label
+  Keys(Action, Message),
+}
+
+const OPTIONS_ENTRIES: [OptionsEntry; 14] = [OptionsEntry::Fullscreen, OptionsEntry::Muted,
+    OptionsEntry::MasterVolume, OptionsEntry::MusicVolume, OptionsEntry::EffectsVolume,
+    OptionsEntry::Difficulty, OptionsEntry::Language,
+    OptionsEntry::Keys(Action::SteerLeft, Message::KeySteerLeft),
+    OptionsEntry::Keys(Action::SteerRight, Message::KeySteerRight),
+    OptionsEntry::Keys(Action::SteerUp, Message::KeySteerUp),
+    OptionsEntry::Keys(Action::SteerDown, Message::KeySteerDown),
+    OptionsEntry::Keys(Action::DropGift, Message::KeyDropGift),
+    OptionsEntry::Keys(Action::Pause, Message::KeyPause),
+    OptionsEntry::Keys(Action::Mute, Message::KeyMute)];
 
 pub struct Game<'a> {
   options: &'a mut options::Options,
@@ -16,7 +51,25 @@ pub struct Game<'a> {
   buffer_size: Point,
   event_pump: &'a mut sdl2::EventPump,
   text_input_util: &'a sdl2::keyboard::TextInputUtil,
-
+  controllers: &'a mut controller::Controllers,
+
+  simulation: simulation::Simulation<'a>,
+  frame_clock: clock::ScaledClock,
+  tick_accumulator: std::time::Duration,
+  interpolation: f64,
+  drop_gift_requested: bool,
+  key_bindings: key_bindings::KeyBindings,
+  // levels of the current run, none while playing back replays
+  campaign: Option<campaign::Campaign>,
+  // leaderboard server, if configured
+  global_leaderboard: Option<Box<dyn LeaderboardBackend>>,
+  recorded_replay: Option<replay::Replay>,
+  // replay of the last run, attached to a new highscore of the run
+  finished_replay: Option<replay::Replay>,
+  // table keys, entries and replays of a won level and of a completed campaign, which are
+  // submitted one after another after the names of previous entries have been entered
+  pending_highscores: Vec<(String, highscore::Highscore, Option<replay::Replay>)>,
+  replay_player: Option<replay::ReplayPlayer>,
   target_fps: f64,
   quit_flag: bool,
   fps: f64,
@@ -25,66 +78,60 @@ pub struct Game<'a> {
   last_fps_update_instant: std::time::Instant,
 
   asset_library: &'a asset::AssetLibrary<'a>,
-  music: &'a asset::Song<'a>,
-  won_sound: &'a asset::Sound,
-  lost_sound: &'a asset::Sound,
+  texture_library: sdl_render::TextureLibrary<'a>,
+  // none if sound is disabled
+  audio: Option<sdl_audio::SdlAudio>,
+  music: &'a dyn asset::Song,
+  won_sound: &'a dyn asset::Sound,
+  lost_sound: &'a dyn asset::Sound,
 
   mode: GameMode,
-  difficulty: GameDifficulty,
 
   font: ui::Font<'a>,
-  score: ui::Score<'a>,
-  highscore_table: ui::HighscoreTable<'a>,
-  landscape: level::Landscape<'a>,
-  level: level::Level<'a>,
-  sleigh: sleigh::Sleigh<'a>,
+  highscore_table: ui::HighscoreTable,
+  options_menu: ui::OptionsMenu,
+  level_select_menu: ui::OptionsMenu,
 
-  counting_down: bool,
   splash_end_instant: std::time::Instant,
 
   countdown_duration: std::time::Duration,
   splash_duration: std::time::Duration,
+  max_frame_duration: std::time::Duration,
 }
 
-struct DrawArguments<'a> {
+struct DrawArguments<'a, 't> {
   options: &'a options::Options,
   buffer_size: Point,
   asset_library: &'a asset::AssetLibrary<'a>,
+  texture_library: &'a mut sdl_render::TextureLibrary<'t>,
   mode: &'a GameMode,
   font: &'a ui::Font<'a>,
-  highscore_table: &'a ui::HighscoreTable<'a>,
+  highscore_table: &'a ui::HighscoreTable,
+  options_menu: &'a ui::OptionsMenu,
+  options_entries: Vec<(String, String)>,
+  level_select_menu: &'a ui::OptionsMenu,
+  level_select_entries: Vec<(String, String)>,
+  campaign_text: Option<String>,
+  highscores: Result<Vec<highscore::Highscore>, String>,
   score: &'a ui::Score<'a>,
   landscape: &'a level::Landscape<'a>,
   level: &'a level::Level<'a>,
   sleigh: &'a sleigh::Sleigh<'a>,
+  interpolation: f64,
   fps: f64,
 }
 
-#[derive(PartialEq, Debug)]
-pub enum GameMode {
-  Menu,
-  HelpSplash1,
-  HelpSplash2,
-  HighscoreTable,
-  Running,
-  WonSplash,
-  LostDueToDamageSplash,
-  LostDueToTimeSplash,
-  NewHighscore,
-}
-
-#[derive(Clone, Copy)]
-pub enum GameDifficulty {
-  Easy,
-  Hard,
-}
-
 impl<'a> Game<'a> {
   pub fn new(canvas: &'a mut sdl2::render::WindowCanvas,
         texture_creator: &'a sdl2::render::TextureCreator<sdl2::video::WindowContext>,
         event_pump: &'a mut sdl2::EventPump, text_input_util: &'a sdl2::keyboard::TextInputUtil,
-        asset_library: &'a asset::AssetLibrary, options: &'a mut options::Options) -> Game<'a> {
-    let buffer_size = Point::new(640.0, 480.0);
+        controllers: &'a mut controller::Controllers, asset_library: &'a asset::AssetLibrary,
+        audio: Option<sdl_audio::SdlAudio>, options: &'a mut options::Options,
+        replay: Option<replay::Replay>) -> Game<'a> {
+    let seed = options.seed().unwrap_or_else(|| rand::thread_rng().gen());
+    let mut simulation = simulation::Simulation::new(asset_library, seed);
+    simulation.achievements_mut().set_unlocked(options.unlocked_achievements());
+    let buffer_size = simulation.canvas_size();
     let buffer_texture = texture_creator.create_texture_target(
         None, buffer_size.x() as u32, buffer_size.y() as u32).expect(
         "Could not create buffer texture");
@@ -92,9 +139,15 @@ impl<'a> Game<'a> {
     let music = asset_library.get_song("music");
     music.play();
 
+    let time_scale = if options.slow_motion_enabled() { 0.5 } else { 1.0 };
+    let key_bindings = key_bindings::KeyBindings::new(options.controls());
+    let global_leaderboard = options.leaderboard_url().map(|url| Box::new(
+        leaderboard::HttpLeaderboard::new(url, options.leaderboard_queue_file_path(),
+          options.verbose_enabled())) as Box<dyn LeaderboardBackend>);
+    let splash_end_instant = simulation.now();
     let now = std::time::Instant::now();
 
-    return Game{
+    let mut game = Game{
       options: options,
 
       canvas: canvas,
@@ -102,7 +155,20 @@ impl<'a> Game<'a> {
       buffer_size: buffer_size,
       event_pump: event_pump,
       text_input_util: text_input_util,
-
+      controllers: controllers,
+
+      simulation: simulation,
+      frame_clock: clock::ScaledClock::new(time_scale),
+      tick_accumulator: std::time::Duration::from_secs_f64(0.0),
+      interpolation: 0.0,
+      drop_gift_requested: false,
+      key_bindings: key_bindings,
+      campaign: None,
+      global_leaderboard: global_leaderboard,
+      recorded_replay: None,
+      finished_replay: None,
+      pending_highscores: Vec::new(),
+      replay_player: None,
       target_fps: 60.0,
       quit_flag: false,
       fps: 0.0,
@@ -111,33 +177,38 @@ impl<'a> Game<'a> {
       last_fps_update_instant: now,
 
       asset_library: asset_library,
+      texture_library: sdl_render::TextureLibrary::new(texture_creator, asset_library),
+      audio: audio,
       music: music,
       won_sound: asset_library.get_sound("won"),
       lost_sound: asset_library.get_sound("lost"),
 
       mode: GameMode::Menu,
-      difficulty: GameDifficulty::Easy,
 
       font: ui::Font::new(asset_library),
-      score: ui::Score::new(asset_library, buffer_size),
-      highscore_table: ui::HighscoreTable::new(buffer_size, texture_creator),
-      landscape: level::Landscape::new(asset_library),
-      level: level::Level::new(asset_library, buffer_size),
-      sleigh: sleigh::Sleigh::new(asset_library, buffer_size),
+      highscore_table: ui::HighscoreTable::new(buffer_size,
+          highscore::table_keys(&asset_library.level_names())),
+      options_menu: ui::OptionsMenu::new(buffer_size, GameMode::Options),
+      level_select_menu: ui::OptionsMenu::new(buffer_size, GameMode::LevelSelect),
 
-      counting_down: false,
-      splash_end_instant: now,
+      splash_end_instant: splash_end_instant,
 
       countdown_duration: std::time::Duration::from_secs_f64(3.0),
       splash_duration: std::time::Duration::from_secs_f64(5.0),
+      max_frame_duration: std::time::Duration::from_secs_f64(0.25),
     };
+
+    if let Some(replay) = replay {
+      game.start_replay(replay);
+    }
+
+    return game;
   }
 
   pub fn run_loop(&mut self) {
     while !self.quit_flag {
       self.process_events();
-      self.check_keyboard_state();
-      self.do_logic();
+      self.run_ticks();
       self.draw();
       self.finish_frame();
     }
@@ -148,12 +219,17 @@ impl<'a> Game<'a> {
   }
 
   fn process_events(&mut self) {
-    for event in self.event_pump.poll_iter() {
-      let now = std::time::Instant::now();
+    let events: Vec<sdl2::event::Event> = self.event_pump.poll_iter().collect();
 
+    for event in events {
       match event {
         sdl2::event::Event::Quit{..} => self.quit_flag = true,
-        sdl2::event::Event::KeyDown{keycode, keymod, ..} => {
+        sdl2::event::Event::Window{win_event: sdl2::event::WindowEvent::FocusLost, ..} => {
+          if self.mode == GameMode::Running { self.pause(); }
+        },
+        sdl2::event::Event::KeyDown{keycode, scancode, keymod, ..} => {
+          let actions = self.key_bindings.actions(scancode);
+
           if let Some(keycode) = keycode {
             if (keymod.contains(sdl2::keyboard::Mod::LCTRLMOD)
                   || keymod.contains(sdl2::keyboard::Mod::RCTRLMOD))
@@ -163,111 +239,31 @@ impl<'a> Game<'a> {
             } else if (keymod.contains(sdl2::keyboard::Mod::LALTMOD)
                   || keymod.contains(sdl2::keyboard::Mod::RALTMOD))
                   && (keycode == sdl2::keyboard::Keycode::Return) {
-              let fullscreen_state = match self.canvas.window().fullscreen_state() {
-                sdl2::video::FullscreenType::True => sdl2::video::FullscreenType::Off,
-                _ => sdl2::video::FullscreenType::True,
-              };
-              self.canvas.window_mut().set_fullscreen(fullscreen_state).expect(
-                  "Could not change fullscreen state");
-
-            } else if (keycode == sdl2::keyboard::Keycode::F1)
-                  && ((self.mode == GameMode::Menu) || (self.mode == GameMode::HelpSplash1)
-                    || (self.mode == GameMode::HelpSplash2)
-                    || (self.mode == GameMode::HighscoreTable)) {
-              self.mode = GameMode::HelpSplash1;
-              self.highscore_table.hide();
-
-            } else if (keycode == sdl2::keyboard::Keycode::F2)
-                  && ((self.mode == GameMode::Menu) || (self.mode == GameMode::HelpSplash1)
-                    || (self.mode == GameMode::HelpSplash2)
-                    || (self.mode == GameMode::HighscoreTable)) {
-              self.mode = GameMode::HelpSplash2;
-              self.highscore_table.hide();
-
-            } else if (keycode == sdl2::keyboard::Keycode::F3) && (self.mode == GameMode::Menu) {
-              self.mode = GameMode::HighscoreTable;
-              self.highscore_table.show();
-
-            } else if (keycode == sdl2::keyboard::Keycode::F3)
-                  && (self.mode == GameMode::HighscoreTable) {
-              self.mode = GameMode::Menu;
-              self.highscore_table.hide();
-
-            } else if ((keycode == sdl2::keyboard::Keycode::F5)
-                    || (keycode == sdl2::keyboard::Keycode::F6))
-                  && ((self.mode == GameMode::Menu) || (self.mode == GameMode::HighscoreTable)) {
-              self.mode = GameMode::Running;
-              self.difficulty = if keycode == sdl2::keyboard::Keycode::F5 { GameDifficulty::Easy }
-                  else { GameDifficulty::Hard };
-              let game_start_instant = now + self.countdown_duration;
-              self.counting_down = true;
-              self.score.start_game(game_start_instant);
-              self.highscore_table.hide();
-              self.landscape.start_game(game_start_instant);
-              self.level.start_game(game_start_instant);
-              self.sleigh.start_game(game_start_instant);
-
-            } else if keycode == sdl2::keyboard::Keycode::Escape {
-              match self.mode {
-                GameMode::Menu => {
-                  self.quit_flag = true;
-                },
-                GameMode::HelpSplash1 | GameMode::HelpSplash2 | GameMode::HighscoreTable => {
-                  self.mode = GameMode::Menu;
-                  self.highscore_table.hide();
-                },
-                GameMode::Running => {
-                  self.mode = GameMode::Menu;
-                  self.score.start_menu();
-                  self.landscape.start_menu();
-                  self.level.start_menu();
-                  self.sleigh.start_menu();
-                },
-                _ => {},
-              }
-
-            } else if ((keycode == sdl2::keyboard::Keycode::Escape)
-                    || (keycode == sdl2::keyboard::Keycode::Space))
-                  && ((self.mode == GameMode::HelpSplash1)
-                    || (self.mode == GameMode::HelpSplash2)) {
-              self.mode = GameMode::Menu;
-
-            } else if (keycode == sdl2::keyboard::Keycode::Space)
-                  && (self.mode == GameMode::Running) {
-              self.sleigh.drop_gift(self.asset_library, &self.level, self.difficulty);
-
-            } else if (keycode == sdl2::keyboard::Keycode::Backspace)
-                  && (self.mode == GameMode::NewHighscore) {
-              let highscore = &mut self.options.highscores_mut()[
-                  self.highscore_table.new_highscore_index()];
-              let mut highscore_name = highscore.name();
-
-              if highscore_name.len() > 0 {
-                highscore_name.truncate(highscore_name.len() - 1);
-                highscore.set_name(highscore_name);
-              }
-
-            } else if ((keycode == sdl2::keyboard::Keycode::Return)
-                    || (keycode == sdl2::keyboard::Keycode::KpEnter))
-                  && (self.mode == GameMode::NewHighscore) {
-              self.mode = GameMode::HighscoreTable;
-              self.highscore_table.show();
+              let fullscreen = self.canvas.window().fullscreen_state()
+                  != sdl2::video::FullscreenType::True;
+              self.set_fullscreen(fullscreen);
               self.options.save();
-              self.text_input_util.stop();
+
+            } else if (self.mode == GameMode::NewHighscore) && self.edit_highscore_name(keycode) {
+              // keys for editing the name are not mapped to actions
+
+            } else {
+              self.process_actions(scancode, &actions);
             }
           }
         },
+        sdl2::event::Event::ControllerDeviceAdded{..}
+            | sdl2::event::Event::ControllerDeviceRemoved{..}
+            | sdl2::event::Event::ControllerButtonDown{..} => {
+          let actions = self.controllers.handle_event(&event);
+          if !actions.is_empty() { self.process_actions(None, &actions); }
+        },
         sdl2::event::Event::TextInput{text, ..} => {
           if self.mode == GameMode::NewHighscore {
-            let highscore = &mut self.options.highscores_mut()[
-                self.highscore_table.new_highscore_index()];
-            let mut highscore_name = highscore.name();
-
-            if (highscore_name.len() + 1 <= self.highscore_table.max_name_length())
-                  && (text.len() == 1) {
-              highscore_name += &text;
-              highscore.set_name(highscore_name);
-            }
+            // characters without glyph in the font are rejected
+            let font = &self.font;
+            self.highscore_table.name_field_mut().insert(&text, |x| font.has_glyph(x));
+            self.update_highscore_name();
           }
         },
         _ => {},
@@ -275,109 +271,718 @@ impl<'a> Game<'a> {
     }
   }
 
-  fn check_keyboard_state(&mut self) {
-    let keyboard_state = self.event_pump.keyboard_state();
+  // returns whether the key edits the name of the new highscore
+  fn edit_highscore_name(&mut self, keycode: sdl2::keyboard::Keycode) -> bool {
+    let name_field = self.highscore_table.name_field_mut();
+
+    match keycode {
+      sdl2::keyboard::Keycode::Backspace => name_field.delete_backward(),
+      sdl2::keyboard::Keycode::Delete => name_field.delete_forward(),
+      sdl2::keyboard::Keycode::Left => name_field.move_left(),
+      sdl2::keyboard::Keycode::Right => name_field.move_right(),
+      sdl2::keyboard::Keycode::Home => name_field.move_home(),
+      sdl2::keyboard::Keycode::End => name_field.move_end(),
+      _ => return false,
+    }
 
-    match self.mode {
-      GameMode::Running => {
-        self.sleigh.check_keyboard_state(&keyboard_state);
+    self.update_highscore_name();
+    return true;
+  }
+
+  fn update_highscore_name(&mut self) {
+    let name = self.highscore_table.name_field().text();
+    self.options.highscores_mut(self.highscore_table.table_key())[
+        self.highscore_table.new_highscore_index()].set_name(name);
+  }
+
+  fn process_actions(&mut self, scancode: Option<sdl2::keyboard::Scancode>,
+        actions: &Vec<Action>) {
+    let is = |action| actions.contains(&action);
+
+    if self.mode == GameMode::Options {
+      self.process_options_key(scancode, actions);
+
+    } else if self.mode == GameMode::LevelSelect {
+      self.process_level_select_key(actions);
+
+    } else if is(Action::Confirm) && (self.mode == GameMode::NewHighscore) {
+      let table_key = self.highscore_table.table_key().to_string();
+      let new_highscore_index = self.highscore_table.new_highscore_index();
+
+      if let Some(replay) = self.finished_replay.take() {
+        if let Err(error) = self.options.attach_highscore_replay(&table_key, new_highscore_index,
+              &replay) {
+          println!("{}", error);
+        }
+      }
+
+      let highscore = self.options.highscores(&table_key)[new_highscore_index].clone();
+      self.options.set_last_highscore_name(highscore.name());
+      self.submit_global_highscore(&table_key, &highscore);
+      self.options.save();
+      self.text_input_util.stop();
+      self.submit_pending_highscores();
+
+    } else if self.mode == GameMode::NewHighscore {
+      // other keys are entered as text of the name
+
+    } else if is(Action::Mute) {
+      self.set_muted(!self.options.muted());
+      self.options.save();
+
+    } else if is(Action::Options)
+          && ((self.mode == GameMode::Menu) || (self.mode == GameMode::HighscoreTable)) {
+      self.mode = GameMode::Options;
+      self.highscore_table.hide();
+      self.options_menu.show(0);
+
+    } else if is(Action::Levels)
+          && ((self.mode == GameMode::Menu) || (self.mode == GameMode::HighscoreTable)) {
+      // the last unlocked level is selected initially
+      let level_names = self.asset_library.level_names();
+      let selected_index = (0 .. level_names.len()).rev()
+          .find(|&i| self.options.campaign_progress().is_unlocked(&level_names, i)).unwrap_or(0);
+      self.mode = GameMode::LevelSelect;
+      self.highscore_table.hide();
+      self.level_select_menu.show(selected_index);
+
+    } else if is(Action::Help1)
+          && ((self.mode == GameMode::Menu) || (self.mode == GameMode::HelpSplash1)
+            || (self.mode == GameMode::HelpSplash2)
+            || (self.mode == GameMode::HighscoreTable)) {
+      self.mode = GameMode::HelpSplash1;
+      self.highscore_table.hide();
+
+    } else if is(Action::Help2)
+          && ((self.mode == GameMode::Menu) || (self.mode == GameMode::HelpSplash1)
+            || (self.mode == GameMode::HelpSplash2)
+            || (self.mode == GameMode::HighscoreTable)) {
+      self.mode = GameMode::HelpSplash2;
+      self.highscore_table.hide();
+
+    } else if (is(Action::SteerUp) || is(Action::SteerDown))
+          && (self.mode == GameMode::HighscoreTable) {
+      let number_of_highscores = self.shown_highscores().map_or(0, |x| x.len());
+
+      if is(Action::SteerUp) {
+        self.highscore_table.select_previous(number_of_highscores);
+      } else {
+        self.highscore_table.select_next(number_of_highscores);
+      }
+
+    } else if (is(Action::DropGift) && (self.mode == GameMode::HighscoreTable))
+          || (is(Action::Menu) && self.highscore_table.details_shown()) {
+      self.highscore_table.toggle_details();
+
+    } else if (is(Action::SteerLeft) || is(Action::SteerRight))
+          && ((self.mode == GameMode::Menu) || (self.mode == GameMode::HelpSplash1)
+            || (self.mode == GameMode::HelpSplash2)
+            || (self.mode == GameMode::HighscoreTable)) {
+      let pages = [GameMode::Menu, GameMode::HelpSplash1, GameMode::HelpSplash2,
+          GameMode::HighscoreTable];
+      let index = pages.iter().position(|&x| x == self.mode).unwrap_or(0);
+      let offset = if is(Action::SteerLeft) { pages.len() - 1 } else { 1 };
+      self.mode = pages[(index + offset) % pages.len()];
+      if self.mode == GameMode::HighscoreTable {
+        self.highscore_table.show(&self.default_table_key());
+      } else {
+        self.highscore_table.hide();
+      }
+
+    } else if is(Action::Highscores) && (self.mode == GameMode::Menu) {
+      self.mode = GameMode::HighscoreTable;
+      self.highscore_table.show(&self.default_table_key());
+
+    } else if is(Action::Highscores) && (self.mode == GameMode::HighscoreTable)
+          && !self.highscore_table.global() && self.global_leaderboard.is_some() {
+      self.highscore_table.show_global();
+
+    } else if is(Action::Highscores) && (self.mode == GameMode::HighscoreTable) {
+      self.mode = GameMode::Menu;
+      self.highscore_table.hide();
+
+    } else if (is(Action::StartEasy) || is(Action::StartHard) || is(Action::Confirm))
+          && ((self.mode == GameMode::Menu) || (self.mode == GameMode::HighscoreTable)) {
+      let difficulty = if is(Action::StartEasy) {
+        GameDifficulty::Easy
+      } else if is(Action::StartHard) {
+        GameDifficulty::Hard
+      } else {
+        self.options.difficulty()
+      };
+      self.start_campaign(0, difficulty);
+
+    } else if is(Action::Menu) {
+      match self.mode {
+        GameMode::Menu => {
+          self.quit_flag = true;
+        },
+        GameMode::HelpSplash1 | GameMode::HelpSplash2 | GameMode::HighscoreTable => {
+          self.mode = GameMode::Menu;
+          self.highscore_table.hide();
+        },
+        GameMode::Running | GameMode::Paused => {
+          self.music.resume();
+          self.finish_run();
+          self.campaign = None;
+          self.replay_player = None;
+          self.mode = GameMode::Menu;
+          self.simulation.start_menu();
+        },
+        _ => {},
+      }
+
+    } else if (is(Action::Confirm) || is(Action::DropGift))
+          && ((self.mode == GameMode::HelpSplash1)
+            || (self.mode == GameMode::HelpSplash2)) {
+      self.mode = GameMode::Menu;
+
+    } else if is(Action::DropGift) && (self.mode == GameMode::Running) {
+      self.drop_gift_requested = true;
+
+    } else if is(Action::Pause) && (self.mode == GameMode::Running) {
+      self.pause();
+
+    } else if is(Action::Pause) && (self.mode == GameMode::Paused) {
+      self.resume();
+    }
+  }
+
+  fn process_options_key(&mut self, scancode: Option<sdl2::keyboard::Scancode>,
+        actions: &Vec<Action>) {
+    let entry = OPTIONS_ENTRIES[self.options_menu.selected_index()];
+    let is = |action| actions.contains(&action);
+
+    if self.options_menu.waiting_for_key() {
+      if let (Some(scancode), OptionsEntry::Keys(action, _)) = (scancode, entry) {
+        if !is(Action::Menu) { self.add_key(action, scancode); }
+      }
+
+      self.options_menu.stop_waiting_for_key();
+      return;
+    }
+
+    if scancode == Some(sdl2::keyboard::Scancode::Backspace) {
+      if let OptionsEntry::Keys(action, _) = entry {
+        let mut controls = self.options.controls().clone();
+        controls.reset_keys(action);
+        self.set_controls(controls);
+      }
+    } else if is(Action::SteerUp) {
+      self.options_menu.select_previous(OPTIONS_ENTRIES.len());
+    } else if is(Action::SteerDown) {
+      self.options_menu.select_next(OPTIONS_ENTRIES.len());
+    } else if is(Action::SteerLeft) {
+      self.change_option(entry, -1.0);
+    } else if is(Action::SteerRight) {
+      self.change_option(entry, 1.0);
+    } else if is(Action::Confirm) || is(Action::DropGift) {
+      match entry {
+        OptionsEntry::Keys(..) => self.options_menu.start_waiting_for_key(),
+        _ => self.change_option(entry, 1.0),
+      }
+    } else if is(Action::Menu) || is(Action::Options) {
+      self.options.save();
+      self.options_menu.hide();
+      self.mode = GameMode::Menu;
+    }
+  }
+
+  fn process_level_select_key(&mut self, actions: &Vec<Action>) {
+    let level_names = self.asset_library.level_names();
+    let index = self.level_select_menu.selected_index();
+    let is = |action| actions.contains(&action);
+
+    if is(Action::SteerUp) {
+      self.level_select_menu.select_previous(level_names.len());
+    } else if is(Action::SteerDown) {
+      self.level_select_menu.select_next(level_names.len());
+    } else if (is(Action::Confirm) || is(Action::DropGift) || is(Action::StartEasy)
+          || is(Action::StartHard))
+          && self.options.campaign_progress().is_unlocked(&level_names, index) {
+      let difficulty = if is(Action::StartEasy) {
+        GameDifficulty::Easy
+      } else if is(Action::StartHard) {
+        GameDifficulty::Hard
+      } else {
+        self.options.difficulty()
+      };
+      self.level_select_menu.hide();
+      self.start_campaign(index, difficulty);
+    } else if is(Action::Menu) || is(Action::Levels) {
+      self.level_select_menu.hide();
+      self.mode = GameMode::Menu;
+    }
+  }
+
+  // pairs of level name and whether it is locked or completed
+  fn level_select_entries(&self) -> Vec<(String, String)> {
+    let level_names = self.asset_library.level_names();
+    let progress = self.options.campaign_progress();
+
+    return level_names.iter().enumerate().map(|(i, level_name)| {
+      let label = format!("{}. {}", i + 1, self.asset_library.get_level(level_name).name());
+      let value = if !progress.is_unlocked(&level_names, i) {
+        Message::LevelLocked.text()
+      } else if progress.is_completed(level_name) {
+        Message::LevelCompleted.text()
+      } else {
+        ""
+      };
+      (label, value.to_string())
+    }).collect();
+  }
+
+  fn change_option(&mut self, entry: OptionsEntry, direction: f64) {
+    let volume_step = 0.1;
+
+    match entry {
+      OptionsEntry::Fullscreen => self.set_fullscreen(!self.options.fullscreen_enabled()),
+      OptionsEntry::Muted => self.set_muted(!self.options.muted()),
+      OptionsEntry::MasterVolume => {
+        let master_volume = self.options.master_volume();
+        self.options.set_master_volume(
+            ((master_volume / volume_step).round() + direction) * volume_step);
+        self.apply_volumes();
+      },
+      OptionsEntry::MusicVolume => {
+        let music_volume = self.options.music_volume();
+        self.options.set_music_volume(
+            ((music_volume / volume_step).round() + direction) * volume_step);
+        self.apply_volumes();
+      },
+      OptionsEntry::EffectsVolume => {
+        let effects_volume = self.options.effects_volume();
+        self.options.set_effects_volume(
+            ((effects_volume / volume_step).round() + direction) * volume_step);
+        self.apply_volumes();
+      },
+      OptionsEntry::Difficulty => {
+        self.options.set_difficulty(match self.options.difficulty() {
+          GameDifficulty::Easy => GameDifficulty::Hard,
+          GameDifficulty::Hard => GameDifficulty::Easy,
+        });
+      },
+      OptionsEntry::Language => {
+        let index = Language::ALL.iter().position(|&x| x == self.options.language())
+            .unwrap_or(0) as f64;
+        let number_of_languages = Language::ALL.len() as f64;
+        let index = (index + direction + number_of_languages) % number_of_languages;
+        self.options.set_language(Language::ALL[index as usize]);
       },
       _ => {},
     }
   }
 
-  fn do_logic(&mut self) {
-    let now = std::time::Instant::now();
+  fn set_muted(&mut self, muted: bool) {
+    self.options.set_muted(muted);
+    self.apply_volumes();
+  }
 
-    match self.mode {
-      GameMode::WonSplash => {
-        if now >= self.splash_end_instant {
-          let score_points = self.score.score_points();
-          let number_of_highscores = self.options.number_of_highscores();
-          let highscores = self.options.highscores_mut();
-          let highscore_after_new_score = highscores.iter().enumerate().find(
-              |x| x.1.points() as f64 <= score_points);
+  fn apply_volumes(&mut self) {
+    if let Some(audio) = self.audio.as_mut() {
+      audio.apply_volumes(self.options);
+    } else if self.options.verbose_enabled() {
+      println!("Sound is disabled, volume changes take effect after a restart");
+    }
+  }
 
-          self.music.play();
+  fn add_key(&mut self, action: Action, scancode: sdl2::keyboard::Scancode) {
+    let mut controls = self.options.controls().clone();
 
-          if let Some((new_highscore_index, _)) = highscore_after_new_score {
-            self.mode = GameMode::NewHighscore;
-            self.highscore_table.new_highscore(new_highscore_index);
-            highscores.insert(new_highscore_index,
-                options::Highscore::new("", score_points as i32));
+    if controls.add_key(action, scancode.name()) {
+      self.set_controls(controls);
+    } else if self.options.verbose_enabled() {
+      println!("Key '{}' is the only key of another action", scancode.name());
+    }
+  }
 
-            if highscores.len() > number_of_highscores {
-              highscores.resize(number_of_highscores, options::Highscore::new("", 0));
-            }
+  fn set_controls(&mut self, controls: options::ControlsConfig) {
+    self.key_bindings = key_bindings::KeyBindings::new(&controls);
+    self.options.set_controls(controls);
+  }
 
-            self.text_input_util.start();
-          } else {
-            self.mode = GameMode::HighscoreTable;
+  fn set_fullscreen(&mut self, fullscreen: bool) {
+    let fullscreen_state = if fullscreen { sdl2::video::FullscreenType::True }
+        else { sdl2::video::FullscreenType::Off };
+    self.canvas.window_mut().set_fullscreen(fullscreen_state).expect(
+        "Could not change fullscreen state");
+    self.options.set_fullscreen_enabled(fullscreen);
+  }
+
+  fn options_entries(&self) -> Vec<(String, String)> {
+    let on_off = |x| if x { Message::On } else { Message::Off }.text().to_string();
+    let percent = |x: f64| format!("{}", (100.0 * x).round());
+    let controls = self.options.controls();
+
+    return OPTIONS_ENTRIES.iter().map(|entry| match entry {
+      OptionsEntry::Fullscreen => (Message::OptionFullscreen,
+          on_off(self.options.fullscreen_enabled())),
+      OptionsEntry::Muted => (Message::OptionSound, on_off(!self.options.muted())),
+      OptionsEntry::MasterVolume => (Message::OptionMasterVolume,
+          percent(self.options.master_volume())),
+      OptionsEntry::MusicVolume => (Message::OptionMusicVolume,
+          percent(self.options.music_volume())),
+      OptionsEntry::EffectsVolume => (Message::OptionEffectsVolume,
+          percent(self.options.effects_volume())),
+      OptionsEntry::Difficulty => (Message::OptionDifficulty,
+          self.options.difficulty().label().text().to_string()),
+      OptionsEntry::Language => (Message::OptionLanguage,
+          self.options.language().name().to_string()),
+      OptionsEntry::Keys(action, label) => (*label, controls.keys(*action).join(" / ")),
+    }).map(|(label, value)| (label.text().to_string(), value)).collect();
+  }
+
+  fn start_campaign(&mut self, level_index: usize, difficulty: GameDifficulty) {
+    let campaign = campaign::Campaign::new(self.asset_library.level_names(), level_index,
+        difficulty);
+    let level_name = campaign.level_name().to_string();
+    let seed = self.options.seed().unwrap_or_else(|| rand::thread_rng().gen());
+    self.campaign = Some(campaign);
+    self.start_game(&level_name, difficulty, seed);
+  }
+
+  // returns false if there is no campaign or if its last level has been played
+  fn start_next_level(&mut self) -> bool {
+    let (level_name, difficulty) = match &mut self.campaign {
+      Some(campaign) if campaign.has_next_level() => {
+        campaign.advance();
+        (campaign.level_name().to_string(), campaign.difficulty())
+      },
+      _ => {
+        self.campaign = None;
+        return false;
+      },
+    };
+
+    let seed = self.options.seed().unwrap_or_else(|| rand::thread_rng().gen());
+    self.start_game(&level_name, difficulty, seed);
+    return true;
+  }
+
+  fn start_game(&mut self, level_name: &str, difficulty: GameDifficulty, seed: u64) {
+    self.mode = GameMode::Running;
+
+    if self.options.verbose_enabled() {
+      println!("Starting level '{}' with seed {}", level_name, seed);
+    }
+
+    self.simulation.start_game(level_name, difficulty, seed);
+    self.highscore_table.hide();
+
+    if self.replay_player.is_none() {
+      self.recorded_replay = Some(replay::Replay::new(seed, difficulty, level_name));
+    }
+  }
+
+  fn pause(&mut self) {
+    self.mode = GameMode::Paused;
+    self.drop_gift_requested = false;
+    self.music.pause();
+  }
+
+  fn resume(&mut self) {
+    self.mode = GameMode::Running;
+    self.music.resume();
+  }
+
+  fn start_replay(&mut self, replay: replay::Replay) {
+    let difficulty = replay.difficulty();
+    let seed = replay.seed();
+    let level_name = replay.level().to_string();
+    self.campaign = None;
+    self.recorded_replay = None;
+    self.replay_player = Some(replay::ReplayPlayer::new(replay));
+    self.start_game(&level_name, difficulty, seed);
+  }
+
+  fn finish_run(&mut self) {
+    if self.replay_player.is_some() {
+      println!("{}", Message::ReplayFinished.format(&[&self.simulation.result()]));
+    }
+
+    if let Some(recorded_replay) = self.recorded_replay.take() {
+      if let Some(file_path) = self.options.record_file_path() {
+        match recorded_replay.save(file_path) {
+          Ok(()) => if self.options.verbose_enabled() {
+            println!("Saved replay to '{}'", file_path.display());
+          },
+          Err(error) => println!("{}", error),
+        }
+      }
+
+      self.finished_replay = Some(recorded_replay);
+    }
+  }
+
+  fn run_ticks(&mut self) {
+    let last_frame_clock_instant = self.frame_clock.now();
+    self.frame_clock.tick();
+
+    // the game clock is not advanced while paused, which freezes all gameplay timers
+    if self.mode == GameMode::Paused { return; }
+
+    self.tick_accumulator += (self.frame_clock.now() - last_frame_clock_instant).min(
+        self.max_frame_duration);
+
+    let tick_duration = self.simulation.tick_duration();
+
+    while self.tick_accumulator >= tick_duration {
+      self.simulation.tick();
+      self.check_input();
+      self.do_logic();
+      self.rumble();
+      self.tick_accumulator -= tick_duration;
+    }
+
+    self.interpolation = self.tick_accumulator.as_secs_f64() / tick_duration.as_secs_f64();
+  }
+
+  fn check_input(&mut self) {
+    if self.mode == GameMode::Running {
+      let input = match &mut self.replay_player {
+        Some(replay_player) => replay_player.next_input(),
+        None => Some(Game::tick_input_from_keyboard_state(&self.event_pump.keyboard_state(),
+            &self.key_bindings, self.controllers.steering(), self.drop_gift_requested)),
+      };
+
+      match input {
+        Some(input) => {
+          if let Some(recorded_replay) = &mut self.recorded_replay {
+            recorded_replay.record(input);
+          }
+
+          self.simulation.check_input(&input);
+        },
+        None => {
+          self.finish_run();
+          self.replay_player = None;
+          self.mode = GameMode::Menu;
+          self.simulation.start_menu();
+        },
+      }
+    }
+
+    self.drop_gift_requested = false;
+  }
+
+  fn tick_input_from_keyboard_state(keyboard_state: &sdl2::keyboard::KeyboardState,
+        key_bindings: &key_bindings::KeyBindings, steering: (f64, f64),
+        drop_gift: bool) -> input::TickInput {
+    return input::TickInput::new(
+        key_bindings.is_pressed(keyboard_state, Action::SteerLeft),
+        key_bindings.is_pressed(keyboard_state, Action::SteerRight),
+        key_bindings.is_pressed(keyboard_state, Action::SteerUp),
+        key_bindings.is_pressed(keyboard_state, Action::SteerDown),
+        steering.0, steering.1, drop_gift);
+  }
+
+  fn rumble(&mut self) {
+    let strength = self.simulation.events().iter().map(|event| match event {
+      event::GameEvent::SleighHitTile{..} => 0.5,
+      event::GameEvent::SleighHitNpc{..} => 0.8,
+      _ => 0.0,
+    }).fold(0.0, f64::max);
+
+    if strength > 0.0 {
+      self.controllers.rumble(strength, std::time::Duration::from_millis(200));
+    }
+  }
+
+  fn do_logic(&mut self) {
+    let now = self.simulation.now();
+
+    match self.mode {
+      GameMode::WonSplash => {
+        if (now >= self.splash_end_instant) && self.replay_player.take().is_some() {
+          self.music.play();
+          self.mode = GameMode::Menu;
+          self.simulation.start_menu();
+        } else if now >= self.splash_end_instant {
+          let difficulty = self.simulation.difficulty();
+          self.pending_highscores.push((
+              highscore::table_key(self.simulation.level().name(), difficulty),
+              highscore::Highscore::from_simulation(&self.simulation),
+              self.finished_replay.take()));
+
+          if let Some(highscore) = self.campaign.as_ref().and_then(|x| x.total_highscore()) {
+            self.pending_highscores.push((
+                highscore::table_key(highscore::CAMPAIGN_TABLE_NAME, difficulty), highscore,
+                None));
           }
 
-          self.score.start_menu();
-          self.landscape.start_menu();
-          self.level.start_menu();
-          self.sleigh.start_menu();
+          self.music.play();
+          self.submit_pending_highscores();
         }
       },
       GameMode::LostDueToDamageSplash | GameMode::LostDueToTimeSplash => {
         if now >= self.splash_end_instant {
           self.music.play();
           self.mode = GameMode::Menu;
-          self.score.start_menu();
-          self.landscape.start_menu();
-          self.level.start_menu();
-          self.sleigh.start_menu();
+          self.campaign = None;
+          self.replay_player = None;
+          self.simulation.start_menu();
         }
       },
       GameMode::HelpSplash1 | GameMode::HelpSplash2 => {},
       _ => {
-        self.score.do_logic();
-        self.landscape.do_logic(&self.level);
-        self.level.do_logic(self.asset_library, &mut self.score, &mut self.landscape,
-            &mut self.sleigh);
-        self.sleigh.do_logic(&mut self.score, &mut self.level);
+        self.simulation.do_logic();
+
+        let newly_unlocked = self.simulation.achievements_mut().take_newly_unlocked();
+
+        for &achievement in newly_unlocked.iter() {
+          if self.options.verbose_enabled() {
+            println!("{}", Message::AchievementUnlocked.format(&[&achievement]));
+          }
+
+          // runs of played back replays don't count
+          if self.replay_player.is_none() { self.options.unlock_achievement(achievement); }
+        }
+
+        if !newly_unlocked.is_empty() && self.replay_player.is_none() { self.options.save(); }
 
-        if self.score.won() {
+        if self.simulation.score().won() {
           if self.mode == GameMode::Running {
             self.music.stop();
             self.won_sound.play();
             self.mode = GameMode::WonSplash;
             self.splash_end_instant = now + self.splash_duration;
+            self.finish_run();
+            self.complete_level();
           } else {
-            self.score.start_menu();
-            self.landscape.start_menu();
-            self.level.start_menu();
-            self.sleigh.start_menu();
+            self.simulation.start_menu();
           }
-        } else if self.score.lost_due_to_damage() {
+        } else if self.simulation.score().lost_due_to_damage() {
           self.music.stop();
           self.lost_sound.play();
           self.mode = GameMode::LostDueToDamageSplash;
           self.splash_end_instant = now + self.splash_duration;
-        } else if self.score.lost_due_to_time() {
+          self.finish_run();
+        } else if self.simulation.score().lost_due_to_time() {
           self.music.stop();
           self.lost_sound.play();
           self.mode = GameMode::LostDueToTimeSplash;
           self.splash_end_instant = now + self.splash_duration;
+          self.finish_run();
         }
       }
     }
   }
 
+  // entries making the local table wait for the name to be entered, all others are submitted
+  // with the last entered name; the next level of the campaign starts afterwards
+  fn submit_pending_highscores(&mut self) {
+    let mut shown_table_key = self.highscore_table.table_key().to_string();
+
+    while !self.pending_highscores.is_empty() {
+      let (table_key, mut highscore, replay) = self.pending_highscores.remove(0);
+
+      if let Ok(Some(new_highscore_index)) = self.options.submit(&table_key, &highscore) {
+        self.mode = GameMode::NewHighscore;
+        self.finished_replay = replay;
+        self.highscore_table.new_highscore(&table_key, new_highscore_index,
+            &self.options.last_highscore_name());
+        self.update_highscore_name();
+        self.text_input_util.start();
+        self.simulation.start_menu();
+        return;
+      }
+
+      highscore.set_name(self.options.last_highscore_name());
+      self.submit_global_highscore(&table_key, &highscore);
+      shown_table_key = table_key;
+    }
+
+    if !self.start_next_level() {
+      self.mode = GameMode::HighscoreTable;
+      self.highscore_table.show(&shown_table_key);
+      self.simulation.start_menu();
+    }
+  }
+
+  // won runs are submitted even if they do not make the local table
+  fn submit_global_highscore(&mut self, table_key: &str, highscore: &highscore::Highscore) {
+    if let Some(global_leaderboard) = &mut self.global_leaderboard {
+      if let Err(error) = global_leaderboard.submit(table_key, highscore) {
+        println!("{}", error);
+      }
+    }
+  }
+
+  // adds the entry of the level to the campaign and unlocks the next level
+  fn complete_level(&mut self) {
+    let campaign = match &mut self.campaign {
+      Some(campaign) => campaign,
+      None => return,
+    };
+
+    campaign.complete_level(highscore::Highscore::from_simulation(&self.simulation));
+    self.options.complete_campaign_level(self.simulation.level().name());
+    self.options.save();
+  }
+
+  // shown on the splash after winning a level of a campaign with multiple levels
+  fn campaign_text(&self) -> Option<String> {
+    let campaign = self.campaign.as_ref().filter(|x| x.number_of_levels() > 1)?;
+
+    return Some(if campaign.has_next_level() {
+      Message::CampaignProgress.format(&[&campaign.level_number(),
+          &campaign.number_of_levels(), &campaign.total_points()])
+    } else {
+      Message::CampaignCompleted.format(&[&campaign.number_of_levels(),
+          &campaign.total_points()])
+    });
+  }
+
+  // local or global highscores of the table shown in the highscore table
+  fn shown_highscores(&mut self) -> Result<Vec<highscore::Highscore>, String> {
+    let table_key = self.highscore_table.table_key();
+
+    return match &mut self.global_leaderboard {
+      Some(global_leaderboard) if self.highscore_table.global() => {
+        global_leaderboard.fetch(table_key)
+      },
+      _ => Ok(self.options.highscores(table_key)),
+    };
+  }
+
+  // table of the first level with the configured difficulty
+  fn default_table_key(&self) -> String {
+    let level_names = self.asset_library.level_names();
+    let level_name = level_names.first().map_or(level::DEFAULT_LEVEL_NAME, |x| x.as_str());
+    return highscore::table_key(level_name, self.options.difficulty());
+  }
+
   fn draw(&mut self) {
+    let options_entries = if self.mode == GameMode::Options { self.options_entries() }
+        else { Vec::new() };
+    let level_select_entries = if self.mode == GameMode::LevelSelect {
+      self.level_select_entries()
+    } else {
+      Vec::new()
+    };
+    let campaign_text = if self.mode == GameMode::WonSplash { self.campaign_text() }
+        else { None };
+    let highscores = self.shown_highscores();
     let draw_arguments = DrawArguments{
       options: &self.options,
       buffer_size: self.buffer_size,
       asset_library: &self.asset_library,
+      texture_library: &mut self.texture_library,
       mode: &self.mode,
       font: &self.font,
-      score: &self.score,
+      score: self.simulation.score(),
       highscore_table: &self.highscore_table,
-      landscape: &self.landscape,
-      level: &self.level,
-      sleigh: &self.sleigh,
+      options_menu: &self.options_menu,
+      options_entries: options_entries,
+      level_select_menu: &self.level_select_menu,
+      level_select_entries: level_select_entries,
+      campaign_text: campaign_text,
+      highscores: highscores,
+      landscape: self.simulation.landscape(),
+      level: self.simulation.level(),
+      sleigh: self.simulation.sleigh(),
+      interpolation: self.interpolation,
       fps: self.fps,
     };
 
@@ -413,22 +1018,46 @@ impl<'a> Game<'a> {
       _ => "background",
     };
 
-    draw_arguments.asset_library.get_image(background_image_name).draw(canvas, Point::zero(), 0.0);
+    let mut renderer = sdl_render::SdlRenderer::new(canvas, draw_arguments.texture_library);
+    draw_arguments.asset_library.get_localized_image(background_image_name).draw(&mut renderer,
+        Point::zero(), 0.0);
 
     match draw_arguments.mode {
-      GameMode::Menu | GameMode::HighscoreTable | GameMode::Running | GameMode::NewHighscore => {
-        draw_arguments.landscape.draw(canvas);
-        draw_arguments.level.draw(canvas);
-        draw_arguments.sleigh.draw(canvas, draw_arguments.font, draw_arguments.level);
-        draw_arguments.score.draw(canvas, draw_arguments.font);
-        draw_arguments.highscore_table.draw(canvas, draw_arguments.font,
-            &draw_arguments.options.highscores());
+      GameMode::Menu | GameMode::HighscoreTable | GameMode::Running | GameMode::Paused
+          | GameMode::NewHighscore | GameMode::Options | GameMode::LevelSelect => {
+        draw_arguments.landscape.draw(&mut renderer, draw_arguments.interpolation);
+        draw_arguments.level.draw(&mut renderer, draw_arguments.interpolation);
+        draw_arguments.sleigh.draw(&mut renderer, draw_arguments.font, draw_arguments.level,
+            draw_arguments.interpolation);
+        draw_arguments.score.draw(&mut renderer, draw_arguments.font);
+        draw_arguments.highscore_table.draw(&mut renderer, draw_arguments.font,
+            &draw_arguments.highscores);
+        draw_arguments.options_menu.draw(&mut renderer, draw_arguments.font,
+            &draw_arguments.options_entries);
+        draw_arguments.level_select_menu.draw(&mut renderer, draw_arguments.font,
+            &draw_arguments.level_select_entries);
       },
       _ => {},
     }
 
+    if let Some(campaign_text) = &draw_arguments.campaign_text {
+      draw_arguments.font.draw(&mut renderer, Point::new(draw_arguments.buffer_size.x() / 2.0,
+          draw_arguments.buffer_size.y() - 10.0), campaign_text, ui::Alignment::BottomCenter);
+    }
+
+    if *draw_arguments.mode == GameMode::Paused {
+      renderer.fill_rect(asset::Rect::new(0, 0, draw_arguments.buffer_size.x() as u32,
+          draw_arguments.buffer_size.y() as u32), render::Color::new(0, 0, 0, 127));
+
+      let center = draw_arguments.buffer_size / 2.0;
+      draw_arguments.font.draw(&mut renderer, center, Message::Paused.text(),
+          ui::Alignment::BottomCenter);
+      draw_arguments.font.draw(&mut renderer, center, Message::PausedResume.text(),
+          ui::Alignment::TopCenter);
+    }
+
     if draw_arguments.options.verbose_enabled() {
-      draw_arguments.font.draw(canvas, draw_arguments.buffer_size,
+      draw_arguments.font.draw(&mut renderer, draw_arguments.buffer_size,
           format!("{:.0} FPS", draw_arguments.fps), ui::Alignment::BottomRight);
     }
   }
@@ -0,0 +1,45 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::*;
+use santa_racer_core::input::Action;
+
+pub struct KeyBindings {
+  map: std::collections::HashMap<Action, Vec<sdl2::keyboard::Scancode>>,
+}
+
+impl KeyBindings {
+  pub fn new(controls: &options::ControlsConfig) -> KeyBindings {
+    let mut map = std::collections::HashMap::new();
+
+    for action in Action::ALL.iter() {
+      let scancodes = controls.keys(*action).iter().filter_map(|name| {
+        let scancode = sdl2::keyboard::Scancode::from_name(name);
+        if scancode.is_none() { println!("Unknown key '{}' for {:?} in config", name, action); }
+        scancode
+      }).collect();
+      map.insert(*action, scancodes);
+    }
+
+    return KeyBindings{
+      map: map,
+    };
+  }
+
+  pub fn actions(&self, scancode: Option<sdl2::keyboard::Scancode>) -> Vec<Action> {
+    return match scancode {
+      Some(scancode) => Action::ALL.iter().filter(
+          |action| self.map[action].contains(&scancode)).cloned().collect(),
+      None => Vec::new(),
+    };
+  }
+
+  pub fn is_pressed(&self, keyboard_state: &sdl2::keyboard::KeyboardState,
+        action: Action) -> bool {
+    return self.map[&action].iter().any(|x| keyboard_state.is_scancode_pressed(*x));
+  }
+}
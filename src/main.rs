@@ -7,27 +7,209 @@
 
 #![allow(dead_code)]
 
-mod asset;
+use santa_racer_core::*;
+
+mod controller;
 mod game;
-mod gift;
-mod level;
-mod npc;
+mod key_bindings;
 mod options;
 mod sdl;
-mod sleigh;
-mod ui;
+mod sdl_audio;
+mod sdl_render;
 
 fn main() {
   let mut options = options::Options::load();
 
+  match options.command() {
+    options::Command::ValidateAssets => validate_assets(&options),
+    options::Command::PrintHighscores => print_highscores(&options),
+    options::Command::ExportHighscores => export_highscores(&options),
+    options::Command::ImportHighscores => import_highscores(&mut options),
+    options::Command::VerifyHighscores => verify_highscores(&options),
+    options::Command::SimulateReplay => simulate_replay(&options),
+    options::Command::ConvertLevel => convert_level(&options),
+    options::Command::Play => {},
+  }
+
+  let replay = options.replay_file_path().map(load_replay);
+
   let mut sdl_wrapper = sdl::SdlWrapper::new(&options);
 
-  let asset_library = asset::AssetLibrary::new(&sdl_wrapper.texture_creator, &options);
+  if options.verbose_enabled() { options.print_assets_search_path(); }
+  let mut asset_library = asset::AssetLibrary::new(&options.assets_dirs(),
+      options.verbose_enabled());
+
+  let audio = if options.sound_enabled() {
+    Some(sdl_audio::SdlAudio::new(&mut asset_library, &options))
+  } else {
+    None
+  };
 
   let mut game = game::Game::new(&mut sdl_wrapper.canvas, &sdl_wrapper.texture_creator,
-      &mut sdl_wrapper.event_pump, &sdl_wrapper.text_input_util, &asset_library, &mut options);
+      &mut sdl_wrapper.event_pump, &sdl_wrapper.text_input_util, &mut sdl_wrapper.controllers,
+      &asset_library, audio, &mut options, replay);
 
   game.run_loop();
 
   std::process::exit(0);
 }
+
+fn convert_level(options: &options::Options) {
+  let file_paths = options.level_conversion_file_paths();
+  let name = file_paths[3].file_stem().map_or(String::new(),
+      |x| x.to_string_lossy().to_string());
+
+  if let Err(error) = level_file::LevelFile::convert_legacy_files(name, &file_paths[0],
+        &file_paths[1], &file_paths[2]).and_then(|x| x.save(&file_paths[3])) {
+    println!("{}", error);
+    std::process::exit(1);
+  }
+
+  println!("{}", locale::Message::LevelConverted.format(
+      &[&file_paths[0].display(), &file_paths[3].display()]));
+  std::process::exit(0);
+}
+
+fn load_replay(file_path: &std::path::Path) -> replay::Replay {
+  return replay::Replay::load(file_path).unwrap_or_else(|error| {
+    println!("{}", error);
+    std::process::exit(1);
+  });
+}
+
+fn validate_assets(options: &options::Options) {
+  options.print_assets_search_path();
+
+  match asset::AssetLibrary::validate(&options.assets_dirs()) {
+    Ok(file_paths) => {
+      if options.verbose_enabled() {
+        for file_path in &file_paths { println!("Valid asset '{}'", file_path.display()); }
+      }
+
+      println!("{}", locale::Message::AllAssetsValid.format(&[&file_paths.len()]));
+      std::process::exit(0);
+    },
+    Err(errors) => {
+      for error in errors { println!("{}", error); }
+      std::process::exit(1);
+    },
+  }
+}
+
+fn print_highscores(options: &options::Options) {
+  for (j, (table_key, highscores)) in options.highscore_tables().iter().enumerate() {
+    if j > 0 { println!(); }
+    println!("{}", locale::Message::HighscoresTitle.format(&[&highscore::table_label(table_key)]));
+
+    for (i, highscore) in highscores.iter().enumerate() {
+      println!("{:2}. {:<20} {:6}", i + 1, highscore.display_name(), highscore.points());
+
+      if options.verbose_enabled() && highscore.has_details() {
+        for (label, value) in highscore.details().iter().skip(1) {
+          println!("      {:<18} {}", label, value);
+        }
+      }
+    }
+  }
+
+  std::process::exit(0);
+}
+
+fn export_highscores(options: &options::Options) {
+  let file_path = &options.highscore_file_paths()[0];
+  let format = options.highscore_file_format(file_path);
+
+  if let Err(error) = highscore_file::export_highscores(file_path, format,
+        options.highscore_tables()) {
+    println!("{}", error);
+    std::process::exit(1);
+  }
+
+  println!("{}", locale::Message::HighscoresExported.format(&[&file_path.display()]));
+  std::process::exit(0);
+}
+
+// all files are validated before any of them is merged
+fn import_highscores(options: &mut options::Options) {
+  let mut imported_tables = Vec::new();
+
+  for file_path in options.highscore_file_paths() {
+    let format = options.highscore_file_format(file_path);
+
+    match highscore_file::import_highscores(file_path, format) {
+      Ok(tables) => imported_tables.push((file_path.clone(), tables)),
+      Err(error) => {
+        println!("{}", error);
+        std::process::exit(1);
+      },
+    }
+  }
+
+  for (file_path, tables) in &imported_tables {
+    options.merge_highscore_tables(tables);
+    let number_of_highscores: usize = tables.values().map(|x| x.len()).sum();
+    println!("{}", locale::Message::HighscoresImported.format(
+        &[&number_of_highscores, &file_path.display()]));
+  }
+
+  options.save();
+  print_highscores(options);
+}
+
+fn verify_highscores(options: &options::Options) {
+  let key = options.highscore_key().unwrap_or_else(|error| {
+    println!("{}", error);
+    std::process::exit(1);
+  });
+
+  if options.verbose_enabled() { options.print_assets_search_path(); }
+  let asset_library = asset::AssetLibrary::new(&options.assets_dirs(),
+      options.verbose_enabled());
+  let mut number_of_highscores = 0;
+  let mut number_of_valid_highscores = 0;
+  let mut number_of_unsigned_highscores = 0;
+
+  for (j, (table_key, highscores)) in options.highscore_tables().iter().enumerate() {
+    if j > 0 { println!(); }
+    println!("{}", locale::Message::HighscoresTitle.format(&[&highscore::table_label(table_key)]));
+
+    for (i, highscore) in highscores.iter().enumerate() {
+      if highscore.is_placeholder() { continue; }
+
+      let verification = highscore.verify(&asset_library, &key,
+          options.load_highscore_replay(highscore));
+      println!("{:2}. {:<20} {:6}  {}", i + 1, highscore.name(), highscore.points(),
+          verification);
+
+      number_of_highscores += 1;
+      if verification == highscore::Verification::Valid { number_of_valid_highscores += 1; }
+      if verification == highscore::Verification::Unsigned { number_of_unsigned_highscores += 1; }
+    }
+  }
+
+  println!();
+  println!("{}", locale::Message::VerificationSummary.format(&[&number_of_valid_highscores,
+      &number_of_highscores, &number_of_unsigned_highscores]));
+
+  // unsigned entries are reported, but only tampered entries make the verification fail
+  let tampered = number_of_valid_highscores + number_of_unsigned_highscores
+      < number_of_highscores;
+  std::process::exit(if tampered { 1 } else { 0 });
+}
+
+fn simulate_replay(options: &options::Options) {
+  let replay = match options.replay_file_path() {
+    Some(file_path) => load_replay(file_path),
+    None => {
+      println!("{}", locale::Message::ReplayFileRequired.text());
+      std::process::exit(1);
+    },
+  };
+
+  if options.verbose_enabled() { options.print_assets_search_path(); }
+  let asset_library = asset::AssetLibrary::new(&options.assets_dirs(),
+      options.verbose_enabled());
+  let result = simulation::Simulation::run_replay(&asset_library, &replay);
+  println!("{}", locale::Message::ReplayFinished.format(&[&result]));
+  std::process::exit(0);
+}
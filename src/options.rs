@@ -8,157 +8,926 @@
 use serde::Serialize;
 use serde::Deserialize;
 
+use santa_racer_core::achievement::Achievement;
+use santa_racer_core::campaign::CampaignProgress;
+use santa_racer_core::game::GameDifficulty;
+use santa_racer_core::highscore;
+use santa_racer_core::highscore::Highscore;
+use santa_racer_core::highscore_file;
+use santa_racer_core::highscore_file::HighscoreFileFormat;
+use santa_racer_core::input::Action;
+use santa_racer_core::leaderboard::LeaderboardBackend;
+use santa_racer_core::level;
+use santa_racer_core::locale;
+use santa_racer_core::locale::Language;
+use santa_racer_core::replay;
+
 #[derive(Clone)]
 pub struct Options {
-  fullscreen_enabled: bool,
-  sound_enabled: bool,
+  // settings as persisted in the config file, without command-line overrides
+  config_file: ConfigFile,
+  config_file_path: Option<std::path::PathBuf>,
+
+  command: Command,
   verbose_enabled: bool,
-  highscores: Vec<Highscore>,
+  language: Language,
+  display: DisplayConfig,
+  audio: AudioConfig,
+  controls: ControlsConfig,
+  gameplay: GameplayConfig,
+  leaderboard: LeaderboardConfig,
+  // pairs of directory and where it came from, in the order in which assets are searched
+  assets_search_path: Vec<(std::path::PathBuf, &'static str)>,
+  headless_enabled: bool,
+  seed: Option<u64>,
+  record_file_path: Option<std::path::PathBuf>,
+  replay_file_path: Option<std::path::PathBuf>,
+  highscore_file_paths: Vec<std::path::PathBuf>,
+  // deduced from the file extension if not set
+  highscore_file_format: Option<HighscoreFileFormat>,
+  highscore_tables: std::collections::BTreeMap<String, Vec<Highscore>>,
+  // tile map, NPC map, and chimneys files of the original game followed by the level file
+  level_conversion_file_paths: Vec<std::path::PathBuf>,
 
   number_of_highscores: usize,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Command {
+  Play,
+  ValidateAssets,
+  PrintHighscores,
+  ExportHighscores,
+  ImportHighscores,
+  VerifyHighscores,
+  SimulateReplay,
+  ConvertLevel,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
 struct ConfigFile {
+  verbose: bool,
+  // detected from the locale if not set
+  language: Option<Language>,
+  // prefilled when entering the name of a new highscore
+  last_highscore_name: String,
+  display: DisplayConfig,
+  audio: AudioConfig,
+  controls: ControlsConfig,
+  gameplay: GameplayConfig,
+  leaderboard: LeaderboardConfig,
+  campaign: CampaignProgress,
+  // unlocked in previous runs
+  achievements: Vec<Achievement>,
+  // single table of versions before per-difficulty tables, migrated to the easy table of the
+  // default level
+  #[serde(skip_serializing_if = "Vec::is_empty")]
   highscores: Vec<Highscore>,
+  highscore_tables: std::collections::BTreeMap<String, Vec<Highscore>>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+struct DisplayConfig {
+  fullscreen: bool,
+  window_size: (u32, u32),
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+struct AudioConfig {
+  sound: bool,
+  muted: bool,
+  master_volume: f64,
+  music_volume: f64,
+  effects_volume: f64,
+}
+
+// keys are SDL scancode names, e.g., "Left" or "Space", each action can have multiple keys
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ControlsConfig {
+  steer_left: Vec<String>,
+  steer_right: Vec<String>,
+  steer_up: Vec<String>,
+  steer_down: Vec<String>,
+  drop_gift: Vec<String>,
+  pause: Vec<String>,
+  menu: Vec<String>,
+  confirm: Vec<String>,
+  help1: Vec<String>,
+  help2: Vec<String>,
+  highscores: Vec<String>,
+  options: Vec<String>,
+  start_easy: Vec<String>,
+  start_hard: Vec<String>,
+  mute: Vec<String>,
+  levels: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+struct GameplayConfig {
+  difficulty: GameDifficulty,
+  slow_motion: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
-pub struct Highscore {
-  name: String,
-  points: i32,
+#[serde(default)]
+struct LeaderboardConfig {
+  // base URL of the leaderboard server, global highscores are disabled if empty
+  url: String,
 }
 
 impl Options {
   pub fn load() -> Options {
-    let config_file: ConfigFile = confy::load("santa-racer").expect("Failed to read config");
+    let matches = Options::build_app().get_matches();
+
+    let config_file_path = matches.value_of_os("config").map(std::path::PathBuf::from);
+    let mut config_file: ConfigFile = match &config_file_path {
+      Some(config_file_path) => confy::load_path(config_file_path),
+      None => confy::load("santa-racer"),
+    }.expect("Failed to read config");
+
+    let easy_table_key = highscore::difficulty_key(GameDifficulty::Easy).to_string();
+
+    if !config_file.highscores.is_empty()
+          && !config_file.highscore_tables.contains_key(&easy_table_key) {
+      let highscores = std::mem::replace(&mut config_file.highscores, Vec::new());
+      config_file.highscore_tables.insert(easy_table_key, highscores);
+    }
+
+    config_file.highscores.clear();
+    highscore_file::migrate_table_keys(&mut config_file.highscore_tables);
 
     let mut options = Options {
-      fullscreen_enabled: false,
-      sound_enabled: true,
-      verbose_enabled: false,
-      highscores: config_file.highscores,
+      config_file: config_file.clone(),
+      config_file_path: config_file_path,
+
+      command: Command::Play,
+      verbose_enabled: config_file.verbose,
+      language: Language::German,
+      display: config_file.display.clone(),
+      audio: config_file.audio.clone(),
+      controls: config_file.controls.clone(),
+      gameplay: config_file.gameplay.clone(),
+      leaderboard: config_file.leaderboard.clone(),
+      assets_search_path: Vec::new(),
+      headless_enabled: false,
+      seed: None,
+      record_file_path: None,
+      replay_file_path: None,
+      highscore_file_paths: Vec::new(),
+      highscore_file_format: None,
+      highscore_tables: config_file.highscore_tables.clone(),
+      level_conversion_file_paths: Vec::new(),
 
       number_of_highscores: 10,
     };
 
-    for _ in options.highscores.len() .. options.number_of_highscores {
-      options.highscores.push(Highscore::new("Leer", 0));
-    }
-
-    for argument in std::env::args().skip(1) {
-      if (argument == "-h") || (argument == "--help") {
-        Options::print_description();
-        println!("");
-        println!("-f, --fullscreen     enable fullscreen mode");
-        println!("    --no-fullscreen  disable fullscreen mode");
-        println!("-s, --sound          enable sound");
-        println!("    --no-sound       disable sound");
-        println!("-v, --verbose        increase verbosity");
-        println!("-h, --help           display help message");
-        println!("-V, --version        display version");
-        println!("-L, --license        display license info");
-        std::process::exit(0);
-      } else if (argument == "-V") || (argument == "--version") {
-        println!("Santa Racer {}", env!("CARGO_PKG_VERSION"));
-        std::process::exit(0);
-      } else if (argument == "-L") || (argument == "--license") {
-        Options::print_description();
-        std::process::exit(0);
-      } else if (argument == "-f") || (argument == "--fullscreen") {
-        options.fullscreen_enabled = true;
-      } else if argument == "--no-fullscreen" {
-        options.fullscreen_enabled = false;
-      } else if (argument == "-s") || (argument == "--sound") {
-        options.sound_enabled = true;
-      } else if argument == "--no-sound" {
-        options.sound_enabled = false;
-      } else if (argument == "-v") || (argument == "--verbose") {
-        options.verbose_enabled = true;
-      }
+    if matches.is_present("reset-config") {
+      let campaign = config_file.campaign.clone();
+      let achievements = config_file.achievements.clone();
+      config_file = ConfigFile::new(config_file.highscore_tables);
+      config_file.campaign = campaign;
+      config_file.achievements = achievements;
+      options.verbose_enabled = config_file.verbose;
+      options.display = config_file.display.clone();
+      options.audio = config_file.audio.clone();
+      options.controls = config_file.controls.clone();
+      options.gameplay = config_file.gameplay.clone();
+      options.leaderboard = config_file.leaderboard.clone();
+      options.config_file = config_file;
+      options.save();
+    }
+
+    options.language = matches.value_of("language").and_then(Language::from_code)
+        .or(options.config_file.language).or_else(Language::from_environment)
+        .unwrap_or(Language::German);
+    locale::set_language(options.language);
+
+    if matches.is_present("license") {
+      Options::print_description();
+      std::process::exit(0);
+    }
+
+    options.fill_highscore_tables();
+
+    if matches.is_present("fullscreen") { options.display.fullscreen = true; }
+    if matches.is_present("no-fullscreen") { options.display.fullscreen = false; }
+    if matches.is_present("sound") { options.audio.sound = true; }
+    if matches.is_present("no-sound") { options.audio.sound = false; }
+    if matches.is_present("mute") { options.audio.muted = true; }
+    if matches.is_present("slow-motion") { options.gameplay.slow_motion = true; }
+    if matches.is_present("headless") { options.headless_enabled = true; }
+    if matches.is_present("verbose") { options.verbose_enabled = true; }
+
+    if let Some(window_size) = matches.value_of("window-size") {
+      options.display.window_size = Options::parse_window_size(window_size)
+          .expect("Window size has already been validated");
+    }
+
+    if let Some(volume) = matches.value_of("volume") {
+      options.audio.master_volume = Options::parse_volume(volume)
+          .expect("Volume has already been validated");
+    }
+
+    if let Some(volume) = matches.value_of("music-volume") {
+      options.audio.music_volume = Options::parse_volume(volume)
+          .expect("Volume has already been validated");
+    }
+
+    if let Some(volume) = matches.value_of("effects-volume") {
+      options.audio.effects_volume = Options::parse_volume(volume)
+          .expect("Volume has already been validated");
+    }
+
+    if let Some(difficulty) = matches.value_of("difficulty") {
+      options.gameplay.difficulty = Options::parse_difficulty(difficulty)
+          .expect("Difficulty has already been validated");
+    }
+
+    if let Some(url) = matches.value_of("leaderboard-url") {
+      options.leaderboard.url = url.to_string();
+    }
+
+    if let Some(seed) = matches.value_of("seed") {
+      options.seed = Some(seed.parse().expect("Seed has already been validated"));
+    }
+
+    options.assets_search_path = Options::build_assets_search_path(
+        matches.value_of_os("assets-dir").map(std::path::PathBuf::from));
+
+    options.record_file_path = matches.value_of_os("record").map(std::path::PathBuf::from);
+    options.replay_file_path = matches.value_of_os("replay").map(std::path::PathBuf::from);
+
+    match matches.subcommand() {
+      ("validate-assets", _) => options.command = Command::ValidateAssets,
+      ("print-highscores", _) => options.command = Command::PrintHighscores,
+      ("verify-highscores", _) => options.command = Command::VerifyHighscores,
+      ("export-highscores", Some(sub_matches)) | ("import-highscores", Some(sub_matches)) => {
+        options.command = if matches.subcommand_name() == Some("export-highscores") {
+          Command::ExportHighscores
+        } else {
+          Command::ImportHighscores
+        };
+        options.highscore_file_paths = sub_matches.values_of_os("FILE").into_iter().flatten()
+            .map(std::path::PathBuf::from).collect();
+        options.highscore_file_format = sub_matches.value_of("format")
+            .and_then(HighscoreFileFormat::from_name);
+      },
+      ("convert-level", Some(sub_matches)) => {
+        options.command = Command::ConvertLevel;
+        options.level_conversion_file_paths = ["TILE_MAP", "NPC_MAP", "CHIMNEYS", "FILE"].iter()
+            .filter_map(|&x| sub_matches.value_of_os(x)).map(std::path::PathBuf::from)
+            .collect();
+      },
+      ("simulate-replay", Some(sub_matches)) => {
+        options.command = Command::SimulateReplay;
+        options.replay_file_path = sub_matches.value_of_os("FILE").map(std::path::PathBuf::from);
+      },
+      _ => if options.headless_enabled {
+        options.command = Command::SimulateReplay;
+      },
     }
 
     return options;
   }
 
-  pub fn save(&self) {
-    confy::store("santa-racer", ConfigFile::new(self.highscores.to_vec())).expect(
-        "Could not save options");
+  fn build_app() -> clap::App<'static, 'static> {
+    return clap::App::new("Santa Racer")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("An open-source clone of \"Nikolaus Express 2000\".")
+        .setting(clap::AppSettings::VersionlessSubcommands)
+        .arg(clap::Arg::with_name("fullscreen").short("f").long("fullscreen")
+          .overrides_with("no-fullscreen").help("Enable fullscreen mode"))
+        .arg(clap::Arg::with_name("no-fullscreen").long("no-fullscreen")
+          .overrides_with("fullscreen").help("Disable fullscreen mode"))
+        .arg(clap::Arg::with_name("window-size").long("window-size").value_name("WIDTHxHEIGHT")
+          .validator(|x| Options::parse_window_size(&x).map(|_| ()))
+          .help("Set the size of the window, e.g., 1280x960"))
+        .arg(clap::Arg::with_name("sound").short("s").long("sound")
+          .overrides_with("no-sound").help("Enable sound"))
+        .arg(clap::Arg::with_name("no-sound").long("no-sound")
+          .overrides_with("sound").help("Disable sound"))
+        .arg(clap::Arg::with_name("mute").long("mute")
+          .help("Start with muted sound, toggled with M"))
+        .arg(clap::Arg::with_name("volume").long("volume").value_name("PERCENT")
+          .validator(|x| Options::parse_volume(&x).map(|_| ()))
+          .help("Set the master volume from 0 to 100"))
+        .arg(clap::Arg::with_name("music-volume").long("music-volume").value_name("PERCENT")
+          .validator(|x| Options::parse_volume(&x).map(|_| ()))
+          .help("Set the music volume from 0 to 100"))
+        .arg(clap::Arg::with_name("effects-volume").long("effects-volume").value_name("PERCENT")
+          .validator(|x| Options::parse_volume(&x).map(|_| ()))
+          .help("Set the sound effects volume from 0 to 100"))
+        .arg(clap::Arg::with_name("slow-motion").long("slow-motion")
+          .help("Run the game at half speed"))
+        .arg(clap::Arg::with_name("difficulty").long("difficulty").value_name("DIFFICULTY")
+          .validator(|x| Options::parse_difficulty(&x).map(|_| ()))
+          .help("Set the difficulty of games started with Return, 'easy' or 'hard'"))
+        .arg(clap::Arg::with_name("seed").long("seed").value_name("SEED")
+          .validator(|x| x.parse::<u64>().map(|_| ()).map_err(|_| format!(
+            "Invalid seed '{}', expected a non-negative integer", x)))
+          .help("Use SEED for all random numbers to make runs reproducible"))
+        .arg(clap::Arg::with_name("record").long("record").value_name("FILE")
+          .help("Record the inputs of each run to the replay file FILE"))
+        .arg(clap::Arg::with_name("replay").long("replay").value_name("FILE")
+          .help("Play back the run recorded in the replay file FILE"))
+        .arg(clap::Arg::with_name("headless").long("headless").requires("replay")
+          .help("Verify the replay given with --replay without window and sound"))
+        .arg(clap::Arg::with_name("leaderboard-url").long("leaderboard-url").value_name("URL")
+          .help("Submit highscores to and show global highscores of the server at URL"))
+        .arg(clap::Arg::with_name("assets-dir").long("assets-dir").value_name("DIR")
+          .help("Search assets in DIR before all other asset directories"))
+        .arg(clap::Arg::with_name("config").long("config").value_name("FILE")
+          .help("Read and write the config file FILE instead of the default one"))
+        .arg(clap::Arg::with_name("reset-config").long("reset-config")
+          .help("Reset all settings in the config file (keeps highscores, campaign progress and \
+            achievements)"))
+        .arg(clap::Arg::with_name("language").long("language").value_name("LANGUAGE")
+          .validator(|x| Language::from_code(&x).map(|_| ()).ok_or_else(|| format!(
+            "Invalid language '{}', expected 'en' or 'de'", x)))
+          .help("Set the language, 'en' or 'de' (default: from the locale)"))
+        .arg(clap::Arg::with_name("verbose").short("v").long("verbose")
+          .help("Increase verbosity"))
+        .arg(clap::Arg::with_name("license").short("L").long("license")
+          .help("Display license info"))
+        .subcommand(clap::SubCommand::with_name("validate-assets")
+          .about("Check that all assets exist and can be loaded"))
+        .subcommand(clap::SubCommand::with_name("print-highscores")
+          .about("Print the highscore table"))
+        .subcommand(clap::SubCommand::with_name("export-highscores")
+          .about("Export the highscore tables to a JSON or CSV file")
+          .arg(Options::highscore_file_format_arg())
+          .arg(clap::Arg::with_name("FILE").required(true).help("Highscore file to write")))
+        .subcommand(clap::SubCommand::with_name("import-highscores")
+          .about("Merge the highscore tables of JSON or CSV files into the highscore tables")
+          .arg(Options::highscore_file_format_arg())
+          .arg(clap::Arg::with_name("FILE").required(true).multiple(true)
+            .help("Highscore files to merge")))
+        .subcommand(clap::SubCommand::with_name("verify-highscores")
+          .about("Check the signatures of highscores and simulate their replays"))
+        .subcommand(clap::SubCommand::with_name("convert-level")
+          .about("Convert the level data files of the original game to a level file")
+          .arg(clap::Arg::with_name("TILE_MAP").required(true)
+            .help("Tile map file, e.g., levelTileMap.txt"))
+          .arg(clap::Arg::with_name("NPC_MAP").required(true)
+            .help("NPC map file, e.g., levelNpcMap.txt"))
+          .arg(clap::Arg::with_name("CHIMNEYS").required(true)
+            .help("Chimneys file, e.g., chimneys.txt"))
+          .arg(clap::Arg::with_name("FILE").required(true)
+            .help("Level file to write, e.g., assets/levels/level1.json")))
+        .subcommand(clap::SubCommand::with_name("simulate-replay")
+          .about("Verify a replay without window and sound and print its result")
+          .arg(clap::Arg::with_name("FILE").required(true).help("Replay file to simulate")));
+  }
+
+  fn highscore_file_format_arg() -> clap::Arg<'static, 'static> {
+    return clap::Arg::with_name("format").long("format").value_name("FORMAT")
+        .validator(|x| HighscoreFileFormat::from_name(&x).map(|_| ()).ok_or_else(|| format!(
+          "Invalid format '{}', expected 'json' or 'csv'", x)))
+        .help("Use the file format FORMAT, 'json' or 'csv' (default: from the file extension)");
+  }
+
+  fn build_assets_search_path(assets_dir: Option<std::path::PathBuf>)
+        -> Vec<(std::path::PathBuf, &'static str)> {
+    let mut search_path = Vec::new();
+
+    if let Some(assets_dir) = assets_dir { search_path.push((assets_dir, "--assets-dir")); }
+
+    if let Some(assets_dir) = std::env::var_os("SANTA_RACER_ASSETS_DIR") {
+      search_path.push((std::path::PathBuf::from(assets_dir), "SANTA_RACER_ASSETS_DIR"));
+    }
+
+    if let Some(exe_dir) = std::env::current_exe().ok().as_ref().and_then(|x| x.parent()) {
+      search_path.push((exe_dir.join("assets"), "next to executable"));
+    }
+
+    search_path.push((std::path::PathBuf::from("./assets"), "working directory"));
+
+    let data_home = std::env::var_os("XDG_DATA_HOME").filter(|x| !x.is_empty())
+        .map(std::path::PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(
+          |x| std::path::Path::new(&x).join(".local/share")));
+    if let Some(data_home) = data_home {
+      search_path.push((data_home.join("santa-racer/assets"), "XDG_DATA_HOME"));
+    }
+
+    let data_dirs = std::env::var("XDG_DATA_DIRS").ok().filter(|x| !x.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    for data_dir in data_dirs.split(':').filter(|x| !x.is_empty()) {
+      search_path.push((std::path::Path::new(data_dir).join("santa-racer/assets"),
+          "XDG_DATA_DIRS"));
+    }
+
+    let mut deduplicated_search_path: Vec<(std::path::PathBuf, &'static str)> = Vec::new();
+
+    for (dir_path, source) in search_path {
+      if !deduplicated_search_path.iter().any(|x| x.0 == dir_path) {
+        deduplicated_search_path.push((dir_path, source));
+      }
+    }
+
+    return deduplicated_search_path;
+  }
+
+  pub fn print_assets_search_path(&self) {
+    println!("Asset search path:");
+
+    for (dir_path, source) in &self.assets_search_path {
+      println!("  {} ({}{})", dir_path.display(), source,
+          if dir_path.is_dir() { "" } else { ", not found" });
+    }
+  }
+
+  fn parse_window_size(window_size: &str) -> Result<(u32, u32), String> {
+    let error = || format!("Invalid window size '{}', expected WIDTHxHEIGHT", window_size);
+    let mut parts = window_size.split('x');
+
+    let width: u32 = parts.next().and_then(|x| x.parse().ok()).ok_or_else(error)?;
+    let height: u32 = parts.next().and_then(|x| x.parse().ok()).ok_or_else(error)?;
+    if parts.next().is_some() || (width == 0) || (height == 0) { return Err(error()); }
+
+    return Ok((width, height));
+  }
+
+  fn parse_volume(volume: &str) -> Result<f64, String> {
+    return match volume.parse::<u32>() {
+      Ok(volume) if volume <= 100 => Ok((volume as f64) / 100.0),
+      _ => Err(format!("Invalid volume '{}', expected an integer from 0 to 100", volume)),
+    };
+  }
+
+  fn parse_difficulty(difficulty: &str) -> Result<GameDifficulty, String> {
+    return match difficulty.to_lowercase().as_str() {
+      "easy" => Ok(GameDifficulty::Easy),
+      "hard" => Ok(GameDifficulty::Hard),
+      _ => Err(format!("Invalid difficulty '{}', expected 'easy' or 'hard'", difficulty)),
+    };
+  }
+
+  // creates the tables of the default level and fills up tables with placeholders up to the
+  // number of highscores, placeholders are stored without name (older versions stored the
+  // localized name shown for them)
+  fn fill_highscore_tables(&mut self) {
+    for difficulty in GameDifficulty::ALL.iter() {
+      self.highscore_tables.entry(highscore::table_key(level::DEFAULT_LEVEL_NAME, *difficulty))
+          .or_insert_with(Vec::new);
+    }
+
+    for highscores in self.highscore_tables.values_mut() {
+      for _ in highscores.len() .. self.number_of_highscores {
+        highscores.push(Highscore::new("", 0));
+      }
+    }
+
+    for highscore in self.highscore_tables.values_mut().flatten() {
+      if highscore.is_placeholder() { highscore.set_name(""); }
+    }
+  }
+
+  pub fn save(&mut self) {
+    self.config_file.highscore_tables = self.highscore_tables.clone();
+
+    match &self.config_file_path {
+      Some(config_file_path) => confy::store_path(config_file_path, self.config_file.clone()),
+      None => confy::store("santa-racer", self.config_file.clone()),
+    }.expect("Could not save options");
   }
 
   fn print_description() {
-    println!("Santa Racer - an open-source clone of \"Nikolaus Express 2000\".");
-    println!("Source code: Copyright (C) 2020 Julian Valentin, licensed under MPL 2.0.");
-    println!("Exception: External assets such as music, sounds, and textures may be subject to \
-        be intellectual property of third parties.");
+    println!("{}", locale::Message::Description.text());
+    println!("{}", locale::Message::DescriptionSourceCode.text());
+    println!("{}", locale::Message::DescriptionAssets.text());
+  }
+
+  pub fn command(&self) -> Command {
+    return self.command;
+  }
+
+  pub fn language(&self) -> Language {
+    return self.language;
+  }
+
+  pub fn set_language(&mut self, language: Language) {
+    self.language = language;
+    self.config_file.language = Some(language);
+    locale::set_language(language);
   }
 
   pub fn fullscreen_enabled(&self) -> bool {
-    return self.fullscreen_enabled;
+    return self.display.fullscreen;
   }
 
   pub fn set_fullscreen_enabled(&mut self, fullscreen_enabled: bool) {
-    self.fullscreen_enabled = fullscreen_enabled;
+    self.display.fullscreen = fullscreen_enabled;
+    self.config_file.display.fullscreen = fullscreen_enabled;
+  }
+
+  pub fn window_size(&self) -> (u32, u32) {
+    return self.display.window_size;
   }
 
   pub fn sound_enabled(&self) -> bool {
-    return self.sound_enabled;
+    return self.audio.sound;
+  }
+
+  pub fn muted(&self) -> bool {
+    return self.audio.muted;
+  }
+
+  pub fn set_muted(&mut self, muted: bool) {
+    self.audio.muted = muted;
+    self.config_file.audio.muted = muted;
+  }
+
+  pub fn master_volume(&self) -> f64 {
+    return self.audio.master_volume;
+  }
+
+  pub fn set_master_volume(&mut self, master_volume: f64) {
+    self.audio.master_volume = master_volume.max(0.0).min(1.0);
+    self.config_file.audio.master_volume = self.audio.master_volume;
+  }
+
+  pub fn music_volume(&self) -> f64 {
+    return self.audio.music_volume;
+  }
+
+  pub fn set_music_volume(&mut self, music_volume: f64) {
+    self.audio.music_volume = music_volume.max(0.0).min(1.0);
+    self.config_file.audio.music_volume = self.audio.music_volume;
+  }
+
+  pub fn effects_volume(&self) -> f64 {
+    return self.audio.effects_volume;
+  }
+
+  pub fn set_effects_volume(&mut self, effects_volume: f64) {
+    self.audio.effects_volume = effects_volume.max(0.0).min(1.0);
+    self.config_file.audio.effects_volume = self.audio.effects_volume;
+  }
+
+  pub fn controls(&self) -> &ControlsConfig {
+    return &self.controls;
+  }
+
+  pub fn set_controls(&mut self, controls: ControlsConfig) {
+    self.controls = controls.clone();
+    self.config_file.controls = controls;
+  }
+
+  pub fn difficulty(&self) -> GameDifficulty {
+    return self.gameplay.difficulty;
+  }
+
+  pub fn set_difficulty(&mut self, difficulty: GameDifficulty) {
+    self.gameplay.difficulty = difficulty;
+    self.config_file.gameplay.difficulty = difficulty;
+  }
+
+  pub fn slow_motion_enabled(&self) -> bool {
+    return self.gameplay.slow_motion;
+  }
+
+  pub fn assets_dirs(&self) -> Vec<std::path::PathBuf> {
+    return self.assets_search_path.iter().map(|x| x.0.clone()).collect();
+  }
+
+  pub fn headless_enabled(&self) -> bool {
+    return self.headless_enabled;
   }
 
   pub fn verbose_enabled(&self) -> bool {
     return self.verbose_enabled;
   }
 
+  pub fn seed(&self) -> Option<u64> {
+    return self.seed;
+  }
+
+  pub fn record_file_path(&self) -> Option<&std::path::Path> {
+    return self.record_file_path.as_deref();
+  }
+
+  pub fn replay_file_path(&self) -> Option<&std::path::Path> {
+    return self.replay_file_path.as_deref();
+  }
+
+  pub fn highscore_file_paths(&self) -> &Vec<std::path::PathBuf> {
+    return &self.highscore_file_paths;
+  }
+
+  pub fn level_conversion_file_paths(&self) -> &Vec<std::path::PathBuf> {
+    return &self.level_conversion_file_paths;
+  }
+
+  pub fn highscore_file_format(&self, file_path: &std::path::Path) -> HighscoreFileFormat {
+    return self.highscore_file_format.unwrap_or_else(|| HighscoreFileFormat::from_path(file_path));
+  }
+
   pub fn number_of_highscores(&self) -> usize {
     return self.number_of_highscores;
   }
 
-  pub fn highscores(&self) -> &Vec<Highscore> {
-    return &self.highscores;
+  // tables of levels without highscores yet only consist of placeholders
+  pub fn highscores(&self, table_key: &str) -> Vec<Highscore> {
+    return self.highscore_tables.get(table_key).cloned()
+        .unwrap_or_else(|| vec![Highscore::new("", 0); self.number_of_highscores]);
+  }
+
+  pub fn highscores_mut(&mut self, table_key: &str) -> &mut Vec<Highscore> {
+    let number_of_highscores = self.number_of_highscores;
+    return self.highscore_tables.entry(table_key.to_string())
+        .or_insert_with(|| vec![Highscore::new("", 0); number_of_highscores]);
+  }
+
+  // directory of the config file, which also contains highscore replays and the signing key
+  fn data_dir(&self) -> std::path::PathBuf {
+    return match &self.config_file_path {
+      Some(config_file_path) => config_file_path.parent().map(std::path::Path::to_path_buf)
+          .unwrap_or_default(),
+      None => directories::ProjectDirs::from("rs", "", "santa-racer")
+          .map(|x| x.config_dir().to_path_buf()).unwrap_or_default(),
+    };
+  }
+
+  fn highscore_replays_dir(&self) -> std::path::PathBuf {
+    return self.data_dir().join("highscore-replays");
+  }
+
+  // random key created on first use, which makes editing entries without the key detectable
+  pub fn highscore_key(&self) -> Result<Vec<u8>, String> {
+    let file_path = self.data_dir().join("highscore.key");
+
+    if file_path.is_file() {
+      return std::fs::read(&file_path).map_err(|error| format!(
+          "Could not read highscore key '{}': {}", file_path.display(), error));
+    }
+
+    let key: String = (0 .. 32).map(|_| format!("{:02x}", rand::random::<u8>())).collect();
+    std::fs::create_dir_all(self.data_dir()).and_then(|_| std::fs::write(&file_path, &key))
+        .map_err(|error| format!("Could not write highscore key '{}': {}",
+          file_path.display(), error))?;
+    return Ok(key.into_bytes());
+  }
+
+  pub fn load_highscore_replay(&self, highscore: &Highscore) -> Result<replay::Replay, String> {
+    return replay::Replay::load(&self.highscore_replays_dir().join(highscore.replay_file()));
+  }
+
+  // saves the replay of a new highscore and signs the entry including its name
+  pub fn attach_highscore_replay(&mut self, table_key: &str, index: usize,
+        replay: &replay::Replay) -> Result<(), String> {
+    let key = self.highscore_key()?;
+    let replays_dir = self.highscore_replays_dir();
+    let highscore = &mut self.highscores_mut(table_key)[index];
+    let replay_file = format!("{}-{}-{}.json", table_key, highscore.timestamp(), replay.seed());
+
+    std::fs::create_dir_all(&replays_dir).map_err(|error| format!(
+        "Could not create directory '{}': {}", replays_dir.display(), error))?;
+    replay.save(&replays_dir.join(&replay_file))?;
+    highscore.set_replay_file(replay_file);
+    highscore.sign(&key, replay);
+
+    self.remove_unused_highscore_replays();
+    return Ok(());
+  }
+
+  // replays of entries that dropped out of the tables
+  fn remove_unused_highscore_replays(&self) {
+    let replay_files: Vec<String> = self.highscore_tables.values().flatten()
+        .map(|x| x.replay_file()).filter(|x| !x.is_empty()).collect();
+    let entries = match std::fs::read_dir(self.highscore_replays_dir()) {
+      Ok(entries) => entries,
+      Err(_) => return,
+    };
+
+    for entry in entries.filter_map(|x| x.ok()) {
+      let file_name = entry.file_name().to_string_lossy().to_string();
+
+      if file_name.ends_with(".json") && !replay_files.contains(&file_name) {
+        let _ = std::fs::remove_file(entry.path());
+      }
+    }
+  }
+
+  pub fn leaderboard_url(&self) -> Option<&str> {
+    return Some(self.leaderboard.url.as_str()).filter(|x| !x.is_empty());
+  }
+
+  // highscores that could not be submitted to the leaderboard server yet
+  pub fn leaderboard_queue_file_path(&self) -> std::path::PathBuf {
+    return self.data_dir().join("leaderboard-queue.json");
+  }
+
+  pub fn campaign_progress(&self) -> &CampaignProgress {
+    return &self.config_file.campaign;
+  }
+
+  pub fn complete_campaign_level(&mut self, level_name: &str) {
+    self.config_file.campaign.complete_level(level_name);
+  }
+
+  pub fn unlocked_achievements(&self) -> &Vec<Achievement> {
+    return &self.config_file.achievements;
+  }
+
+  pub fn unlock_achievement(&mut self, achievement: Achievement) {
+    if !self.config_file.achievements.contains(&achievement) {
+      self.config_file.achievements.push(achievement);
+    }
+  }
+
+  pub fn last_highscore_name(&self) -> String {
+    return self.config_file.last_highscore_name.to_string();
+  }
+
+  pub fn set_last_highscore_name<S: Into<String>>(&mut self, name: S) {
+    self.config_file.last_highscore_name = name.into();
+  }
+
+  pub fn highscore_tables(&self) -> &std::collections::BTreeMap<String, Vec<Highscore>> {
+    return &self.highscore_tables;
   }
 
-  pub fn highscores_mut(&mut self) -> &mut Vec<Highscore> {
-    return &mut self.highscores;
+  pub fn merge_highscore_tables(
+        &mut self, tables: &std::collections::BTreeMap<String, Vec<Highscore>>) {
+    for (key, highscores) in tables {
+      highscore_file::merge_highscores(self.highscore_tables.entry(key.to_string())
+          .or_insert_with(Vec::new), highscores, self.number_of_highscores);
+    }
+
+    self.fill_highscore_tables();
   }
 }
 
 impl ConfigFile {
-  fn new(highscores: Vec<Highscore>) -> ConfigFile {
+  fn new(highscore_tables: std::collections::BTreeMap<String, Vec<Highscore>>) -> ConfigFile {
     return ConfigFile {
-      highscores: highscores,
+      verbose: false,
+      language: None,
+      last_highscore_name: String::new(),
+      display: DisplayConfig::default(),
+      audio: AudioConfig::default(),
+      controls: ControlsConfig::default(),
+      gameplay: GameplayConfig::default(),
+      leaderboard: LeaderboardConfig::default(),
+      campaign: CampaignProgress::default(),
+      achievements: Vec::new(),
+      highscores: Vec::new(),
+      highscore_tables: highscore_tables,
     };
   }
 }
 
+impl ControlsConfig {
+  pub fn keys(&self, action: Action) -> &Vec<String> {
+    return match action {
+      Action::SteerLeft => &self.steer_left,
+      Action::SteerRight => &self.steer_right,
+      Action::SteerUp => &self.steer_up,
+      Action::SteerDown => &self.steer_down,
+      Action::DropGift => &self.drop_gift,
+      Action::Pause => &self.pause,
+      Action::Menu => &self.menu,
+      Action::Confirm => &self.confirm,
+      Action::Help1 => &self.help1,
+      Action::Help2 => &self.help2,
+      Action::Highscores => &self.highscores,
+      Action::Options => &self.options,
+      Action::StartEasy => &self.start_easy,
+      Action::StartHard => &self.start_hard,
+      Action::Mute => &self.mute,
+      Action::Levels => &self.levels,
+    };
+  }
+
+  fn keys_mut(&mut self, action: Action) -> &mut Vec<String> {
+    return match action {
+      Action::SteerLeft => &mut self.steer_left,
+      Action::SteerRight => &mut self.steer_right,
+      Action::SteerUp => &mut self.steer_up,
+      Action::SteerDown => &mut self.steer_down,
+      Action::DropGift => &mut self.drop_gift,
+      Action::Pause => &mut self.pause,
+      Action::Menu => &mut self.menu,
+      Action::Confirm => &mut self.confirm,
+      Action::Help1 => &mut self.help1,
+      Action::Help2 => &mut self.help2,
+      Action::Highscores => &mut self.highscores,
+      Action::Options => &mut self.options,
+      Action::StartEasy => &mut self.start_easy,
+      Action::StartHard => &mut self.start_hard,
+      Action::Mute => &mut self.mute,
+      Action::Levels => &mut self.levels,
+    };
+  }
+
+  // the key is removed from all other actions, which fails if an action would lose its last key
+  pub fn add_key<S: Into<String>>(&mut self, action: Action, key: S) -> bool {
+    let key = key.into();
+
+    for other_action in Action::ALL.iter() {
+      if (*other_action != action) && (self.keys(*other_action) == &vec![key.clone()]) {
+        return false;
+      }
+    }
+
+    for other_action in Action::ALL.iter() {
+      self.keys_mut(*other_action).retain(|x| *x != key);
+    }
+
+    self.keys_mut(action).push(key);
+    return true;
+  }
+
+  pub fn reset_keys(&mut self, action: Action) {
+    *self.keys_mut(action) = ControlsConfig::default().keys(action).clone();
+  }
+}
+
 impl std::default::Default for ConfigFile {
   fn default() -> ConfigFile {
-    return ConfigFile{
-      highscores: Vec::new(),
+    return ConfigFile::new(std::collections::BTreeMap::new());
+  }
+}
+
+impl std::default::Default for DisplayConfig {
+  fn default() -> DisplayConfig {
+    return DisplayConfig{
+      fullscreen: false,
+      window_size: (640, 480),
     };
   }
 }
 
-impl Highscore {
-  pub fn new<S: Into<String>>(name: S, points: i32) -> Highscore {
-    return Highscore{
-      name: name.into(),
-      points: points,
+impl std::default::Default for AudioConfig {
+  fn default() -> AudioConfig {
+    return AudioConfig{
+      sound: true,
+      muted: false,
+      master_volume: 1.0,
+      music_volume: 1.0,
+      effects_volume: 1.0,
     };
   }
+}
 
-  pub fn name(&self) -> String {
-    return self.name.to_string();
+impl std::default::Default for ControlsConfig {
+  fn default() -> ControlsConfig {
+    let keys = |x: &[&str]| x.iter().map(|y| y.to_string()).collect();
+
+    return ControlsConfig{
+      steer_left: keys(&["Left", "A"]),
+      steer_right: keys(&["Right", "D"]),
+      steer_up: keys(&["Up", "W"]),
+      steer_down: keys(&["Down", "S"]),
+      drop_gift: keys(&["Space"]),
+      pause: keys(&["P"]),
+      menu: keys(&["Escape"]),
+      confirm: keys(&["Return", "Keypad Enter"]),
+      help1: keys(&["F1", "1"]),
+      help2: keys(&["F2", "2"]),
+      highscores: keys(&["F3", "3"]),
+      options: keys(&["F4", "4"]),
+      start_easy: keys(&["F5", "5"]),
+      start_hard: keys(&["F6", "6"]),
+      mute: keys(&["M"]),
+      levels: keys(&["F7", "7"]),
+    };
   }
+}
 
-  pub fn set_name<S: Into<String>>(&mut self, name: S) {
-    self.name = name.into();
+impl std::default::Default for LeaderboardConfig {
+  fn default() -> LeaderboardConfig {
+    return LeaderboardConfig{
+      url: String::new(),
+    };
+  }
+}
+
+impl std::default::Default for GameplayConfig {
+  fn default() -> GameplayConfig {
+    return GameplayConfig{
+      difficulty: GameDifficulty::Easy,
+      slow_motion: false,
+    };
   }
+}
 
-  pub fn points(&self) -> i32 {
-    return self.points;
+// the local highscore tables
+impl LeaderboardBackend for Options {
+  fn submit(&mut self, table_key: &str, highscore: &Highscore)
+        -> Result<Option<usize>, String> {
+    let number_of_highscores = self.number_of_highscores;
+    return Ok(highscore::insert_highscore(self.highscores_mut(table_key), highscore.clone(),
+        number_of_highscores));
   }
 
-  pub fn set_points(&mut self, points: i32) {
-    self.points = points;
+  fn fetch(&mut self, table_key: &str) -> Result<Vec<Highscore>, String> {
+    return Ok(self.highscores(table_key));
   }
 }
@@ -17,10 +17,9 @@ pub struct SdlWrapper {
   pub event_pump: sdl2::EventPump,
 
   pub text_input_util: sdl2::keyboard::TextInputUtil,
+  pub controllers: controller::Controllers,
 }
 
-const WINDOW_WIDTH: u32 = 640;
-const WINDOW_HEIGHT: u32 = 480;
 const AUDIO_FREQUENCY: i32 = 44100;
 const AUDIO_NUMBER_OF_CHANNELS: i32 = 2;
 const AUDIO_CHUNK_SIZE: i32 = 256;
@@ -30,7 +29,8 @@ impl SdlWrapper {
     let sdl = sdl2::init().expect("Could not initialize SDL");
 
     let video_subsystem = sdl.video().expect("Could not initialize video subsystem");
-    let mut window_builder = video_subsystem.window("Santa Racer", WINDOW_WIDTH, WINDOW_HEIGHT);
+    let (window_width, window_height) = options.window_size();
+    let mut window_builder = video_subsystem.window("Santa Racer", window_width, window_height);
     window_builder.resizable().position_centered();
     if options.fullscreen_enabled() { window_builder.fullscreen(); }
     let window = window_builder.build().expect("Could not create window");
@@ -51,6 +51,7 @@ impl SdlWrapper {
 
     let event_pump = sdl.event_pump().expect("Could not create event pump");
     let text_input_util = video_subsystem.text_input();
+    let controllers = controller::Controllers::new(&sdl, options.verbose_enabled());
 
     return SdlWrapper{
       sdl: sdl,
@@ -62,6 +63,7 @@ impl SdlWrapper {
       event_pump: event_pump,
 
       text_input_util: text_input_util,
+      controllers: controllers,
     };
   }
 }
@@ -0,0 +1,122 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::*;
+
+pub struct SdlSong<'a> {
+  music: sdl2::mixer::Music<'a>,
+}
+
+pub struct SdlSound {
+  chunk: sdl2::mixer::Chunk,
+}
+
+// applies the volumes of the options to the mixer, the effects volume is the volume of all
+// channels, on top of which the volume of each sound effect is applied as distance
+pub struct SdlAudio {
+  music_volume: f64,
+  effects_volume: f64,
+}
+
+impl SdlAudio {
+  pub fn new(asset_library: &mut asset::AssetLibrary, options: &options::Options) -> SdlAudio {
+    asset_library.load_songs(|file_path| Box::new(SdlSong::new(file_path)),
+        options.verbose_enabled());
+    asset_library.load_sounds(|file_path| Box::new(SdlSound::new(file_path)),
+        options.verbose_enabled());
+
+    let mut audio = SdlAudio{music_volume: 1.0, effects_volume: 1.0};
+    audio.apply_volumes(options);
+    return audio;
+  }
+
+  // muting sets the volumes to zero, songs and sounds keep playing
+  pub fn apply_volumes(&mut self, options: &options::Options) {
+    let master_volume = if options.muted() { 0.0 } else { options.master_volume() };
+    self.music_volume = master_volume * options.music_volume();
+    self.effects_volume = master_volume * options.effects_volume();
+
+    sdl2::mixer::Music::set_volume(SdlAudio::mixer_volume(self.music_volume));
+    sdl2::mixer::Channel::all().set_volume(SdlAudio::mixer_volume(self.effects_volume));
+  }
+
+  pub fn music_volume(&self) -> f64 {
+    return self.music_volume;
+  }
+
+  pub fn effects_volume(&self) -> f64 {
+    return self.effects_volume;
+  }
+
+  fn mixer_volume(volume: f64) -> i32 {
+    return (volume * (sdl2::mixer::MAX_VOLUME as f64)).round() as i32;
+  }
+}
+
+impl<'a> SdlSong<'a> {
+  pub fn new(file_path: &std::path::Path) -> SdlSong<'a> {
+    let file_path_str = file_path.to_str().expect("Could not convert path to string");
+
+    return SdlSong{
+      music: sdl2::mixer::Music::from_file(file_path).expect(
+          format!("Could not load song from {}", file_path_str).as_str()),
+    };
+  }
+}
+
+impl<'a> asset::Song for SdlSong<'a> {
+  fn play(&self) {
+    self.music.play(-1).expect("Could not play song");
+  }
+
+  fn stop(&self) {
+    sdl2::mixer::Music::halt();
+  }
+
+  fn pause(&self) {
+    sdl2::mixer::Music::pause();
+  }
+
+  fn resume(&self) {
+    sdl2::mixer::Music::resume();
+  }
+}
+
+impl SdlSound {
+  pub fn new(file_path: &std::path::Path) -> SdlSound {
+    let file_path_str = file_path.to_str().expect("Could not convert path to string");
+
+    return SdlSound{
+      chunk: sdl2::mixer::Chunk::from_file(file_path).expect(
+          format!("Could not load sound from {}", file_path_str).as_str()),
+    };
+  }
+
+  fn get_free_channel() -> Option<sdl2::mixer::Channel> {
+    for i in 0 .. 256 {
+      let channel = sdl2::mixer::Channel(i);
+      if !channel.is_playing() { return Some(channel); }
+    }
+
+    return None;
+  }
+}
+
+impl asset::Sound for SdlSound {
+  fn play_with_volume_and_pan(&self, volume: f64, pan: f64) {
+    let left = (2.0 * (1.0 - pan) * 255.0).max(0.0).min(255.0) as u8;
+    let right = (2.0 * pan * 255.0).max(0.0).min(255.0) as u8;
+
+    let channel = SdlSound::get_free_channel().expect("Could not find free channel");
+    let distance = ((1.0 - volume) * 255.0).max(0.0).min(255.0) as u8;
+    channel.set_distance(distance).expect(
+        format!("Could not set distance to {}", distance).as_str());
+    channel.set_panning(left, right).expect(
+        format!("Could not set panning with left = {} and right = {}", left, right).as_str());
+    channel.play(&self.chunk, 0).expect("Could not play sound");
+  }
+}
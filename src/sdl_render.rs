@@ -0,0 +1,85 @@
+/* Copyright (C) 2020 Julian Valentin
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use sdl2::image::LoadTexture;
+
+use crate::*;
+use santa_racer_core::asset::Point;
+
+pub struct TextureLibrary<'a> {
+  map: std::collections::HashMap<String, sdl2::render::Texture<'a>>,
+}
+
+pub struct SdlRenderer<'c, 't, RenderTarget: sdl2::render::RenderTarget> {
+  canvas: &'c mut sdl2::render::Canvas<RenderTarget>,
+  texture_library: &'c mut TextureLibrary<'t>,
+  alpha: f64,
+}
+
+impl<'a> TextureLibrary<'a> {
+  pub fn new(texture_creator: &'a sdl2::render::TextureCreator<sdl2::video::WindowContext>,
+        asset_library: &asset::AssetLibrary) -> TextureLibrary<'a> {
+    let mut map = std::collections::HashMap::new();
+
+    for image in asset_library.images() {
+      if let Some(file_path) = image.file_path() {
+        let texture = texture_creator.load_texture(file_path).expect(
+            format!("Could not load texture from '{}'", file_path.display()).as_str());
+        map.insert(image.name().to_string(), texture);
+      }
+    }
+
+    return TextureLibrary{
+      map: map,
+    };
+  }
+
+  pub fn get_texture_mut(&mut self, name: &str) -> Option<&mut sdl2::render::Texture<'a>> {
+    return self.map.get_mut(name);
+  }
+}
+
+impl<'c, 't, RenderTarget: sdl2::render::RenderTarget> SdlRenderer<'c, 't, RenderTarget> {
+  pub fn new(canvas: &'c mut sdl2::render::Canvas<RenderTarget>,
+        texture_library: &'c mut TextureLibrary<'t>) -> SdlRenderer<'c, 't, RenderTarget> {
+    return SdlRenderer{
+      canvas: canvas,
+      texture_library: texture_library,
+      alpha: 1.0,
+    };
+  }
+}
+
+impl<'c, 't, RenderTarget: sdl2::render::RenderTarget> render::Renderer
+      for SdlRenderer<'c, 't, RenderTarget> {
+  fn draw_image(&mut self, image: &asset::Image, src_rect: asset::Rect, dst_point: Point) {
+    if let Some(texture) = self.texture_library.get_texture_mut(image.name()) {
+      let src_rect = sdl2::rect::Rect::new(src_rect.x(), src_rect.y(), src_rect.width(),
+          src_rect.height());
+      let dst_rect = sdl2::rect::Rect::new(dst_point.x() as i32, dst_point.y() as i32,
+          src_rect.width(), src_rect.height());
+      texture.set_alpha_mod((self.alpha * 255.0) as u8);
+      self.canvas.copy(texture, src_rect, dst_rect).expect("Could not copy texture");
+    }
+  }
+
+  fn fill_rect(&mut self, rect: asset::Rect, color: render::Color) {
+    let rect = sdl2::rect::Rect::new(rect.x(), rect.y(), rect.width(), rect.height());
+    self.canvas.set_blend_mode(sdl2::render::BlendMode::Blend);
+    self.canvas.set_draw_color(sdl2::pixels::Color::RGBA(color.r(), color.g(), color.b(),
+        ((color.a() as f64) * self.alpha) as u8));
+    self.canvas.fill_rect(rect).expect("Could not fill rect");
+  }
+
+  fn set_alpha(&mut self, alpha: f64) {
+    self.alpha = alpha.max(0.0).min(1.0);
+  }
+
+  fn alpha(&self) -> f64 {
+    return self.alpha;
+  }
+}
@@ -87,6 +87,12 @@ fileNames = {
 
 
 
+# glyphs missing in the font of the original game, appended to font.png in this order
+# (has to match the characters of Font in santa-racer-core/src/ui.rs)
+additionalFontCharacters = "!\"%&'()+,;=?"
+
+
+
 def convertFmapToPpm(fmapFilePath):
   with open(fmapFilePath, "rb") as f: fmap = f.read()
   i = fmap.index(b"BGFX")
@@ -176,6 +182,31 @@ def decodeRunLengthEncoding(bmpFilePath):
 
 
 
+def appendFontGlyphs(fontFilePath, tmpDirPath):
+  numberOfFrames = 45
+  width, height = (int(x) for x in subprocess.run(["identify", "-format", "%w %h", fontFilePath],
+      stdout=subprocess.PIPE).stdout.decode().split())
+  frameWidth = width // numberOfFrames
+  # average color of the existing glyphs, weighted by opacity
+  color = subprocess.run(["convert", fontFilePath, "-resize", "1x1!", "-alpha", "off",
+      "-format", "%[pixel:p{0,0}]", "info:"], stdout=subprocess.PIPE).stdout.decode()
+  glyphFilePaths = []
+
+  for i, character in enumerate(additionalFontCharacters):
+    glyphFilePath = os.path.join(tmpDirPath, f"fontGlyph{i:02}.png")
+    # "%" starts escape sequences in ImageMagick
+    text = character.replace("%", "%%")
+    subprocess.run(["convert", "-size", f"{frameWidth}x{height}", "xc:none",
+        "-family", "Palatino", "-weight", "Bold", "-pointsize", str(int(0.8 * height)),
+        "-fill", color, "-gravity", "Center", "-annotate", "+0+0", text,
+        f"png32:{glyphFilePath}"])
+    glyphFilePaths.append(glyphFilePath)
+
+  subprocess.run(["convert", fontFilePath] + glyphFilePaths + ["+append",
+      f"png32:{fontFilePath}"])
+
+
+
 def main():
   baseName = "NikolausExpress2000"
   rootDirPath = os.path.abspath(os.path.join(os.path.dirname(__file__), ".."))
@@ -248,6 +279,9 @@ def main():
           subprocess.run(["montage"] + levelPartFilePaths + ["-mode", "Concatenate", "-tile", "8x",
               "-background", "none", dstFilePath])
 
+        elif dstFileName == "font.png":
+          appendFontGlyphs(dstFilePath, tmpDirPath)
+
         elif dstFileName in ["bigStar.png", "drunkStar.png", "electrocutedReindeer.png",
               "electrocutedSleigh.png", "shield.png", "smallDrunkStar.png", "smallStar.png",
               "star.png"]:
@@ -0,0 +1,78 @@
+#!/usr/bin/python3
+
+# Copyright (C) 2020 Julian Valentin
+#
+# This Source Code Form is subject to the terms of the Mozilla Public
+# License, v. 2.0. If a copy of the MPL was not distributed with this
+# file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+# Minimal leaderboard server for testing --leaderboard-url, e.g.:
+#   tools/mockLeaderboardServer.py 8080
+#   santa-racer --leaderboard-url http://localhost:8080
+# Highscores are only kept in memory. Pass --offline to answer all requests with status 503,
+# which lets the game queue its submissions.
+
+import http.server
+import json
+import re
+import sys
+
+
+numberOfHighscores = 10
+tables = {}
+offline = False
+
+
+
+class LeaderboardRequestHandler(http.server.BaseHTTPRequestHandler):
+  def getTableKey(self):
+    # level name and difficulty, e.g., "level1-easy"
+    match = re.fullmatch(r"/scores/([^/]+-(?:easy|hard))", self.path)
+    return (match.group(1) if match is not None else None)
+
+  def sendJson(self, status, value):
+    body = json.dumps(value).encode()
+    self.send_response(status)
+    self.send_header("Content-Type", "application/json")
+    self.send_header("Content-Length", str(len(body)))
+    self.end_headers()
+    self.wfile.write(body)
+
+  def do_GET(self):
+    tableKey = self.getTableKey()
+    if offline: self.sendJson(503, {"error" : "offline"})
+    elif tableKey is None: self.sendJson(404, {"error" : "not found"})
+    else: self.sendJson(200, tables.get(tableKey, []))
+
+  def do_POST(self):
+    tableKey = self.getTableKey()
+    if offline: return self.sendJson(503, {"error" : "offline"})
+    if tableKey is None: return self.sendJson(404, {"error" : "not found"})
+
+    try:
+      highscore = json.loads(self.rfile.read(int(self.headers["Content-Length"])))
+      assert isinstance(highscore["name"], str) and isinstance(highscore["points"], int)
+    except (AssertionError, KeyError, TypeError, ValueError):
+      return self.sendJson(400, {"error" : "invalid highscore"})
+
+    table = tables.setdefault(tableKey, [])
+    table.append(highscore)
+    table.sort(key=lambda x: -x["points"])
+    del table[numberOfHighscores:]
+    self.sendJson(201, highscore)
+
+
+
+def main():
+  global offline
+  arguments = [x for x in sys.argv[1:] if x != "--offline"]
+  offline = (len(arguments) < len(sys.argv) - 1)
+  port = (int(arguments[0]) if len(arguments) > 0 else 8080)
+
+  print(f"Serving mock leaderboard on http://localhost:{port}{' (offline)' if offline else ''}...")
+  http.server.HTTPServer(("localhost", port), LeaderboardRequestHandler).serve_forever()
+
+
+
+if __name__ == "__main__":
+  main()